@@ -0,0 +1,142 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use crate::error::OssError;
+
+/// OSS访问日志(日志转存)中的一行，值为`-`的字段为None
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessLogEntry {
+    pub remote_ip: String,
+    pub time: DateTime<FixedOffset>,
+    /// 请求行，例如`GET /example.jpg HTTP/1.1`
+    pub request_uri: String,
+    pub status: u16,
+    /// 响应的字节数
+    pub sent_bytes: Option<u64>,
+    /// 请求总耗时(毫秒)
+    pub request_time: Option<u64>,
+    pub referer: Option<String>,
+    pub user_agent: Option<String>,
+    pub host: String,
+    pub request_id: String,
+    /// 操作名称，例如`GetObject`
+    pub operation: String,
+    pub bucket: Option<String>,
+    /// 对象key(已URL解码)
+    pub key: Option<String>,
+    pub object_size: Option<u64>,
+    /// OSS服务端处理耗时(毫秒)
+    pub server_cost_time: Option<u64>,
+    pub error_code: Option<String>,
+}
+
+/// 按空格拆分日志行，`"..."`和`[...]`作为一个字段(去掉引号和方括号)
+fn split_fields(line: &str) -> Result<Vec<&str>, OssError> {
+    let mut fields = Vec::new();
+    let mut rest = line.trim();
+    while !rest.is_empty() {
+        let (field, next) = match rest.as_bytes()[0] {
+            b'"' => {
+                let end = rest[1..]
+                    .find('"')
+                    .ok_or_else(|| OssError::Err(format!("unterminated quote in access log: {}", line)))?;
+                (&rest[1..end + 1], &rest[end + 2..])
+            }
+            b'[' => {
+                let end = rest
+                    .find(']')
+                    .ok_or_else(|| OssError::Err(format!("unterminated bracket in access log: {}", line)))?;
+                (&rest[1..end], &rest[end + 1..])
+            }
+            _ => {
+                let end = rest.find(' ').unwrap_or(rest.len());
+                (&rest[..end], &rest[end..])
+            }
+        };
+        fields.push(field);
+        rest = next.trim_start();
+    }
+    Ok(fields)
+}
+
+/// 解析OSS访问日志的一行，只解析前20个文档中固定的字段，之后新增的字段会被忽略
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::access_log::parse_access_log_line;
+/// let line = r#"192.168.0.1 - - [03/Jan/2021:14:59:49 +0800] "GET /example.jpg HTTP/1.1" 200 368 9 "-" "curl/7.65.3" "examplebucket.oss-cn-hangzhou.aliyuncs.com" "5FF16B65F05BC932307A3C3C" "true" "16125****" "GetObject" "examplebucket" "example.jpg" 368 7 "-" 40 "16125****" "-""#;
+/// let entry = parse_access_log_line(line).unwrap();
+/// assert_eq!(entry.operation, "GetObject");
+/// assert_eq!(entry.status, 200);
+/// ```
+pub fn parse_access_log_line(line: &str) -> Result<AccessLogEntry, OssError> {
+    let fields = split_fields(line)?;
+    if fields.len() < 20 {
+        return Err(OssError::Err(format!(
+            "access log line has {} fields, expected at least 20: {}",
+            fields.len(),
+            line
+        )));
+    }
+    let optional = |index: usize| Some(fields[index]).filter(|v| *v != "-").map(|v| v.to_string());
+    let number = |index: usize| fields[index].parse::<u64>().ok();
+    Ok(AccessLogEntry {
+        remote_ip: fields[0].to_string(),
+        time: DateTime::parse_from_str(fields[3], "%d/%b/%Y:%H:%M:%S %z")
+            .map_err(|e| OssError::Err(format!("invalid access log time {}: {}", fields[3], e)))?,
+        request_uri: fields[4].to_string(),
+        status: fields[5]
+            .parse()
+            .map_err(|_| OssError::Err(format!("invalid access log status: {}", fields[5])))?,
+        sent_bytes: number(6),
+        request_time: number(7),
+        referer: optional(8),
+        user_agent: optional(9),
+        host: fields[10].to_string(),
+        request_id: fields[11].to_string(),
+        operation: fields[14].to_string(),
+        bucket: optional(15),
+        key: optional(16).map(|key| urlencoding::decode(&key).map(|k| k.into_owned()).unwrap_or(key)),
+        object_size: number(17),
+        server_cost_time: number(18),
+        error_code: optional(19),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::access_log::{parse_access_log_line, AccessLogEntry};
+
+    #[test]
+    fn test_parse_access_log_line() {
+        let line = r#"192.168.0.1 - - [03/Jan/2021:14:59:49 +0800] "GET /dir/%E4%B8%AD.jpg?x-oss-process=image/resize HTTP/1.1" 404 290 12 "http://www.aliyun.com/product/oss" "Mozilla/5.0 (Windows NT 10.0)" "examplebucket.oss-cn-hangzhou.aliyuncs.com" "5FF16B65F05BC932307A3C3C" "true" "-" "GetObject" "examplebucket" "dir%2F%E4%B8%AD.jpg" - 3 "NoSuchKey" 40 "-" "-" "-" "-""#;
+        let entry = parse_access_log_line(line).unwrap();
+        assert_eq!(entry.remote_ip, "192.168.0.1");
+        assert_eq!(entry.time.to_rfc3339(), "2021-01-03T14:59:49+08:00");
+        assert_eq!(entry.request_uri, "GET /dir/%E4%B8%AD.jpg?x-oss-process=image/resize HTTP/1.1");
+        assert_eq!(entry.status, 404);
+        assert_eq!(entry.sent_bytes, Some(290));
+        assert_eq!(entry.request_time, Some(12));
+        assert_eq!(entry.user_agent.as_deref(), Some("Mozilla/5.0 (Windows NT 10.0)"));
+        assert_eq!(entry.request_id, "5FF16B65F05BC932307A3C3C");
+        assert_eq!(entry.operation, "GetObject");
+        assert_eq!(entry.key.as_deref(), Some("dir/中.jpg"));
+        assert_eq!(entry.object_size, None);
+        assert_eq!(entry.server_cost_time, Some(3));
+        assert_eq!(entry.error_code.as_deref(), Some("NoSuchKey"));
+        assert!(parse_access_log_line("192.168.0.1 - -").is_err());
+        assert!(parse_access_log_line(r#"192.168.0.1 - - [03/Jan/2021 "GET"#).is_err());
+    }
+
+    #[test]
+    fn test_access_log_entry_serde_round_trip() {
+        let line = r#"192.168.0.1 - - [03/Jan/2021:14:59:49 +0800] "GET /example.jpg HTTP/1.1" 200 368 9 "-" "curl/7.65.3" "examplebucket.oss-cn-hangzhou.aliyuncs.com" "5FF16B65F05BC932307A3C3C" "true" "-" "GetObject" "examplebucket" "example.jpg" 368 7 "-" 40 "-" "-""#;
+        let entry = parse_access_log_line(line).unwrap();
+        let value = serde_json::to_value(&entry).unwrap();
+        assert_eq!(value["time"], "2021-01-03T14:59:49+08:00");
+        assert_eq!(value["request_id"], "5FF16B65F05BC932307A3C3C");
+        assert_eq!(value["referer"], serde_json::Value::Null);
+        let decoded: AccessLogEntry = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, entry);
+    }
+}
@@ -0,0 +1,975 @@
+use std::time::{Duration, Instant};
+use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+use crate::bucket_config::{
+    CorsConfiguration, LifecycleConfiguration, LoggingConfiguration, RefererConfiguration, WebsiteConfiguration,
+};
+use crate::debug;
+use crate::error::OssError;
+use crate::list::{decode_list_body, ListObjectsResult, ObjectSummary};
+use crate::fetch::{task_id_from_xml, AsyncFetchSpec, AsyncFetchTaskInfo};
+use crate::oss::OSS;
+use crate::request::{RequestBuilder, RequestType};
+use crate::stat::BucketStat;
+use crate::worm::WormConfiguration;
+use crate::xml::{element_text, XmlWriter};
+
+impl OSS {
+    /// 开启或关闭bucket的传输加速
+    ///
+    /// 开启后需要把endpoint配置为`oss-accelerate.aliyuncs.com`才能走加速域名访问，开启后约30分钟生效
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let builder = RequestBuilder::new();
+    /// oss.put_bucket_transfer_acceleration(true, builder).await.unwrap();
+    /// ```
+    pub async fn put_bucket_transfer_acceleration(
+        &self,
+        enabled: bool,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("transferAcceleration", "");
+        build.method = RequestType::Put;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!(
+            "oss log: put bucket transfer acceleration: {} headers: {:?}",
+            url, headers
+        );
+        let mut xml = XmlWriter::new();
+        xml.start("TransferAccelerationConfiguration")
+            .element("Enabled", enabled.to_string());
+        let body = xml.finish();
+        let response = client.put(url).headers(headers).body(body).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!(
+                "oss log: put bucket transfer acceleration status: {} error: {}",
+                status, result
+            );
+            Err(OssError::from_response("put bucket transfer acceleration", status, result))
+        }
+    }
+
+    /// 获取bucket是否开启了传输加速
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let builder = RequestBuilder::new();
+    /// let enabled = oss.get_bucket_transfer_acceleration(builder).await.unwrap();
+    /// println!("transfer acceleration: {}", enabled);
+    /// ```
+    pub async fn get_bucket_transfer_acceleration(
+        &self,
+        build: RequestBuilder,
+    ) -> Result<bool, OssError> {
+        let build = build.parameters_put("transferAcceleration", "");
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!(
+            "oss log: get bucket transfer acceleration: {} headers: {:?}",
+            url, headers
+        );
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            let enabled = element_text(&result, "Enabled")
+                .ok_or_else(|| OssError::Err(format!("invalid transfer acceleration xml: {}", result)))?;
+            Ok(enabled == "true")
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!(
+                "oss log: get bucket transfer acceleration status: {} error: {}",
+                status, result
+            );
+            Err(OssError::from_response("get bucket transfer acceleration", status, result))
+        }
+    }
+
+    /// 创建异步拉取任务，OSS会从指定url拉取文件保存到bucket中，返回TaskId
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::fetch::AsyncFetchSpec;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let spec = AsyncFetchSpec::new("https://cdn.example.com/a.png", "/images/a.png");
+    /// let task_id = oss.create_async_fetch_task(spec, RequestBuilder::new()).await.unwrap();
+    /// println!("task id: {}", task_id);
+    /// ```
+    pub async fn create_async_fetch_task(
+        &self,
+        spec: AsyncFetchSpec,
+        build: RequestBuilder,
+    ) -> Result<String, OssError> {
+        let mut build = build.parameters_put("asyncFetch", "");
+        build.method = RequestType::Post;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: create async fetch task: {} headers: {:?}", url, headers);
+        let response = client.post(url).headers(headers).body(spec.to_xml()).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            task_id_from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: create async fetch task status: {} error: {}", status, result);
+            Err(OssError::from_response("create async fetch task", status, result))
+        }
+    }
+
+    /// 查询异步拉取任务的状态
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let info = oss.get_async_fetch_task("task id", RequestBuilder::new()).await.unwrap();
+    /// println!("state: {}", info.state);
+    /// ```
+    pub async fn get_async_fetch_task<S: AsRef<str>>(
+        &self,
+        task_id: S,
+        build: RequestBuilder,
+    ) -> Result<AsyncFetchTaskInfo, OssError> {
+        let build = build
+            .parameters_put("asyncFetch", "")
+            .oss_header_put("x-oss-task-id", task_id.as_ref());
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get async fetch task: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            AsyncFetchTaskInfo::from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: get async fetch task status: {} error: {}", status, result);
+            Err(OssError::from_response("get async fetch task", status, result))
+        }
+    }
+
+    /// 创建异步拉取任务并每隔`interval`查询一次，直到任务成功或失败，超过`timeout`返回错误
+    ///
+    /// 任务失败时返回的[AsyncFetchTaskInfo]中包含失败原因
+    /// # 使用例子
+    /// ```rust
+    /// use std::time::Duration;
+    /// use aliyun_oss_rust_sdk::fetch::AsyncFetchSpec;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let spec = AsyncFetchSpec::new("https://cdn.example.com/a.png", "/images/a.png");
+    /// let info = oss
+    ///     .fetch_and_wait(spec, RequestBuilder::new(), Duration::from_secs(2), Duration::from_secs(300))
+    ///     .await
+    ///     .unwrap();
+    /// println!("state: {} error: {:?}", info.state, info.error_msg);
+    /// ```
+    pub async fn fetch_and_wait(
+        &self,
+        spec: AsyncFetchSpec,
+        build: RequestBuilder,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<AsyncFetchTaskInfo, OssError> {
+        let start = Instant::now();
+        let task_id = self.create_async_fetch_task(spec, build.clone()).await?;
+        loop {
+            let info = self.get_async_fetch_task(&task_id, build.clone()).await?;
+            if info.state.is_terminal() {
+                return Ok(info);
+            }
+            if start.elapsed() + interval > timeout {
+                return Err(OssError::Err(format!(
+                    "async fetch task {} timeout, state: {}",
+                    task_id, info.state
+                )));
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// 检查AccessKey、endpoint和bucket配置是否可用，适合在应用启动时调用
+    ///
+    /// 失败时返回[OssError::CheckFailed]说明具体原因，endpoint地域不对时返回[OssError::RegionMismatch]
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// oss.check(&RequestBuilder::new()).await.unwrap();
+    /// ```
+    pub async fn check(&self, build: &RequestBuilder) -> Result<(), OssError> {
+        let build = build.clone().parameters_put("bucketInfo", "");
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: check: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: check status: {} error: {}", status, result);
+            Err(OssError::check_failed(status, result))
+        }
+    }
+
+    /// 列举bucket中的对象(ListObjectsV2)，每次返回一页
+    ///
+    /// 通过[RequestBuilder::with_prefix]、[RequestBuilder::with_max_keys]、[RequestBuilder::with_start_after]、
+    /// [RequestBuilder::with_continuation_token]等控制列举范围
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let build = RequestBuilder::new()
+    ///     .with_prefix("logs/")
+    ///     .with_start_after("logs/2024-01-01.log")
+    ///     .with_max_keys(100);
+    /// let result = oss.list_objects(build).await.unwrap();
+    /// for object in result.objects {
+    ///     println!("{} {}", object.key, object.size);
+    /// }
+    /// ```
+    pub async fn list_objects(&self, build: RequestBuilder) -> Result<ListObjectsResult, OssError> {
+        self.list_objects_page(build, true).await
+    }
+
+    /// 列举一页对象，`use_cache`为false时不读也不写列举缓存，用于[OSS::delete_by_prefix]这类会改变列举结果的操作
+    pub(crate) async fn list_objects_page(&self, build: RequestBuilder, use_cache: bool) -> Result<ListObjectsResult, OssError> {
+        let build = self.scope_list_query(build.query_put("list-type", "2"));
+        let raw_response = build.raw_response;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        let list_cache = self.list_cache.as_ref().filter(|_| use_cache);
+        // 需要原始响应时缓存中没有body，直接请求
+        let cached = if raw_response { None } else { list_cache.and_then(|cache| cache.get(&url)) };
+        if let Some(result) = cached {
+            debug!("oss log: list objects from cache: {}", url);
+            return Ok(result.unscope(|key| self.unscope_key(key)));
+        }
+        let cache_key = url.clone();
+        self.acquire_rate_limit().await;
+        debug!("oss log: list objects: {} headers: {:?}", url, headers);
+        let response = client
+            .get(url)
+            .headers(headers)
+            .header(ACCEPT_ENCODING, "gzip")
+            .send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let content_encoding = response
+                .headers()
+                .get(CONTENT_ENCODING)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string());
+            let body = response.bytes().await?;
+            let result = decode_list_body(content_encoding.as_deref(), &body)?;
+            let mut list = ListObjectsResult::from_xml(&result)?;
+            // 缓存由不同作用域的clone共享，缓存原始结果，取出后再按当前作用域去掉前缀
+            if let Some(cache) = list_cache {
+                cache.insert(cache_key, list.clone());
+            }
+            if raw_response {
+                list.raw = Some(result);
+            }
+            Ok(list.unscope(|key| self.unscope_key(key)))
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: list objects status: {} error: {}", status, result);
+            Err(OssError::from_response("list objects", status, result))
+        }
+    }
+
+    /// 填充列举结果中对象的标签数量([ObjectSummary::tagging_count])
+    ///
+    /// ListObjectsV2不返回标签数量，这里对每个对象发起一次HEAD请求([OSS::head_objects])读取`x-oss-tagging-count`，
+    /// 最多同时发起`concurrency`个请求，没有标签的对象填充为`Some(0)`。
+    /// `concurrency`为0时按1处理。单个对象失败不会中断其它对象，失败的对象保持None，返回第一个错误
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let mut result = oss.list_objects(RequestBuilder::new().with_prefix("images/")).await.unwrap();
+    /// oss.fill_tagging_count(&mut result.objects, 8, RequestBuilder::new()).await.unwrap();
+    /// for object in result.objects {
+    ///     println!("{} tags: {:?}", object.key, object.tagging_count);
+    /// }
+    /// ```
+    pub async fn fill_tagging_count(
+        &self,
+        objects: &mut [ObjectSummary],
+        concurrency: usize,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let keys = objects.iter().map(|object| object.key.to_string()).collect::<Vec<_>>();
+        let results = self.head_objects(&keys, concurrency.max(1), build).await;
+        let mut first_error = None;
+        for (object, (_, result)) in objects.iter_mut().zip(results) {
+            match result {
+                Ok(metadata) => object.tagging_count = Some(metadata.tagging_count().unwrap_or(0)),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// 获取bucket的存储统计信息，可配合[crate::stat::estimate_monthly_cost]估算存储费用
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let stat = oss.get_bucket_stat(RequestBuilder::new()).await.unwrap();
+    /// println!("objects: {} storage: {}", stat.object_count, stat.storage);
+    /// ```
+    pub async fn get_bucket_stat(&self, build: RequestBuilder) -> Result<BucketStat, OssError> {
+        let build = build.parameters_put("stat", "");
+        let raw_response = build.raw_response;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket stat: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            let mut stat = BucketStat::from_xml(&result)?;
+            if raw_response {
+                stat.raw = Some(result);
+            }
+            Ok(stat)
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: get bucket stat status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket stat", status, result))
+        }
+    }
+
+    /// 获取bucket的合规保留策略(WORM)，只支持读取，创建、锁定和延长保留策略暂不支持
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let worm = oss.get_bucket_worm(RequestBuilder::new()).await.unwrap();
+    /// println!("state: {} days: {}", worm.state, worm.retention_period_in_days);
+    /// ```
+    pub async fn get_bucket_worm(&self, build: RequestBuilder) -> Result<WormConfiguration, OssError> {
+        let build = build.parameters_put("worm", "");
+        let raw_response = build.raw_response;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket worm: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            let mut worm = WormConfiguration::from_xml(&result)?;
+            if raw_response {
+                worm.raw = Some(result);
+            }
+            Ok(worm)
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: get bucket worm status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket worm", status, result))
+        }
+    }
+
+    /// 设置bucket的静态网站托管，访问目录时返回默认主页，对象不存在时返回404页
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::bucket_config::WebsiteConfiguration;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let website = WebsiteConfiguration::new("index.html").with_error_document("error.html");
+    /// oss.put_bucket_website(&website, RequestBuilder::new()).await.unwrap();
+    /// ```
+    pub async fn put_bucket_website(
+        &self,
+        website: &WebsiteConfiguration,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("website", "");
+        build.method = RequestType::Put;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: put bucket website: {} headers: {:?}", url, headers);
+        let response = client.put(url).headers(headers).body(website.to_xml()).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: put bucket website status: {} error: {}", status, result);
+            Err(OssError::from_response("put bucket website", status, result))
+        }
+    }
+
+    /// 获取bucket的静态网站托管配置，没有配置时返回NoSuchWebsiteConfiguration错误
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let website = oss.get_bucket_website(RequestBuilder::new()).await.unwrap();
+    /// println!("index: {}", website.index_document);
+    /// ```
+    pub async fn get_bucket_website(&self, build: RequestBuilder) -> Result<WebsiteConfiguration, OssError> {
+        let build = build.parameters_put("website", "");
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket website: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            WebsiteConfiguration::from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: get bucket website status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket website", status, result))
+        }
+    }
+
+    /// 设置bucket的防盗链(Referer白名单)
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::bucket_config::RefererConfiguration;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let referer = RefererConfiguration::new(false).with_referer("https://*.example.com");
+    /// oss.put_bucket_referer(&referer, RequestBuilder::new()).await.unwrap();
+    /// ```
+    pub async fn put_bucket_referer(
+        &self,
+        referer: &RefererConfiguration,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("referer", "");
+        build.method = RequestType::Put;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: put bucket referer: {} headers: {:?}", url, headers);
+        let response = client.put(url).headers(headers).body(referer.to_xml()).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: put bucket referer status: {} error: {}", status, result);
+            Err(OssError::from_response("put bucket referer", status, result))
+        }
+    }
+
+    /// 获取bucket的防盗链配置
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let referer = oss.get_bucket_referer(RequestBuilder::new()).await.unwrap();
+    /// println!("referers: {:?}", referer.referers);
+    /// ```
+    pub async fn get_bucket_referer(&self, build: RequestBuilder) -> Result<RefererConfiguration, OssError> {
+        let build = build.parameters_put("referer", "");
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket referer: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            RefererConfiguration::from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: get bucket referer status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket referer", status, result))
+        }
+    }
+
+    /// 开启bucket的访问日志，日志写入`target_bucket`，目标bucket需要和当前bucket在同一地域
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::bucket_config::LoggingConfiguration;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let logging = LoggingConfiguration::new("log-bucket").with_target_prefix("logs/");
+    /// oss.put_bucket_logging(&logging, RequestBuilder::new()).await.unwrap();
+    /// ```
+    pub async fn put_bucket_logging(
+        &self,
+        logging: &LoggingConfiguration,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("logging", "");
+        build.method = RequestType::Put;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: put bucket logging: {} headers: {:?}", url, headers);
+        let response = client.put(url).headers(headers).body(logging.to_xml()).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: put bucket logging status: {} error: {}", status, result);
+            Err(OssError::from_response("put bucket logging", status, result))
+        }
+    }
+
+    /// 获取bucket的访问日志配置，没有开启时返回None
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let logging = oss.get_bucket_logging(RequestBuilder::new()).await.unwrap();
+    /// println!("logging: {:?}", logging);
+    /// ```
+    pub async fn get_bucket_logging(&self, build: RequestBuilder) -> Result<Option<LoggingConfiguration>, OssError> {
+        let build = build.parameters_put("logging", "");
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket logging: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            LoggingConfiguration::from_logging_status(&result)
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: get bucket logging status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket logging", status, result))
+        }
+    }
+
+    /// 设置bucket的跨域资源共享(CORS)规则，会覆盖已有的全部规则
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::bucket_config::{CorsConfiguration, CorsRule};
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let cors = CorsConfiguration::new()
+    ///     .with_rule(CorsRule::new().with_allowed_origin("*").with_allowed_method("GET"));
+    /// oss.put_bucket_cors(&cors, RequestBuilder::new()).await.unwrap();
+    /// ```
+    pub async fn put_bucket_cors(
+        &self,
+        cors: &CorsConfiguration,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("cors", "");
+        build.method = RequestType::Put;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: put bucket cors: {} headers: {:?}", url, headers);
+        let response = client.put(url).headers(headers).body(cors.to_xml()).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: put bucket cors status: {} error: {}", status, result);
+            Err(OssError::from_response("put bucket cors", status, result))
+        }
+    }
+
+    /// 获取bucket的跨域资源共享(CORS)规则，没有配置时返回NoSuchCORSConfiguration错误
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let cors = oss.get_bucket_cors(RequestBuilder::new()).await.unwrap();
+    /// println!("rules: {}", cors.rules.len());
+    /// ```
+    pub async fn get_bucket_cors(&self, build: RequestBuilder) -> Result<CorsConfiguration, OssError> {
+        let build = build.parameters_put("cors", "");
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket cors: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            CorsConfiguration::from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: get bucket cors status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket cors", status, result))
+        }
+    }
+
+    /// 设置bucket的生命周期规则，会覆盖已有的全部规则
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::bucket_config::{LifecycleConfiguration, LifecycleRule};
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let lifecycle = LifecycleConfiguration::new()
+    ///     .with_rule(LifecycleRule::new("logs", "logs/").with_expiration_days(30));
+    /// oss.put_bucket_lifecycle(&lifecycle, RequestBuilder::new()).await.unwrap();
+    /// ```
+    pub async fn put_bucket_lifecycle(
+        &self,
+        lifecycle: &LifecycleConfiguration,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("lifecycle", "");
+        build.method = RequestType::Put;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: put bucket lifecycle: {} headers: {:?}", url, headers);
+        let response = client.put(url).headers(headers).body(lifecycle.to_xml()).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: put bucket lifecycle status: {} error: {}", status, result);
+            Err(OssError::from_response("put bucket lifecycle", status, result))
+        }
+    }
+
+    /// 获取bucket的生命周期规则，没有配置时返回NoSuchLifecycle错误
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let lifecycle = oss.get_bucket_lifecycle(RequestBuilder::new()).await.unwrap();
+    /// println!("rules: {}", lifecycle.rules.len());
+    /// ```
+    pub async fn get_bucket_lifecycle(&self, build: RequestBuilder) -> Result<LifecycleConfiguration, OssError> {
+        let build = build.parameters_put("lifecycle", "");
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket lifecycle: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            LifecycleConfiguration::from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: get bucket lifecycle status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket lifecycle", status, result))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::time::Duration;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use crate::bucket_config::{LifecycleConfiguration, LifecycleRule};
+    use crate::entity::StorageClass;
+    use crate::error::{CheckFailure, OssError};
+    use crate::fetch::{AsyncFetchSpec, AsyncFetchState};
+    use crate::list::ListObjectsResult;
+    use crate::mock_server;
+    use crate::oss::OSS;
+    use crate::request::RequestBuilder;
+
+    #[tokio::test]
+    async fn test_fetch_and_wait() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::xml_response("<AsyncFetchTaskResult><TaskId>task-1</TaskId></AsyncFetchTaskResult>"),
+            mock_server::xml_response("<AsyncFetchTaskInfo><TaskId>task-1</TaskId><State>Running</State></AsyncFetchTaskInfo>"),
+            mock_server::xml_response("<AsyncFetchTaskInfo><TaskId>task-1</TaskId><State>Success</State></AsyncFetchTaskInfo>"),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let spec = AsyncFetchSpec::new("https://cdn.example.com/a.png", "/images/a.png");
+        let info = oss
+            .fetch_and_wait(
+                spec,
+                RequestBuilder::new().with_cdn(addr),
+                Duration::from_millis(1),
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(info.task_id, "task-1");
+        assert_eq!(info.state, AsyncFetchState::Success);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("post /?asyncfetch "));
+        assert!(requests[1].starts_with("get /?asyncfetch "));
+        assert!(requests[1].contains("x-oss-task-id: task-1"));
+        assert!(requests[2].contains("x-oss-task-id: task-1"));
+    }
+
+    #[tokio::test]
+    async fn test_check() {
+        let body = "<Error><Code>InvalidAccessKeyId</Code><Message>The OSS Access Key Id you provided does not exist in our records.</Message></Error>";
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::xml_response("<BucketInfo><Bucket><Name>bucket</Name></Bucket></BucketInfo>"),
+            mock_server::response("403 Forbidden", "", body),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr);
+        oss.check(&build).await.unwrap();
+        let err = oss.check(&build).await.unwrap_err();
+        assert!(matches!(err, OssError::CheckFailed { kind: CheckFailure::InvalidAccessKey, .. }));
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("get /?bucketinfo "));
+
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "");
+        assert!(oss.check(&RequestBuilder::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_objects_start_after() {
+        let body = "<ListBucketResult><Name>bucket</Name><Prefix>dir/</Prefix><StartAfter>dir/b.txt</StartAfter>\
+<MaxKeys>1</MaxKeys><IsTruncated>true</IsTruncated><NextContinuationToken>token-1</NextContinuationToken>\
+<Contents><Key>dir/c.txt</Key><Size>3</Size></Contents><KeyCount>1</KeyCount></ListBucketResult>";
+        let (addr, server) = mock_server::serve(vec![mock_server::xml_response(body)]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new()
+            .with_cdn(addr)
+            .with_prefix("dir/")
+            .with_start_after("dir/b.txt")
+            .with_max_keys(1);
+        let result = oss.list_objects(build).await.unwrap();
+        assert_eq!(result.start_after.as_deref(), Some("dir/b.txt"));
+        assert_eq!(result.objects[0].key, "dir/c.txt");
+        assert_eq!(result.next_continuation_token.as_deref(), Some("token-1"));
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("get /?list-type=2&max-keys=1&prefix=dir%2f&start-after=dir%2fb.txt "));
+    }
+
+    #[tokio::test]
+    async fn test_fill_tagging_count() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "x-oss-tagging-count: 2\r\n", ""),
+            mock_server::response("200 OK", "", ""),
+        ]);
+        let body = "<ListBucketResult><Name>bucket</Name><Contents><Key>a.txt</Key></Contents>\
+<Contents><Key>b.txt</Key></Contents></ListBucketResult>";
+        let mut objects = ListObjectsResult::from_xml(body).unwrap().objects;
+        assert_eq!(objects[0].tagging_count, None);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        oss.fill_tagging_count(&mut objects, 1, RequestBuilder::new().with_cdn(addr))
+            .await
+            .unwrap();
+        assert_eq!(objects[0].tagging_count, Some(2));
+        assert_eq!(objects[1].tagging_count, Some(0));
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("head /a.txt "));
+        assert!(requests[1].starts_with("head /b.txt "));
+    }
+
+    #[tokio::test]
+    async fn test_list_objects_scoped() {
+        let body = "<ListBucketResult><Name>bucket</Name><Prefix>tenants/a/dir/</Prefix>\
+<Contents><Key>tenants/a/dir/c.txt</Key><Size>3</Size></Contents>\
+<CommonPrefixes><Prefix>tenants/a/dir/sub/</Prefix></CommonPrefixes></ListBucketResult>";
+        let (addr, server) = mock_server::serve(vec![mock_server::xml_response(body)]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket").scoped("tenants/a");
+        let result = oss
+            .list_objects(RequestBuilder::new().with_cdn(addr).with_prefix("dir/"))
+            .await
+            .unwrap();
+        assert_eq!(result.prefix, "dir/");
+        assert_eq!(result.objects[0].key, "dir/c.txt");
+        assert_eq!(result.common_prefixes, vec!["dir/sub/"]);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("get /?list-type=2&prefix=tenants%2fa%2fdir%2f "));
+    }
+
+    #[tokio::test]
+    async fn test_list_objects_cache() {
+        let body = "<ListBucketResult><Name>bucket</Name><Contents><Key>a.txt</Key></Contents></ListBucketResult>";
+        let (addr, server) = mock_server::serve(vec![mock_server::xml_response(body), mock_server::xml_response(body)]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket")
+            .with_list_cache(Duration::from_secs(60), 16);
+        let build = RequestBuilder::new().with_cdn(addr).with_prefix("dir/");
+        oss.list_objects(build.clone()).await.unwrap();
+        let cached = oss.list_objects(build.clone()).await.unwrap();
+        assert_eq!(cached.objects[0].key, "a.txt");
+        // 参数不同的列举不命中缓存
+        oss.list_objects(build.with_max_keys(10)).await.unwrap();
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].contains("max-keys=10"));
+    }
+
+    #[tokio::test]
+    async fn test_list_objects_cache_scoped() {
+        let body = "<ListBucketResult><Name>bucket</Name><Prefix>tenants/a/b/</Prefix>\
+<Contents><Key>tenants/a/b/c.txt</Key></Contents></ListBucketResult>";
+        let (addr, server) = mock_server::serve(vec![mock_server::xml_response(body)]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket")
+            .with_list_cache(Duration::from_secs(60), 16);
+        let build = RequestBuilder::new().with_cdn(addr);
+        let result = oss.scoped("tenants/a").list_objects(build.clone().with_prefix("b/")).await.unwrap();
+        assert_eq!(result.objects[0].key, "b/c.txt");
+        // 请求URL相同但作用域不同，命中缓存后按各自的作用域去掉前缀
+        let cached = oss.scoped("tenants/a/b").list_objects(build).await.unwrap();
+        assert_eq!(cached.objects[0].key, "c.txt");
+        assert_eq!(server.join().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_objects_gzip() {
+        let body = "<ListBucketResult><Name>bucket</Name><IsTruncated>false</IsTruncated>\
+<Contents><Key>a.txt</Key><Size>1</Size></Contents><KeyCount>1</KeyCount></ListBucketResult>";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(body.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            compressed.len()
+        )
+        .into_bytes();
+        response.extend_from_slice(&compressed);
+        let (addr, server) = mock_server::serve(vec![response]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let result = oss.list_objects(RequestBuilder::new().with_cdn(addr)).await.unwrap();
+        assert_eq!(result.objects[0].key, "a.txt");
+        let requests = server.join().unwrap();
+        assert!(requests[0].contains("accept-encoding: gzip"));
+    }
+
+    #[tokio::test]
+    async fn test_get_bucket_stat() {
+        let body = "<BucketStat><Storage>1600</Storage><ObjectCount>230</ObjectCount><StandardStorage>430</StandardStorage></BucketStat>";
+        let (addr, server) = mock_server::serve(vec![mock_server::xml_response(body)]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let stat = oss.get_bucket_stat(RequestBuilder::new().with_cdn(addr)).await.unwrap();
+        assert_eq!(stat.object_count, 230);
+        assert_eq!(stat.standard_storage, 430);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("get /?stat "));
+    }
+
+    #[tokio::test]
+    async fn test_put_and_get_bucket_lifecycle() {
+        let lifecycle = LifecycleConfiguration::new().with_rule(
+            LifecycleRule::new("logs", "logs/")
+                .with_transition(30, StorageClass::IA)
+                .with_expiration_days(180),
+        );
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "", ""),
+            mock_server::xml_response(&lifecycle.to_xml()),
+            mock_server::xml_response("<BucketLoggingStatus></BucketLoggingStatus>"),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr);
+        oss.put_bucket_lifecycle(&lifecycle, build.clone()).await.unwrap();
+        assert_eq!(oss.get_bucket_lifecycle(build.clone()).await.unwrap(), lifecycle);
+        assert_eq!(oss.get_bucket_logging(build).await.unwrap(), None);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("put /?lifecycle "));
+        assert!(requests[0].contains("<storageclass>ia</storageclass>"));
+        assert!(requests[1].starts_with("get /?lifecycle "));
+        assert!(requests[2].starts_with("get /?logging "));
+    }
+
+    #[tokio::test]
+    async fn test_raw_response() {
+        let list = "<ListBucketResult><Name>bucket</Name><NewField>1</NewField></ListBucketResult>";
+        let stat = "<BucketStat><Storage>1600</Storage><NewField>1</NewField></BucketStat>";
+        let worm = "<WormConfiguration><WormId>1666E2CFB2B3418****</WormId><State>Locked</State>\
+<RetentionPeriodInDays>1</RetentionPeriodInDays><NewField>1</NewField></WormConfiguration>";
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::xml_response(list),
+            mock_server::xml_response(list),
+            mock_server::xml_response(stat),
+            mock_server::xml_response(stat),
+            mock_server::xml_response(worm),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket")
+            .with_list_cache(Duration::from_secs(60), 16);
+        let build = RequestBuilder::new().with_cdn(addr);
+        assert_eq!(oss.list_objects(build.clone()).await.unwrap().raw, None);
+        // 需要原始响应时不使用缓存
+        let result = oss.list_objects(build.clone().with_raw_response()).await.unwrap();
+        assert_eq!(result.raw.as_deref(), Some(list));
+        assert_eq!(oss.get_bucket_stat(build.clone()).await.unwrap().raw, None);
+        let result = oss.get_bucket_stat(build.clone().with_raw_response()).await.unwrap();
+        assert_eq!(result.storage, 1600);
+        assert_eq!(result.raw.as_deref(), Some(stat));
+        let result = oss.get_bucket_worm(build.with_raw_response()).await.unwrap();
+        assert_eq!(result.raw.as_deref(), Some(worm));
+        assert_eq!(server.join().unwrap().len(), 5);
+    }
+}
@@ -1 +1,18 @@
-mod object;
\ No newline at end of file
+use crate::oss::OSS;
+
+mod bucket;
+mod object;
+mod multipart;
+mod transfer;
+
+impl OSS {
+    /// 等待限流器放行，未设置限流时直接返回
+    pub(crate) async fn acquire_rate_limit(&self) {
+        if let Some(limiter) = &self.rate_limiter {
+            let wait = limiter.reserve();
+            if !wait.is_zero() {
+                tokio::time::sleep(wait).await;
+            }
+        }
+    }
+}
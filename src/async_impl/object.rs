@@ -1,12 +1,23 @@
-use crate::entity::{PolicyBuilder, PolicyResp};
-use crate::error::OssError;
+use crate::multipart::multipart_copy_build;
+use crate::entity::{Callback, CopyOptions, ObjectAcl, PolicyBuilder, PolicyResp, ResumeOptions, StorageClass, TransitionReport, DeleteReport, MAX_COPY_OBJECT_SIZE, tags_from_xml};
+use crate::delete::{deleted_keys_from_xml, delete_objects_xml, MAX_DELETE_OBJECTS};
+use crate::error::{OssError, OssErrorCode, RenameStep};
+use crate::xml::element_text;
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_MATCH, RANGE};
+use reqwest::StatusCode;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use md5::{Digest, Md5};
+use std::path::Path;
+use std::time::Duration;
 use crate::metadata::ObjectMetadata;
-use crate::oss::{OSSInfo, API, OSS};
+use crate::process::{save_as_body, ImageInfo, ProcessSaveResult};
+use crate::oss::{API, OSS};
+use crate::restore_cache::next_poll_delay;
 use crate::request::{RequestBuilder, RequestType};
+use crate::worm::RetentionState;
 use crate::util::read_file;
-use crate::{debug, util};
-use hmac::Hmac;
-use sha1::digest::Mac;
+use crate::{date, debug, util};
+use futures_util::stream::{self, StreamExt};
 
 impl OSS {
     /// 获取对象
@@ -27,24 +38,267 @@ impl OSS {
         build: RequestBuilder,
     ) -> Result<Vec<u8>, OssError> {
         let key = self.format_key(key);
-        let (url, headers) = self
-            .build_request(key.as_str(), build)
-            .map_err(|e| OssError::Err(format!("build request error: {}", e)))?;
+        let verify_content_md5 = build.verify_content_md5;
+        let verify_sha256 = build.verify_sha256;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
         debug!("oss logget object url: {} headers: {:?}", url, headers);
-        let client = reqwest::Client::new();
         let response = client.get(url).headers(headers).send().await?;
-        return if response.status().is_success() {
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let content_md5 = util::content_md5_header(response.headers(), verify_content_md5);
+            let sha256 = util::Sha256Check::new(response.headers(), verify_sha256);
             let result = response.bytes().await?;
+            util::verify_content_md5(content_md5.as_deref(), &result)?;
+            if let Some(mut sha256) = sha256 {
+                sha256.update(&result);
+                sha256.finish()?;
+            }
             Ok(result.to_vec())
         } else {
             let status = response.status();
             let result = response.text().await?;
             debug!("oss log: get object status: {} error: {}", status, result);
-            Err(OssError::Err(format!(
-                "get object status: {} error: {}",
-                status, result
-            )))
+            Err(OssError::from_response("get object", status, result))
+        }
+    }
+
+    /// 获取对象内容和元数据，元数据中包含上传时设置的Content-Type
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let (bytes, metadata) = oss.get_object_full("/hello.txt", RequestBuilder::new()).await.unwrap();
+    /// println!("{} bytes, content type: {:?}", bytes.len(), metadata.content_type());
+    /// ```
+    pub async fn get_object_full<S: AsRef<str>>(
+        &self,
+        key: S,
+        build: RequestBuilder,
+    ) -> Result<(Vec<u8>, ObjectMetadata), OssError> {
+        let key = self.format_key(key);
+        let verify_content_md5 = build.verify_content_md5;
+        let verify_sha256 = build.verify_sha256;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: get object full url: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let metadata = ObjectMetadata::new(response.headers());
+            let content_md5 = util::content_md5_header(response.headers(), verify_content_md5);
+            let sha256 = util::Sha256Check::new(response.headers(), verify_sha256);
+            let result = response.bytes().await?;
+            util::verify_content_md5(content_md5.as_deref(), &result)?;
+            if let Some(mut sha256) = sha256 {
+                sha256.update(&result);
+                sha256.finish()?;
+            }
+            Ok((result.to_vec(), metadata))
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: get object full status: {} error: {}", status, result);
+            Err(OssError::from_response("get object", status, result))
+        }
+    }
+
+    /// 通过`oss://bucket/key`格式的URI获取对象
+    ///
+    /// URI中的bucket会覆盖OSS配置中的bucket，endpoint仍使用OSS配置，endpoint或凭证为空时返回错误
+    ///
+    /// # 使用例子
+    ///
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let build = RequestBuilder::new();
+    /// let bytes = oss.get_object_uri("oss://my-bucket/hello.txt", build).await.unwrap();
+    /// println!("file content: {}", String::from_utf8_lossy(bytes.as_slice()));
+    /// ```
+    pub async fn get_object_uri<S: AsRef<str>>(
+        &self,
+        uri: S,
+        build: RequestBuilder,
+    ) -> Result<Vec<u8>, OssError> {
+        let (bucket, key) = self.resolve_oss_uri(uri)?;
+        self.get_object(key, build.with_bucket(bucket)).await
+    }
+
+    /// 下载对象到文件，返回写入的字节数
+    ///
+    /// 传输中途连接断开时，会带上`Range`和首次响应的`ETag`(`If-Match`)从已写入的位置继续下载，
+    /// 期间对象被修改则返回[OssError::ObjectChangedDuringDownload]，不会把两个版本的内容拼在一起
+    ///
+    /// 下载过程中先写入`<文件名>.part`临时文件，完成后才重命名为目标文件，失败或取消时删除临时文件，
+    /// 不会留下看起来完整的半截文件
+    ///
+    /// 设置了[RequestBuilder::with_cancel_token]时，每个数据块之前检查是否取消，取消后返回[OssError::Cancelled]
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::entity::ResumeOptions;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let build = RequestBuilder::new();
+    /// let size = oss.get_object_to_file("/hello.txt", "hello.txt", build, ResumeOptions::new()).await.unwrap();
+    /// println!("download size: {}", size);
+    /// ```
+    pub async fn get_object_to_file<S: AsRef<str>, P: AsRef<Path>>(
+        &self,
+        key: S,
+        file: P,
+        build: RequestBuilder,
+        resume: ResumeOptions,
+    ) -> Result<u64, OssError> {
+        let key = self.format_key(key);
+        let path = file.as_ref();
+        let part_path = util::part_file_path(path);
+        let mut file = tokio::fs::File::create(&part_path).await?;
+        let result = self.download_to(key, &mut file, build, resume).await;
+        drop(file);
+        let result = match result {
+            Ok(size) => tokio::fs::rename(&part_path, path).await.map(|_| size).map_err(OssError::from),
+            Err(e) => Err(e),
         };
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&part_path).await;
+        }
+        result
+    }
+
+    /// 下载对象写入`writer`，返回写入的字节数
+    ///
+    /// 和[OSS::get_object_to_file]一样按`resume`续传，续传时从已写入`writer`的位置继续，
+    /// 期间对象被修改则返回[OssError::ObjectChangedDuringDownload]；出错时`writer`中可能已经写入了部分数据
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::entity::ResumeOptions;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let mut buffer = Vec::new();
+    /// let size = oss.get_object_to_writer("/hello.txt", &mut buffer, RequestBuilder::new(), ResumeOptions::new()).await.unwrap();
+    /// println!("download size: {}", size);
+    /// ```
+    pub async fn get_object_to_writer<S: AsRef<str>, W: AsyncWrite + Unpin>(
+        &self,
+        key: S,
+        writer: &mut W,
+        build: RequestBuilder,
+        resume: ResumeOptions,
+    ) -> Result<u64, OssError> {
+        let key = self.format_key(key);
+        self.download_to(key, writer, build, resume).await
+    }
+
+    async fn download_to<W: AsyncWrite + Unpin>(
+        &self,
+        key: String,
+        writer: &mut W,
+        build: RequestBuilder,
+        resume: ResumeOptions,
+    ) -> Result<u64, OssError> {
+        let mut offset: u64 = 0;
+        let mut etag: Option<String> = None;
+        let mut sha256: Option<util::Sha256Check> = None;
+        let mut attempts = 0;
+        loop {
+            if build.is_cancelled() {
+                return Err(OssError::Cancelled);
+            }
+            self.acquire_rate_limit().await;
+            let client = self.http_client(&build)?;
+            let (url, headers) = self.build_request(key.as_str(), build.clone())?;
+            debug!("oss log: get object to file url: {} offset: {} headers: {:?}", url, offset, headers);
+            let mut request = client.get(url).headers(headers);
+            if let Some(etag) = &etag {
+                request = request
+                    .header(RANGE, format!("bytes={}-", offset))
+                    .header(IF_MATCH, etag.as_str());
+            }
+            let mut response = match request.send().await {
+                Ok(response) => response,
+                Err(_e) if attempts < resume.max_attempts => {
+                    attempts += 1;
+                    debug!("oss log: get object to file send error: {}, retry: {}", _e, attempts);
+                    tokio::time::sleep(resume.delay(attempts)).await;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            self.record_server_time(response.headers());
+            let status = response.status();
+            if etag.is_some() && status == StatusCode::PRECONDITION_FAILED {
+                return Err(OssError::ObjectChangedDuringDownload(key));
+            }
+            if !status.is_success() {
+                let result = response.text().await?;
+                debug!("oss log: get object to file status: {} error: {}", status, result);
+                return Err(OssError::from_response("get object", status, result));
+            }
+            if etag.is_some() {
+                let expect = format!("bytes {}-", offset);
+                let content_range = response
+                    .headers()
+                    .get(CONTENT_RANGE)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default();
+                if status != StatusCode::PARTIAL_CONTENT || !content_range.starts_with(&expect) {
+                    return Err(OssError::Err(format!(
+                        "resume get object status: {} content range: {}",
+                        status, content_range
+                    )));
+                }
+            } else {
+                // 还没有收到过成功的响应，writer中没有数据，从头开始
+                offset = 0;
+                etag = response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|v| v.to_string());
+                sha256 = util::Sha256Check::new(response.headers(), build.verify_sha256);
+            }
+            loop {
+                if build.is_cancelled() {
+                    return Err(OssError::Cancelled);
+                }
+                match response.chunk().await {
+                    Ok(Some(chunk)) => {
+                        writer.write_all(&chunk).await?;
+                        if let Some(sha256) = sha256.as_mut() {
+                            sha256.update(&chunk);
+                        }
+                        offset += chunk.len() as u64;
+                    }
+                    Ok(None) => {
+                        writer.flush().await?;
+                        if let Some(sha256) = sha256.take() {
+                            sha256.finish()?;
+                        }
+                        return Ok(offset);
+                    }
+                    Err(_e) if etag.is_some() && attempts < resume.max_attempts => {
+                        attempts += 1;
+                        debug!("oss log: get object to file read error: {}, resume from: {}", _e, offset);
+                        tokio::time::sleep(resume.delay(attempts)).await;
+                        break;
+                    }
+                    // 响应头已经是200，传输中途出错时文件不完整，不能当作成功
+                    Err(e) => {
+                        return Err(OssError::DownloadInterrupted {
+                            received: offset,
+                            message: e.to_string(),
+                        })
+                    }
+                }
+            }
+        }
     }
 
     /// 获取上传对象的policy
@@ -66,8 +320,8 @@ impl OSS {
     /// //file为上传的文件，类型跟with_content_type一致
     /// ```
     pub fn get_upload_object_policy(&self, build: PolicyBuilder) -> Result<PolicyResp, OssError> {
-        let date = chrono::Local::now().naive_local() + chrono::Duration::seconds(build.expire);
-        let date_str = date.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        // expiration必须是UTC时间，之前用本地时间加Z后缀会在非UTC时区提前或推迟过期
+        let date_str = date::iso8601(chrono::Utc::now() + chrono::Duration::seconds(build.expire));
         let mut json_data = r#"
         {
             "expiration": "{time}",
@@ -83,7 +337,7 @@ impl OSS {
         .to_string();
         let success_action_status = 200;
         json_data = json_data.replacen("{time}", &date_str, 1);
-        json_data = json_data.replacen("{bucket}", &self.bucket(), 1);
+        json_data = json_data.replacen("{bucket}", self.bucket_str(), 1);
         //limit 1GB bytes
         json_data = json_data.replacen("{size}", &build.max_upload_size.to_string(), 1); //允许上传的最大文件大小
                                                                                          //success status
@@ -98,13 +352,11 @@ impl OSS {
         //只允许上传哪个类型文件
         debug!("oss log: policy json: {}", json_data);
         let base64_policy = util::base64_encode(json_data.as_bytes());
-        let mut hasher: Hmac<sha1::Sha1> = Hmac::new_from_slice(self.key_secret().as_bytes())
-            .map_err(|_| OssError::Err("Hmac new from slice error".to_string()))?;
-        hasher.update(base64_policy.as_bytes());
-        let signature = util::base64_encode(&hasher.finalize().into_bytes());
+        let credentials = self.credentials();
+        let signature = util::base64_encode(util::hmac_sha1(credentials.key_secret.as_bytes(), base64_policy.as_bytes()));
         Ok(PolicyResp {
-            access_id: self.key_id().to_string(),
-            host: format!("https://{}.{}", self.bucket(), self.endpoint()),
+            access_id: credentials.key_id,
+            host: format!("https://{}.{}", self.bucket_str(), self.endpoint_str()),
             policy: base64_policy,
             signature,
             success_action_status,
@@ -131,27 +383,27 @@ impl OSS {
         let buffer = read_file(file_path)?;
         let mut build = build.clone();
         build.method = RequestType::Put;
+        let crc64 = util::apply_full_integrity(&mut build, &buffer);
+        let sha256 = util::upload_sha256(&build, &buffer);
         let key = self.format_key(key);
-        let (url, headers) = self
-            .build_request(key.as_str(), build)
-            .map_err(|e| OssError::Err(format!("build request error: {}", e)))?;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
         debug!(
             "oss log: put object from file: {} headers: {:?}",
             url, headers
         );
-        let client = reqwest::Client::new();
         let response = client.put(url).headers(headers).body(buffer).send().await?;
-        return if response.status().is_success() {
-            Ok(())
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            util::verify_crc64(crc64, response.headers())?;
+            util::verify_sha256(sha256, response.headers())
         } else {
             let status = response.status();
             let result = response.text().await?;
             debug!("oss log: get object status: {} error: {}", status, result);
-            Err(OssError::Err(format!(
-                "get object status: {} error: {}",
-                status, result
-            )))
-        };
+            Err(OssError::from_response("get object", status, result))
+        }
     }
 
     /// 上传文件(内存)
@@ -174,32 +426,187 @@ impl OSS {
     ) -> Result<(), OssError> {
         let mut build = build.clone();
         build.method = RequestType::Put;
+        let crc64 = util::apply_full_integrity(&mut build, buffer);
+        let sha256 = util::upload_sha256(&build, buffer);
         let key = self.format_key(key);
-        let (url, headers) = self
-            .build_request(key.as_str(), build)
-            .map_err(|e| OssError::Err(format!("build request error: {}", e)))?;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
         debug!(
             "oss log: put object from file: {} headers: {:?}",
             url, headers
         );
-        let client = reqwest::Client::new();
         let response = client
             .put(url)
             .headers(headers)
             .body(buffer.to_owned())
             .send()
             .await?;
-        return if response.status().is_success() {
-            Ok(())
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            util::verify_crc64(crc64, response.headers())?;
+            util::verify_sha256(sha256, response.headers())
         } else {
             let status = response.status();
             let result = response.text().await?;
             debug!("oss log: get object status: {} error: {}", status, result);
-            Err(OssError::Err(format!(
-                "get object status: {} error: {}",
-                status, result
-            )))
+            Err(OssError::from_response("get object", status, result))
+        }
+    }
+
+    /// 上传文件(内存)并触发上传回调，返回应用服务器回调接口的响应内容
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::entity::Callback;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let callback = Callback::new("https://example.com/callback", "object=${object}&uid=${x:uid}")
+    ///     .with_var("x:uid", "10086")
+    ///     .unwrap();
+    /// let body = oss.put_object_with_callback("/hello.txt", b"hello", &callback, RequestBuilder::new()).await.unwrap();
+    /// println!("callback response: {}", String::from_utf8_lossy(&body));
+    /// ```
+    pub async fn put_object_with_callback<S: AsRef<str>>(
+        &self,
+        key: S,
+        buffer: &[u8],
+        callback: &Callback,
+        build: RequestBuilder,
+    ) -> Result<Vec<u8>, OssError> {
+        let mut build = build.with_callback(callback);
+        build.method = RequestType::Put;
+        let crc64 = util::apply_full_integrity(&mut build, buffer);
+        let sha256 = util::upload_sha256(&build, buffer);
+        let key = self.format_key(key);
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: put object with callback: {} headers: {:?}", url, headers);
+        let response = client
+            .put(url)
+            .headers(headers)
+            .body(buffer.to_owned())
+            .send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            util::verify_crc64(crc64, response.headers())?;
+            util::verify_sha256(sha256, response.headers())?;
+            Ok(response.bytes().await?.to_vec())
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: put object with callback status: {} error: {}", status, result);
+            Err(OssError::from_response("put object with callback", status, result))
+        }
+    }
+
+    /// 追加上传，从`position`处追加`content`，返回下一次追加的位置
+    ///
+    /// 对象不存在时`position`需要为0，此时会创建追加类型(Appendable)的对象
+    ///
+    /// `position`与对象当前长度不一致时返回[OssError::PositionNotEqualToLength]，其中带有正确的追加位置
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let next = oss.append_object("/log.txt", b"hello ", 0, RequestBuilder::new()).await.unwrap();
+    /// oss.append_object("/log.txt", b"world", next, RequestBuilder::new()).await.unwrap();
+    /// ```
+    pub async fn append_object<S: AsRef<str>>(
+        &self,
+        key: S,
+        content: &[u8],
+        position: u64,
+        build: RequestBuilder,
+    ) -> Result<u64, OssError> {
+        let mut build = build
+            .parameters_put("append".to_string(), "".to_string())
+            .parameters_put("position".to_string(), position.to_string());
+        build.method = RequestType::Post;
+        let key = self.format_key(key);
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: append object: {} headers: {:?}", url, headers);
+        let response = client
+            .post(url)
+            .headers(headers)
+            .body(content.to_owned())
+            .send().await?;
+        self.record_server_time(response.headers());
+        let next = response
+            .headers()
+            .get("x-oss-next-append-position")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+        if response.status().is_success() {
+            Ok(next.unwrap_or(position + content.len() as u64))
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: append object status: {} error: {}", status, result);
+            // 位置不对时OSS仍然会返回正确的追加位置，直接带给调用方重试
+            if let Some(next_position) = next {
+                if element_text(&result, "Code").as_deref() == Some("PositionNotEqualToLength") {
+                    return Err(OssError::PositionNotEqualToLength { next_position });
+                }
+            }
+            Err(OssError::from_response("append object", status, result))
+        }
+    }
+
+    /// 追加上传，自动获取当前对象长度作为追加位置，对象不存在时创建，返回下一次追加的位置
+    ///
+    /// 对象存在但不是追加类型时返回错误
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let next = oss.append_or_create("/log.txt", b"hello\n", RequestBuilder::new()).await.unwrap();
+    /// println!("next position: {}", next);
+    /// ```
+    pub async fn append_or_create<S: AsRef<str>>(
+        &self,
+        key: S,
+        content: &[u8],
+        build: RequestBuilder,
+    ) -> Result<u64, OssError> {
+        let raw_key = key.as_ref();
+        let key = self.format_key(raw_key);
+        let mut head = build.clone();
+        head.method = RequestType::Head;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&head)?;
+        let (url, headers) = self.build_request(key.as_str(), head)?;
+        debug!("oss log: append or create head: {} headers: {:?}", url, headers);
+        let response = client.head(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        let position = if response.status() == StatusCode::NOT_FOUND {
+            0
+        } else if response.status().is_success() {
+            let metadata = ObjectMetadata::new(response.headers());
+            if metadata.object_type().as_deref() != Some("Appendable") {
+                return Err(OssError::Err(format!(
+                    "{} is not appendable, object type: {}",
+                    key,
+                    metadata.object_type().unwrap_or_default()
+                )));
+            }
+            metadata
+                .content_length()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or_default()
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: append or create head status: {} error: {}", status, result);
+            return Err(OssError::from_response("append or create", status, result));
         };
+        // append_object内部会加上作用域前缀，这里传入原始key
+        self.append_object(raw_key, content, position, build).await
     }
 
     /// 删除文件
@@ -220,63 +627,247 @@ impl OSS {
         let mut build = build.clone();
         build.method = RequestType::Delete;
         let key = self.format_key(key);
-        let (url, headers) = self
-            .build_request(key.as_str(), build)
-            .map_err(|e| OssError::Err(format!("build request error: {}", e)))?;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
         debug!(
             "oss log: put object from file: {} headers: {:?}",
             url, headers
         );
-        let client = reqwest::Client::new();
         let response = client.delete(url).headers(headers).send().await?;
-        return if response.status().is_success() {
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
             Ok(())
         } else {
             let status = response.status();
             let result = response.text().await?;
             debug!("oss log: get object status: {} error: {}", status, result);
-            Err(OssError::Err(format!(
-                "get object status: {} error: {}",
-                status, result
-            )))
-        };
+            Err(OssError::from_response("get object", status, result))
+        }
     }
 
+    /// 批量删除对象(DeleteMultipleObjects)，一次最多1000个key，返回删除成功的key(不带开头的`/`)
+    ///
+    /// 删除不存在的对象也视为成功。设置[RequestBuilder::with_quiet_delete]后OSS不返回删除结果，返回空的Vec
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let deleted = oss.delete_multiple_objects(&["/a.txt", "/b.txt"], RequestBuilder::new()).await.unwrap();
+    /// println!("deleted: {:?}", deleted);
+    /// ```
+    pub async fn delete_multiple_objects<S: AsRef<str>>(
+        &self,
+        keys: &[S],
+        build: RequestBuilder,
+    ) -> Result<Vec<String>, OssError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        if keys.len() > MAX_DELETE_OBJECTS {
+            return Err(OssError::Err(format!(
+                "delete multiple objects supports at most {} keys, got {}",
+                MAX_DELETE_OBJECTS,
+                keys.len()
+            )));
+        }
+        let keys = keys.iter().map(|key| self.format_key(key)).collect::<Vec<_>>();
+        let body = delete_objects_xml(&keys, build.quiet_delete);
+        // DeleteMultipleObjects必须带Content-MD5
+        let mut build = build
+            .parameters_put("delete", "")
+            .with_content_md5(util::base64_encode(Md5::digest(body.as_bytes())));
+        build.method = RequestType::Post;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: delete multiple objects: {} headers: {:?}", url, headers);
+        let response = client.post(url).headers(headers).body(body).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            Ok(deleted_keys_from_xml(&result).into_iter().map(|key| self.unscope_key(key)).collect())
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: delete multiple objects status: {} error: {}", status, result);
+            Err(OssError::from_response("delete multiple objects", status, result))
+        }
+    }
+
+    /// 删除`prefix`下的所有对象，返回删除成功和失败的数量
+    ///
+    /// 按页(每页最多1000个key)列举，每页作为一次[OSS::delete_multiple_objects]请求，最多同时发起
+    /// [with_concurrency](RequestBuilder::with_concurrency)个删除请求(没有设置时为1)。
+    /// 一批删除失败不会中断后续批次，失败的key和原因见[DeleteReport::failed]，列举失败时停止并返回错误。
+    /// 列举不使用[列举缓存](OSS::with_list_cache)，总是使用verbose模式，以便确认每个key的删除结果
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let report = oss
+    ///     .delete_by_prefix("logs/2023/", RequestBuilder::new().with_concurrency(8))
+    ///     .await
+    ///     .unwrap();
+    /// println!("deleted: {} failed: {}", report.deleted, report.failed_count());
+    /// ```
+    pub async fn delete_by_prefix<S: AsRef<str>>(
+        &self,
+        prefix: S,
+        build: RequestBuilder,
+    ) -> Result<DeleteReport, OssError> {
+        let concurrency = self.merge_default_build(build.clone()).concurrency.unwrap_or(1).max(1);
+        let list_build = build
+            .clone()
+            .with_prefix(prefix.as_ref())
+            .with_max_keys(MAX_DELETE_OBJECTS as u32);
+        let delete_build = build.with_quiet_delete(false);
+        let delete_build = &delete_build;
+        let mut report = DeleteReport::default();
+        let mut token: Option<String> = None;
+        let mut truncated = true;
+        while truncated {
+            // 先列举最多concurrency页，再并发删除这些页
+            let mut batches = Vec::new();
+            while truncated && batches.len() < concurrency {
+                let page_build = match &token {
+                    Some(token) => list_build.clone().with_continuation_token(token),
+                    None => list_build.clone(),
+                };
+                let page = self.list_objects_page(page_build, false).await?;
+                token = page.next_continuation_token;
+                truncated = page.is_truncated && token.is_some();
+                let keys = page.objects.into_iter().map(|object| object.key).collect::<Vec<_>>();
+                if !keys.is_empty() {
+                    batches.push(keys);
+                }
+            }
+            let results = stream::iter(batches)
+                .map(|keys| async move {
+                    let result = self.delete_multiple_objects(&keys, delete_build.clone()).await;
+                    (keys, result)
+                })
+                .buffer_unordered(concurrency)
+                .collect::<Vec<_>>()
+                .await;
+            for (keys, result) in results {
+                report.record(keys, result);
+            }
+        }
+        Ok(report)
+    }
+
+    /// 拷贝同一bucket下的对象，`source_key`为源对象，`key`为目标对象
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let builder = RequestBuilder::new();
+    /// oss.copy_object("/hello_copy.txt", "/hello.txt", &builder).await.unwrap();
+    /// ```
     pub async fn copy_object(
         &self,
         key: &str,
         source_key: &str,
         build: &RequestBuilder,
     ) -> Result<(), OssError> {
-        let mut build = build.clone();
-        build.method = RequestType::Put;
+        let copy_source = self.copy_source(source_key, build);
+        self.copy_object_from(key, &copy_source, build).await
+    }
+
+    /// 同[OSS::copy_object]，`copy_source`为已经编码好的`/bucket/key`，可以来自其它bucket
+    pub(crate) async fn copy_object_from(
+        &self,
+        key: &str,
+        copy_source: &str,
+        build: &RequestBuilder,
+    ) -> Result<(), OssError> {
         let key = self.format_key(key);
-        let source_key = self.format_key(source_key);
-        let (url, headers) = self
-            .build_request(key.as_str(), build)
-            .map_err(|e| OssError::Err(format!("build request error: {}", e)))?;
+        let mut build = build.clone().oss_header_put("x-oss-copy-source", copy_source);
+        build.method = RequestType::Put;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
         debug!(
             "oss log: copy object from file: {} headers: {:?}",
             url, headers
         );
-        let client = reqwest::Client::new();
-        let response = client
-            .put(url)
-            .headers(headers)
-            .header("x-oss-copy-source", source_key)
-            .send()
-            .await?;
-        return if response.status().is_success() {
+        let response = client.put(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
             Ok(())
         } else {
             let status = response.status();
             let result = response.text().await?;
             debug!("oss log: copy object status: {} error: {}", status, result);
-            Err(OssError::Err(format!(
-                "copy object status: {} error: {}",
-                status, result
-            )))
+            Err(OssError::from_response("copy object", status, result))
+        }
+    }
+
+    /// 使用[CopyOptions]拷贝对象，可以一次性指定元数据、标签、访问权限、存储类型和拷贝条件
+    ///
+    /// 参数顺序同[OSS::copy_object]，`key`为目标对象，`source_key`为源对象
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::entity::{CopyOptions, ObjectAcl, StorageClass};
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let options = CopyOptions::new()
+    ///     .with_metadata("author", "lake")
+    ///     .with_acl(ObjectAcl::Private)
+    ///     .with_storage_class(StorageClass::IA);
+    /// oss.copy_object_with("/hello_copy.txt", "/hello.txt", options, RequestBuilder::new()).await.unwrap();
+    /// ```
+    pub async fn copy_object_with(
+        &self,
+        key: &str,
+        source_key: &str,
+        options: CopyOptions,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let build = options.apply(build);
+        self.copy_object(key, source_key, &build).await
+    }
+
+    /// 重命名对象，依次拷贝到`key`、HEAD确认目标对象存在、删除`source_key`，不是原子操作
+    ///
+    /// 拷贝失败时直接返回错误，源对象不受影响；拷贝成功后确认目标或删除源对象失败时返回
+    /// [OssError::PartialRename]，其中`step`为失败的步骤，源对象和目标对象都保留，需要调用方处理
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::error::OssError;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// match oss.rename_object("/renamed.txt", "/hello.txt", RequestBuilder::new()).await {
+    ///     Ok(()) => println!("renamed"),
+    ///     Err(OssError::PartialRename { step, .. }) => println!("both objects kept, failed at {}", step),
+    ///     Err(e) => println!("rename failed: {}", e),
+    /// }
+    /// ```
+    pub async fn rename_object(
+        &self,
+        key: &str,
+        source_key: &str,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        self.copy_object(key, source_key, &build).await?;
+        let partial = |step: RenameStep, e: OssError| OssError::PartialRename {
+            step,
+            source_key: source_key.to_string(),
+            target_key: key.to_string(),
+            message: e.to_string(),
         };
+        self.get_object_metadata(key, build.clone())
+            .await
+            .map_err(|e| partial(RenameStep::VerifyTarget, e))?;
+        self.delete_object(source_key, &build)
+            .await
+            .map_err(|e| partial(RenameStep::DeleteSource, e))
     }
 
     /// 获取对象元数据
@@ -298,23 +889,989 @@ impl OSS {
         let mut build = build.clone();
         build.method = RequestType::Head;
         let key = self.format_key(key);
-        let (url, headers) = self
-            .build_request(key.as_str(), build)
-            .map_err(|e| OssError::Err(format!("build request error: {}", e)))?;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
         debug!("put object from file: {} headers: {:?}", url, headers);
-        let client = reqwest::Client::new();
         let response = client.head(url).headers(headers).send().await?;
-        return if response.status().is_success() {
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
             let metadata = ObjectMetadata::new(response.headers());
             Ok(metadata)
         } else {
             let status = response.status();
             let result = response.text().await?;
             debug!("get object status: {} error: {}", status, result);
-            Err(OssError::Err(format!(
-                "get object status: {} error: {}",
+            Err(OssError::from_response("get object", status, result))
+        }
+    }
+
+    /// 批量获取对象元数据，最多同时发起`concurrency`个HEAD请求，`concurrency`为0时按1处理
+    ///
+    /// 按`keys`的顺序返回每个key的结果，单个key失败(例如404)不影响其它key
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let results = oss.head_objects(&["/a.txt", "/b.txt"], 16, RequestBuilder::new()).await;
+    /// for (key, result) in results {
+    ///     match result {
+    ///         Ok(metadata) => println!("{} {:?}", key, metadata.content_length()),
+    ///         Err(e) => println!("{} error: {}", key, e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn head_objects<S: AsRef<str>>(
+        &self,
+        keys: &[S],
+        concurrency: usize,
+        build: RequestBuilder,
+    ) -> Vec<(String, Result<ObjectMetadata, OssError>)> {
+        let concurrency = concurrency.max(1);
+        let results = stream::iter(keys)
+            .map(|key| self.get_object_metadata(key.as_ref(), build.clone()))
+            .buffered(concurrency)
+            .collect::<Vec<_>>()
+            .await;
+        keys.iter().map(|key| key.as_ref().to_string()).zip(results).collect()
+    }
+
+    /// 获取对象的保留状态，bucket没有配置WORM策略时返回None，只支持读取
+    ///
+    /// OSS的WORM策略是bucket级别的，保留期从对象的最后修改时间开始计算，
+    /// 这里通过[OSS::get_bucket_worm]和对象的元数据计算，需要两次请求
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// if let Some(retention) = oss.get_object_retention("/audit.log", RequestBuilder::new()).await.unwrap() {
+    ///     println!("{} until {}, protected: {}", retention.mode, retention.retain_until, retention.is_protected());
+    /// }
+    /// ```
+    pub async fn get_object_retention<S: AsRef<str>>(
+        &self,
+        key: S,
+        build: RequestBuilder,
+    ) -> Result<Option<RetentionState>, OssError> {
+        let worm = match self.get_bucket_worm(build.clone()).await {
+            Ok(worm) => worm,
+            Err(e) if e.code() == Some(OssErrorCode::NoSuchWORMConfiguration) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let metadata = self.get_object_metadata(key, build).await?;
+        let last_modified = metadata
+            .last_modified()
+            .ok_or_else(|| OssError::Err("object metadata has no Last-Modified".to_string()))?;
+        Ok(Some(RetentionState::new(&worm, last_modified)))
+    }
+
+    /// 获取对象的访问权限，归档类型的对象不需要解冻也可以获取
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let acl = oss.get_object_acl("/hello.txt", RequestBuilder::new()).await.unwrap();
+    /// println!("acl: {}", acl);
+    /// ```
+    pub async fn get_object_acl<S: AsRef<str>>(
+        &self,
+        key: S,
+        build: RequestBuilder,
+    ) -> Result<ObjectAcl, OssError> {
+        let build = build.parameters_put("acl", "");
+        let key = self.format_key(key);
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: get object acl: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            element_text(&result, "Grant")
+                .ok_or_else(|| OssError::Err(format!("invalid object acl xml: {}", result)))?
+                .parse()
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: get object acl status: {} error: {}", status, result);
+            Err(OssError::from_response("get object acl", status, result))
+        }
+    }
+
+    /// 获取对象的标签，按OSS返回的顺序
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let tags = oss.get_object_tagging("/hello.txt", RequestBuilder::new()).await.unwrap();
+    /// for (key, value) in tags {
+    ///     println!("{}={}", key, value);
+    /// }
+    /// ```
+    pub async fn get_object_tagging<S: AsRef<str>>(
+        &self,
+        key: S,
+        build: RequestBuilder,
+    ) -> Result<Vec<(String, String)>, OssError> {
+        let build = build.parameters_put("tagging", "");
+        let key = self.format_key(key);
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: get object tagging: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            Ok(tags_from_xml(&result))
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: get object tagging status: {} error: {}", status, result);
+            Err(OssError::from_response("get object tagging", status, result))
+        }
+    }
+
+    /// 数据处理结果另存为，由OSS服务端处理后直接保存到`dest_key`，不经过本地
+    ///
+    /// `dest_bucket`为空时保存到当前bucket
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::process::ImageProcess;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let builder = RequestBuilder::new();
+    /// let process = ImageProcess::new().resize(Some(100), None).unwrap().to_string();
+    /// let result = oss
+    ///     .process_object_save("/panda.png", &process, "/thumbs/panda.png", None, builder)
+    ///     .await
+    ///     .unwrap();
+    /// println!("{:?}", result);
+    /// ```
+    pub async fn process_object_save<S: AsRef<str>>(
+        &self,
+        src_key: S,
+        process: &str,
+        dest_key: &str,
+        dest_bucket: Option<&str>,
+        build: RequestBuilder,
+    ) -> Result<ProcessSaveResult, OssError> {
+        let mut build = build.parameters_put("x-oss-process", "");
+        build.method = RequestType::Post;
+        let key = self.format_key(src_key);
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!(
+            "oss log: process object save: {} headers: {:?}",
+            url, headers
+        );
+        // 保存到同一个bucket时，目标key也在作用域内
+        let dest_key = match dest_bucket {
+            Some(_) => dest_key.to_string(),
+            None => self.format_key(dest_key),
+        };
+        let body = save_as_body(process, &dest_key, dest_bucket);
+        let response = client.post(url).headers(headers).body(body).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            Ok(serde_json::from_str(&result)?)
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!(
+                "oss log: process object save status: {} error: {}",
                 status, result
-            )))
+            );
+            Err(OssError::from_response("process object save", status, result))
+        }
+    }
+
+    /// 获取图片信息，包括宽高、格式、文件大小以及EXIF信息
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let builder = RequestBuilder::new();
+    /// let info = oss.get_image_info("/panda.png", builder).await.unwrap();
+    /// println!("{}x{}", info.width, info.height);
+    /// ```
+    pub async fn get_image_info<S: AsRef<str>>(
+        &self,
+        key: S,
+        build: RequestBuilder,
+    ) -> Result<ImageInfo, OssError> {
+        let build = build.with_process("image/info");
+        let key = self.format_key(key);
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: get image info: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text().await?;
+            ImageInfo::from_json(result)
+        } else {
+            let status = response.status();
+            let result = response.text().await?;
+            debug!("oss log: get image info status: {} error: {}", status, result);
+            Err(OssError::from_response("get image info", status, result))
+        }
+    }
+
+    /// 批量转换存储类型，通过拷贝到自身(`x-oss-metadata-directive: COPY`)实现
+    ///
+    /// 最多同时处理`concurrency`个key，已经是目标存储类型的key会跳过，
+    /// `concurrency`为0时按1处理，单个key失败不会中断整个批次，结果见[TransitionReport]。
+    /// 超过1GB的对象使用分片拷贝(UploadPartCopy)，保留Content-Type等标准header和用户元数据，但不保留标签和对象ACL
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::entity::StorageClass;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let builder = RequestBuilder::new();
+    /// let report = oss
+    ///     .change_storage_class(&["/a.txt", "/b.txt"], StorageClass::Archive, 8, builder)
+    ///     .await
+    ///     .unwrap();
+    /// println!("failed: {:?}, need restore: {:?}", report.failed, report.cold);
+    /// ```
+    pub async fn change_storage_class<S: AsRef<str>>(
+        &self,
+        keys: &[S],
+        target: StorageClass,
+        concurrency: usize,
+        build: RequestBuilder,
+    ) -> Result<TransitionReport, OssError> {
+        let concurrency = concurrency.max(1);
+        let results = stream::iter(keys)
+            .map(|key| self.transition_storage_class(key.as_ref(), target, &build))
+            .buffered(concurrency)
+            .collect::<Vec<_>>()
+            .await;
+        let mut report = TransitionReport::default();
+        for (key, result) in keys.iter().zip(results) {
+            report.record(key.as_ref(), target, result);
+        }
+        Ok(report)
+    }
+
+    /// 解冻归档类型的对象，解冻已经在进行中或者已经完成时也返回成功
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// oss.restore_object("/archive.zip", RequestBuilder::new()).await.unwrap();
+    /// ```
+    pub async fn restore_object<S: AsRef<str>>(&self, key: S, build: RequestBuilder) -> Result<(), OssError> {
+        let key = self.format_key(key);
+        let mut build = build.parameters_put("restore", "");
+        build.method = RequestType::Post;
+        self.acquire_rate_limit().await;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: restore object: {} headers: {:?}", url, headers);
+        let response = client.post(url).headers(headers).send().await?;
+        self.record_server_time(response.headers());
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let result = response.text().await?;
+        debug!("oss log: restore object status: {} error: {}", status, result);
+        match OssError::from_response("restore object", status, result) {
+            err if err.code() == Some(OssErrorCode::RestoreAlreadyInProgress) => Ok(()),
+            err => Err(err),
+        }
+    }
+
+    /// 发起解冻后在后台任务(tokio::spawn)中每隔`interval`查询一次对象状态，可以读取时调用`on_ready`
+    ///
+    /// 查询间隔从`interval`开始每次翻倍，最多到`interval`的16倍，避免长时间解冻期间不停地发起请求；
+    /// 同一个对象有多个等待任务时，`interval`内的HEAD结果由所有任务(包括clone出来的实例)复用
+    ///
+    /// 发起解冻失败时直接返回错误；后台查询出错时停止查询，错误通过返回的JoinHandle获取，此时不会调用`on_ready`
+    /// # 使用例子
+    /// ```rust
+    /// use std::time::Duration;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let handle = oss
+    ///     .restore_object_then("/archive.zip", RequestBuilder::new(), Duration::from_secs(60), || {
+    ///         println!("archive.zip is readable");
+    ///     })
+    ///     .await.unwrap();
+    /// ```
+    pub async fn restore_object_then<S, F>(
+        &self,
+        key: S,
+        build: RequestBuilder,
+        interval: Duration,
+        on_ready: F,
+    ) -> Result<tokio::task::JoinHandle<Result<(), OssError>>, OssError>
+    where
+        S: AsRef<str>,
+        F: FnOnce() + Send + 'static,
+    {
+        // restore_object和get_object_metadata内部会加上作用域前缀，这里保留原始key
+        let key = key.as_ref().to_string();
+        self.restore_object(key.as_str(), build.clone()).await?;
+        let oss = self.clone();
+        Ok(tokio::spawn(async move {
+            let cache_key = oss.restore_cache_key(key.as_str(), &build);
+            let mut delay = interval;
+            let result = loop {
+                let metadata = match oss.restore_poll(key.as_str(), cache_key.as_str(), &build, interval).await {
+                    Ok(metadata) => metadata,
+                    Err(e) => break Err(e),
+                };
+                if metadata.is_readable() {
+                    break Ok(());
+                }
+                tokio::time::sleep(delay).await;
+                delay = next_poll_delay(delay, interval);
+            };
+            oss.restore_cache.remove(cache_key.as_str());
+            if result.is_ok() {
+                on_ready();
+            }
+            result
+        }))
+    }
+
+    /// 等待解冻的缓存key，区分bucket和作用域
+    fn restore_cache_key(&self, key: &str, build: &RequestBuilder) -> String {
+        let bucket = build.bucket.as_deref().unwrap_or(self.bucket_str());
+        format!("{}{}", bucket, self.format_key(key))
+    }
+
+    /// 查询对象状态，`max_age`内已有其它等待任务的HEAD结果时直接复用
+    async fn restore_poll(
+        &self,
+        key: &str,
+        cache_key: &str,
+        build: &RequestBuilder,
+        max_age: Duration,
+    ) -> Result<ObjectMetadata, OssError> {
+        if let Some(metadata) = self.restore_cache.get(cache_key, max_age) {
+            debug!("oss log: restore status from cache: {}", cache_key);
+            return Ok(metadata);
+        }
+        let metadata = self.get_object_metadata(key, build.clone()).await?;
+        self.restore_cache.insert(cache_key.to_string(), metadata.clone());
+        Ok(metadata)
+    }
+
+    async fn transition_storage_class(
+        &self,
+        key: &str,
+        target: StorageClass,
+        build: &RequestBuilder,
+    ) -> Result<bool, OssError> {
+        let metadata = self.get_object_metadata(key, build.clone()).await?;
+        let current = metadata
+            .storage_class()
+            .unwrap_or_else(|| StorageClass::Standard.to_string());
+        if current == target.to_string() {
+            return Ok(false);
+        }
+        let size = metadata
+            .content_length()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or_default();
+        let target = target.to_string();
+        if size > MAX_COPY_OBJECT_SIZE {
+            let build = multipart_copy_build(&metadata, build).oss_header_put("x-oss-storage-class", target.as_str());
+            let crc64 = metadata.crc64().and_then(|crc64| crc64.parse::<u64>().ok());
+            let copy_source = self.copy_source(key, &build);
+            self.multipart_copy(key, &copy_source, size, crc64, build).await?;
+            return Ok(true);
+        }
+        let build = build
+            .clone()
+            .oss_header_put("x-oss-storage-class", target.as_str())
+            .oss_header_put("x-oss-metadata-directive", "COPY");
+        self.copy_object(key, key, &build).await?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::entity::{CancelToken, CopyOptions, ObjectAcl, ResumeOptions, StorageClass};
+    use crate::error::{OssError, RenameStep};
+    use crate::worm::WormState;
+    use crate::mock_server;
+    use crate::oss::OSS;
+    use crate::request::RequestBuilder;
+
+    fn resumed_download_responses(resume_status: &str) -> Vec<Vec<u8>> {
+        vec![
+            b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\nETag: \"abc\"\r\nConnection: close\r\n\r\n01234".to_vec(),
+            format!(
+                "HTTP/1.1 {}\r\nContent-Length: 5\r\nContent-Range: bytes 5-9/10\r\nETag: \"abc\"\r\nConnection: close\r\n\r\n56789",
+                resume_status
+            )
+            .into_bytes(),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_get_object_to_file_resume() {
+        let (addr, server) = mock_server::serve(resumed_download_responses("206 Partial Content"));
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let file = std::env::temp_dir().join("aliyun_oss_async_resume_test.txt");
+        let resume = ResumeOptions::new().with_backoff(Duration::from_millis(1));
+        let size = oss
+            .get_object_to_file("/hello.txt", &file, RequestBuilder::new().with_cdn(addr), resume)
+            .await
+            .unwrap();
+        assert_eq!(size, 10);
+        assert_eq!(std::fs::read(&file).unwrap(), b"0123456789");
+        let requests = server.join().unwrap();
+        assert!(!requests[0].contains("range:"));
+        assert!(requests[1].contains("range: bytes=5-"));
+        assert!(requests[1].contains("if-match: \"abc\""));
+        std::fs::remove_file(file).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_object_to_writer_resume() {
+        let (addr, server) = mock_server::serve(resumed_download_responses("206 Partial Content"));
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let resume = ResumeOptions::new().with_backoff(Duration::from_millis(1));
+        let mut buffer = Vec::new();
+        let size = oss
+            .get_object_to_writer("/hello.txt", &mut buffer, RequestBuilder::new().with_cdn(addr), resume)
+            .await
+            .unwrap();
+        assert_eq!(size, 10);
+        assert_eq!(buffer, b"0123456789");
+        let requests = server.join().unwrap();
+        assert!(requests[1].contains("range: bytes=5-"));
+        assert!(requests[1].contains("if-match: \"abc\""));
+    }
+
+    #[tokio::test]
+    async fn test_get_object_to_file_changed() {
+        let (addr, server) = mock_server::serve(resumed_download_responses("412 Precondition Failed"));
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let file = std::env::temp_dir().join("aliyun_oss_async_changed_test.txt");
+        let resume = ResumeOptions::new().with_backoff(Duration::from_millis(1));
+        let result = oss
+            .get_object_to_file("/hello.txt", &file, RequestBuilder::new().with_cdn(addr), resume)
+            .await;
+        assert!(matches!(result, Err(OssError::ObjectChangedDuringDownload(_))));
+        server.join().unwrap();
+        assert!(!file.exists());
+    }
+
+    #[tokio::test]
+    async fn test_get_object_to_file_resume_disabled() {
+        let (addr, server) = mock_server::serve(resumed_download_responses("206 Partial Content")[..1].to_vec());
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let file = std::env::temp_dir().join("aliyun_oss_async_disabled_test.txt");
+        std::fs::write(&file, b"old").unwrap();
+        let result = oss
+            .get_object_to_file("/hello.txt", &file, RequestBuilder::new().with_cdn(addr), ResumeOptions::disabled())
+            .await;
+        assert!(matches!(result, Err(OssError::DownloadInterrupted { received: 5, .. })));
+        server.join().unwrap();
+        // 下载失败时不覆盖已有的文件，也不留下临时文件
+        assert_eq!(std::fs::read(&file).unwrap(), b"old");
+        assert!(!std::env::temp_dir().join("aliyun_oss_async_disabled_test.txt.part").exists());
+        std::fs::remove_file(file).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_object_to_file_cancelled() {
+        let (addr, server) = mock_server::serve(resumed_download_responses("206 Partial Content")[..1].to_vec());
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let file = std::env::temp_dir().join("aliyun_oss_async_cancelled_test.txt");
+        let token = CancelToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+        // 第一次响应中途断开，在续传等待期间取消
+        let resume = ResumeOptions::new().with_backoff(Duration::from_millis(500));
+        let build = RequestBuilder::new().with_cdn(addr).with_cancel_token(token.clone());
+        let result = oss.get_object_to_file("/hello.txt", &file, build.clone(), resume).await;
+        assert!(matches!(result, Err(OssError::Cancelled)));
+        assert_eq!(server.join().unwrap().len(), 1);
+        let result = oss.get_object_to_file("/hello.txt", &file, build, resume).await;
+        assert!(matches!(result, Err(OssError::Cancelled)));
+        assert!(!file.exists());
+    }
+
+    #[tokio::test]
+    async fn test_put_object_full_integrity() {
+        let crc64 = crate::util::md5_and_crc64(b"hello").1;
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", &format!("x-oss-hash-crc64ecma: {}\r\n", crc64), ""),
+            mock_server::response("200 OK", "x-oss-hash-crc64ecma: 1\r\n", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr).with_full_integrity();
+        oss.pub_object_from_buffer("/hello.txt", b"hello", build.clone()).await.unwrap();
+        let result = oss.pub_object_from_buffer("/hello.txt", b"hello", build).await;
+        assert!(matches!(result, Err(OssError::Crc64Mismatch { expected, actual: Some(_) }) if expected == crc64));
+        let requests = server.join().unwrap();
+        assert!(requests[0].contains("content-md5: xufakrxlkna5cz2rebffkg=="));
+    }
+
+    #[tokio::test]
+    async fn test_last_server_skew() {
+        let server_time = crate::date::http_date(chrono::Utc::now() - chrono::Duration::hours(1));
+        let (addr, server) =
+            mock_server::serve(vec![mock_server::response("200 OK", &format!("Date: {}\r\n", server_time), "hello")]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        assert_eq!(oss.last_server_skew(), None);
+        oss.get_object("/hello.txt", RequestBuilder::new().with_cdn(addr)).await.unwrap();
+        server.join().unwrap();
+        let skew = oss.clone().last_server_skew().unwrap();
+        assert!(skew > Duration::from_secs(3590) && skew < Duration::from_secs(3610));
+    }
+
+    #[tokio::test]
+    async fn test_delete_multiple_objects() {
+        let body = "<DeleteResult><Deleted><Key>a.txt</Key></Deleted><Deleted><Key>b.txt</Key></Deleted></DeleteResult>";
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "", body),
+            mock_server::response("200 OK", "", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr);
+        let deleted = oss.delete_multiple_objects(&["/a.txt", "/b.txt"], build.clone()).await.unwrap();
+        assert_eq!(deleted, vec!["a.txt", "b.txt"]);
+        let deleted = oss
+            .delete_multiple_objects(&["/a.txt", "/b.txt"], build.with_quiet_delete(true))
+            .await
+            .unwrap();
+        assert!(deleted.is_empty());
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("post /?delete "));
+        assert!(requests[0].contains("content-md5: "));
+        assert!(requests[0].contains("<quiet>false</quiet>"));
+        assert!(requests[1].contains("<quiet>true</quiet>"));
+    }
+
+    #[tokio::test]
+    async fn test_delete_by_prefix() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::xml_response("<ListBucketResult><Name>bucket</Name><IsTruncated>true</IsTruncated><NextContinuationToken>next</NextContinuationToken>\
+<Contents><Key>logs/a.txt</Key></Contents><Contents><Key>logs/b.txt</Key></Contents></ListBucketResult>"),
+            mock_server::xml_response("<ListBucketResult><Name>bucket</Name><IsTruncated>false</IsTruncated>\
+<Contents><Key>logs/c.txt</Key></Contents></ListBucketResult>"),
+            // 两批删除并发执行，顺序不确定，两个响应都只返回logs/a.txt
+            mock_server::xml_response("<DeleteResult><Deleted><Key>logs/a.txt</Key></Deleted></DeleteResult>"),
+            mock_server::xml_response("<DeleteResult><Deleted><Key>logs/a.txt</Key></Deleted></DeleteResult>"),
+            // 第二次调用重新列举，不使用缓存中已经删除的页
+            mock_server::xml_response("<ListBucketResult><Name>bucket</Name><IsTruncated>false</IsTruncated></ListBucketResult>"),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket")
+            .with_list_cache(Duration::from_secs(60), 16);
+        let build = RequestBuilder::new().with_cdn(addr).with_quiet_delete(true).with_concurrency(2);
+        let report = oss.delete_by_prefix("logs/", build.clone())
+            .await
+            .unwrap();
+        assert_eq!(report.deleted, 1);
+        let mut failed = report.failed.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>();
+        failed.sort();
+        assert_eq!(failed, vec!["logs/b.txt", "logs/c.txt"]);
+        let report = oss.delete_by_prefix("logs/", build)
+            .await.unwrap();
+        assert_eq!(report.deleted, 0);
+        assert!(report.failed.is_empty());
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("get /?list-type=2&max-keys=1000&prefix=logs%2f "));
+        assert!(requests[4].starts_with("get /?list-type=2&max-keys=1000&prefix=logs%2f "));
+        assert!(requests[1].contains("continuation-token=next"));
+        assert!(requests[2].starts_with("post /?delete "));
+        assert!(requests[2].contains("<quiet>false</quiet>"));
+    }
+
+    #[tokio::test]
+    async fn test_rename_object_partial() {
+        let ok = mock_server::response("200 OK", "", "");
+        let denied = "<Error><Code>AccessDenied</Code></Error>";
+        let (addr, server) = mock_server::serve(vec![
+            ok.clone(),
+            ok.clone(),
+            mock_server::response("403 Forbidden", "", denied),
+            ok,
+            mock_server::response("404 Not Found", "", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr);
+        match oss.rename_object("/b.txt", "/a.txt", build.clone()).await {
+            Err(OssError::PartialRename { step, source_key, target_key, .. }) => {
+                assert_eq!(step, RenameStep::DeleteSource);
+                assert_eq!(source_key, "/a.txt");
+                assert_eq!(target_key, "/b.txt");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        // 目标确认失败时不删除源对象
+        match oss.rename_object("/b.txt", "/a.txt", build).await {
+            Err(OssError::PartialRename { step, .. }) => assert_eq!(step, RenameStep::VerifyTarget),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("put /b.txt "));
+        assert!(requests[1].starts_with("head /b.txt "));
+        assert!(requests[2].starts_with("delete /a.txt "));
+        assert!(requests[4].starts_with("head /b.txt "));
+    }
+
+    #[tokio::test]
+    async fn test_get_object_retention() {
+        let worm = "<WormConfiguration><WormId>1666E2CFB2B3418</WormId><State>Locked</State>\
+<RetentionPeriodInDays>2</RetentionPeriodInDays><CreationDate>2020-10-15T15:50:32</CreationDate></WormConfiguration>";
+        let no_worm = "<Error><Code>NoSuchWORMConfiguration</Code></Error>";
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "", worm),
+            mock_server::response("200 OK", "Last-Modified: Fri, 16 Oct 2020 00:00:00 GMT\r\n", ""),
+            mock_server::response("404 Not Found", "", no_worm),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr);
+        let retention = oss.get_object_retention("/a.log", build.clone()).await.unwrap().unwrap();
+        assert_eq!(retention.mode, WormState::Locked);
+        assert_eq!(retention.retain_until.to_rfc3339(), "2020-10-18T00:00:00+00:00");
+        assert!(oss.get_object_retention("/a.log", build).await.unwrap().is_none());
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("get /?worm "));
+        assert!(requests[1].starts_with("head /a.log "));
+    }
+
+    #[tokio::test]
+    async fn test_head_objects() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "ETag: \"abc\"\r\n", ""),
+            mock_server::response("404 Not Found", "", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let results = oss
+            .head_objects(&["/a.txt", "/missing.txt"], 1, RequestBuilder::new().with_cdn(addr))
+            .await;
+        assert_eq!(results[0].0, "/a.txt");
+        assert_eq!(results[0].1.as_ref().unwrap().etag().as_deref(), Some("abc"));
+        assert_eq!(results[1].0, "/missing.txt");
+        assert!(results[1].1.is_err());
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_head_objects_zero_concurrency() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "ETag: \"abc\"\r\n", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let results = oss.head_objects(&["/a.txt"], 0, RequestBuilder::new().with_cdn(addr)).await;
+        assert!(results[0].1.is_ok());
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_object_verify_content_md5() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "Content-MD5: XUFAKrxLKna5cZ2REBfFkg==\r\n", "hello"),
+            mock_server::response("200 OK", "Content-MD5: XUFAKrxLKna5cZ2REBfFkg==\r\n", "hellO"),
+            mock_server::response("200 OK", "", "hellO"),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr).with_verify_content_md5();
+        assert_eq!(oss.get_object("/hello.txt", build.clone()).await.unwrap(), b"hello");
+        match oss.get_object("/hello.txt", build.clone()).await {
+            Err(OssError::ContentMd5Mismatch { expected, actual }) => {
+                assert_eq!(expected, "XUFAKrxLKna5cZ2REBfFkg==");
+                assert_ne!(actual, expected);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        // 没有Content-MD5时不校验
+        assert_eq!(oss.get_object("/hello.txt", build).await.unwrap(), b"hellO");
+        server.join().unwrap();
+    }
+
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[tokio::test]
+    async fn test_put_object_verify_sha256() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", &format!("x-oss-hash-sha256: {}\r\n", HELLO_SHA256.to_uppercase()), ""),
+            mock_server::response("200 OK", "x-oss-hash-sha256: 00\r\n", ""),
+            mock_server::response("200 OK", "", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr).with_verify_sha256();
+        oss.pub_object_from_buffer("/hello.txt", b"hello", build.clone()).await.unwrap();
+        match oss.pub_object_from_buffer("/hello.txt", b"hello", build.clone()).await {
+            Err(OssError::Sha256Mismatch { expected, actual }) => {
+                assert_eq!(expected, HELLO_SHA256);
+                assert_eq!(actual.as_deref(), Some("00"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        // 开启校验后没有返回x-oss-hash-sha256也当作不一致
+        let result = oss.pub_object_from_buffer("/hello.txt", b"hello", build).await;
+        assert!(matches!(result, Err(OssError::Sha256Mismatch { actual: None, .. })));
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_object_verify_sha256() {
+        let header = format!("x-oss-hash-sha256: {}\r\n", HELLO_SHA256);
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", &header, "hello"),
+            mock_server::response("200 OK", &header, "hellO"),
+            mock_server::response("200 OK", &header, "hello"),
+            mock_server::response("200 OK", &header, "hellO"),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr).with_verify_sha256();
+        assert_eq!(oss.get_object("/hello.txt", build.clone()).await.unwrap(), b"hello");
+        let result = oss.get_object("/hello.txt", build.clone()).await;
+        assert!(matches!(result, Err(OssError::Sha256Mismatch { .. })));
+        let mut buffer = Vec::new();
+        let size = oss
+            .get_object_to_writer("/hello.txt", &mut buffer, build.clone(), ResumeOptions::new())
+            .await
+            .unwrap();
+        assert_eq!(size, 5);
+        let result = oss
+            .get_object_to_writer("/hello.txt", &mut Vec::new(), build, ResumeOptions::new())
+            .await;
+        assert!(matches!(result, Err(OssError::Sha256Mismatch { expected, .. }) if expected != HELLO_SHA256));
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_append_or_create() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("404 Not Found", "", ""),
+            mock_server::response("200 OK", "x-oss-next-append-position: 6\r\n", ""),
+            b"HTTP/1.1 200 OK\r\nx-oss-object-type: Appendable\r\nContent-Length: 6\r\nConnection: close\r\n\r\n".to_vec(),
+            mock_server::response("200 OK", "x-oss-next-append-position: 11\r\n", ""),
+            b"HTTP/1.1 200 OK\r\nx-oss-object-type: Normal\r\nContent-Length: 6\r\nConnection: close\r\n\r\n".to_vec(),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr);
+        assert_eq!(oss.append_or_create("/log.txt", b"hello ", build.clone()).await.unwrap(), 6);
+        assert_eq!(oss.append_or_create("/log.txt", b"world", build.clone()).await.unwrap(), 11);
+        assert!(oss.append_or_create("/log.txt", b"!", build).await.is_err());
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("head /log.txt "));
+        assert!(requests[1].starts_with("post /log.txt?append&position=0 "));
+        assert!(requests[3].starts_with("post /log.txt?append&position=6 "));
+        assert!(requests[4].starts_with("head /log.txt "));
+    }
+
+    #[tokio::test]
+    async fn test_append_or_create_scoped() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("404 Not Found", "", ""),
+            mock_server::response("200 OK", "x-oss-next-append-position: 6\r\n", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket").scoped("tenants/a");
+        let build = RequestBuilder::new().with_cdn(addr);
+        assert_eq!(oss.append_or_create("/log.txt", b"hello ", build).await.unwrap(), 6);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("head /tenants/a/log.txt "));
+        assert!(requests[1].starts_with("post /tenants/a/log.txt?append&position=0 "));
+    }
+
+    #[tokio::test]
+    async fn test_append_position_not_equal_to_length() {
+        let body = "<Error><Code>PositionNotEqualToLength</Code><Message>Position is not equal to file length</Message></Error>";
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("409 Conflict", "x-oss-next-append-position: 11\r\n", body),
+            mock_server::response("200 OK", "x-oss-next-append-position: 12\r\n", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr);
+        let next_position = match oss.append_object("/log.txt", b"!", 6, build.clone()).await {
+            Err(OssError::PositionNotEqualToLength { next_position }) => next_position,
+            other => panic!("unexpected result: {:?}", other),
         };
+        assert_eq!(next_position, 11);
+        assert_eq!(oss.append_object("/log.txt", b"!", next_position, build).await.unwrap(), 12);
+        let requests = server.join().unwrap();
+        assert!(requests[1].starts_with("post /log.txt?append&position=11 "));
+    }
+
+    #[tokio::test]
+    async fn test_get_symlink_metadata_no_follow() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "x-oss-object-type: Symlink\r\nx-oss-symlink-target: target.txt\r\n", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr).with_no_follow_symlink();
+        let metadata = oss.get_object_metadata("/link.txt", build).await.unwrap();
+        assert!(metadata.is_symlink());
+        assert_eq!(metadata.symlink_target().as_deref(), Some("target.txt"));
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("head /link.txt?symlink "));
+    }
+
+    #[tokio::test]
+    async fn test_restore_object_then() {
+        let in_progress = "<Error><Code>RestoreAlreadyInProgress</Code></Error>";
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("409 Conflict", "", in_progress),
+            mock_server::response("200 OK", "x-oss-storage-class: Archive\r\nx-oss-restore: ongoing-request=\"true\"\r\n", ""),
+            mock_server::response("200 OK", "x-oss-storage-class: Archive\r\nx-oss-restore: ongoing-request=\"false\", expiry-date=\"Sun, 16 Apr 2017 08:12:33 GMT\"\r\n", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let (tx, rx) = std::sync::mpsc::channel();
+        let handle = oss
+            .restore_object_then("/archive.zip", RequestBuilder::new().with_cdn(addr), Duration::from_millis(10), move || {
+                tx.send(()).unwrap();
+            })
+            .await
+            .unwrap();
+        handle.await.unwrap().unwrap();
+        rx.try_recv().unwrap();
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("post /archive.zip?restore "));
+        assert!(requests[1].starts_with("head /archive.zip "));
+        assert!(requests[2].starts_with("head /archive.zip "));
+    }
+
+    #[tokio::test]
+    async fn test_restore_object_then_scoped() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("202 Accepted", "", ""),
+            mock_server::response("200 OK", "x-oss-storage-class: Archive\r\nx-oss-restore: ongoing-request=\"false\", expiry-date=\"Sun, 16 Apr 2017 08:12:33 GMT\"\r\n", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket").scoped("tenants/a");
+        let handle = oss
+            .restore_object_then("/archive.zip", RequestBuilder::new().with_cdn(addr), Duration::from_millis(10), || {})
+            .await
+            .unwrap();
+        handle.await.unwrap().unwrap();
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("post /tenants/a/archive.zip?restore "));
+        assert!(requests[1].starts_with("head /tenants/a/archive.zip "));
+    }
+
+    #[tokio::test]
+    async fn test_restore_poll_reuses_head() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "x-oss-storage-class: Archive\r\nx-oss-restore: ongoing-request=\"true\"\r\n", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr);
+        let cache_key = oss.restore_cache_key("/archive.zip", &build);
+        assert_eq!(cache_key, "bucket/archive.zip");
+        let other = oss.clone();
+        let first = oss.restore_poll("/archive.zip", &cache_key, &build, Duration::from_secs(60)).await.unwrap();
+        // 轮询间隔内的第二次查询复用结果，不再发起HEAD
+        let second = other.restore_poll("/archive.zip", &cache_key, &build, Duration::from_secs(60)).await.unwrap();
+        assert!(!first.is_readable());
+        assert_eq!(first.restore(), second.restore());
+        assert_eq!(server.join().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_change_storage_class_multipart_copy() {
+        let initiate = "<InitiateMultipartUploadResult><UploadId>upload-1</UploadId></InitiateMultipartUploadResult>";
+        let copy_part = |etag: &str| {
+            let body = format!("<CopyPartResult><ETag>\"{}\"</ETag></CopyPartResult>", etag);
+            mock_server::response("200 OK", "", &body)
+        };
+        let (addr, server) = mock_server::serve(vec![
+            b"HTTP/1.1 200 OK\r\nx-oss-storage-class: Standard\r\nContent-Type: text/plain\r\nx-oss-meta-app: demo\r\nContent-Length: 2147483649\r\nConnection: close\r\n\r\n".to_vec(),
+            mock_server::response("200 OK", "", initiate),
+            copy_part("e1"),
+            copy_part("e2"),
+            copy_part("e3"),
+            mock_server::response("200 OK", "", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let report = oss
+            .change_storage_class(&["/big.bin"], StorageClass::IA, 1, RequestBuilder::new().with_cdn(addr))
+            .await
+            .unwrap();
+        assert_eq!(report.succeeded, vec!["/big.bin"]);
+        let requests = server.join().unwrap();
+        assert!(requests[1].starts_with("post /big.bin?uploads "));
+        assert!(requests[1].contains("x-oss-storage-class: ia\r\n"));
+        assert!(requests[1].contains("content-type: text/plain\r\n"));
+        assert!(requests[1].contains("x-oss-meta-app: demo\r\n"));
+        assert!(requests[2].starts_with("put /big.bin?partnumber=1&uploadid=upload-1 "));
+        assert!(requests[2].contains("x-oss-copy-source: /bucket/big.bin\r\n"));
+        assert!(requests[2].contains("x-oss-copy-source-range: bytes=0-1073741823\r\n"));
+        assert!(!requests[2].contains("x-oss-storage-class"));
+        assert!(requests[4].contains("x-oss-copy-source-range: bytes=2147483648-2147483648\r\n"));
+        assert!(requests[5].starts_with("post /big.bin?uploadid=upload-1 "));
+        assert!(requests[5].contains("<partnumber>3</partnumber><etag>&quot;e3&quot;</etag>"));
+    }
+
+    #[tokio::test]
+    async fn test_object_acl_on_copy() {
+        let body = "<AccessControlPolicy><Owner><ID>0022012</ID></Owner><AccessControlList><Grant>public-read</Grant></AccessControlList></AccessControlPolicy>";
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "", ""),
+            mock_server::response("200 OK", "", body),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr);
+        oss.copy_object("/b.txt", "/a.txt", &build.clone().with_object_acl(ObjectAcl::PublicRead))
+            .await
+            .unwrap();
+        assert_eq!(oss.get_object_acl("/b.txt", build).await.unwrap(), ObjectAcl::PublicRead);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("put /b.txt "));
+        assert!(requests[0].contains("x-oss-object-acl: public-read"));
+        assert!(requests[1].starts_with("get /b.txt?acl "));
+    }
+
+    #[tokio::test]
+    async fn test_copy_object_with() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let options = CopyOptions::new().with_acl(ObjectAcl::Private);
+        oss.copy_object_with("/b.txt", "/a.txt", options, RequestBuilder::new().with_cdn(addr))
+            .await
+            .unwrap();
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("put /b.txt "));
+        assert!(requests[0].contains("x-oss-copy-source: /bucket/a.txt\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_content_type_round_trip() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "", ""),
+            mock_server::response("200 OK", "Content-Type: image/png\r\n", "png"),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_cdn(addr);
+        oss.pub_object_from_buffer("/a.png", b"png", build.clone().with_content_type("image/png"))
+            .await
+            .unwrap();
+        let (bytes, metadata) = oss.get_object_full("/a.png", build).await.unwrap();
+        assert_eq!(bytes, b"png");
+        assert_eq!(metadata.content_type().as_deref(), Some("image/png"));
+        let requests = server.join().unwrap();
+        assert!(requests[0].contains("content-type: image/png"));
     }
 }
@@ -1,9 +1,9 @@
 use base64::Engine;
 use base64::engine::general_purpose;
-use hmac::{Hmac, Mac};
 use reqwest::header::DATE;
-use crate::debug;
-use crate::oss::{API, OSS, OSSInfo};
+use crate::config::Credentials;
+use crate::{debug, util};
+use crate::oss::{API, OSS};
 use crate::request::{RequestBuilder};
 
 pub trait AuthAPI {
@@ -20,11 +20,29 @@ pub trait AuthAPI {
     ) -> String;
 }
 
-impl<'a> AuthAPI for OSS {
+impl AuthAPI for OSS {
     fn sign<S: AsRef<str>>(
         &self,
         key: S,
         build: &RequestBuilder,
+    ) -> String {
+        self.sign_with(&self.credentials(), key, build)
+    }
+
+    fn oss_sign<S: AsRef<str>>(&self, object: S, build: &RequestBuilder) -> String {
+        let credentials = self.credentials();
+        let sign_str_base64 = self.sign_with(&credentials, object, build);
+        format!("OSS {}:{}", credentials.key_id, sign_str_base64)
+    }
+}
+
+impl OSS {
+    /// 使用指定的凭证签名
+    pub(crate) fn sign_with<S: AsRef<str>>(
+        &self,
+        credentials: &Credentials,
+        key: S,
+        build: &RequestBuilder,
     ) -> String {
         let date = build
             .headers
@@ -38,29 +56,32 @@ impl<'a> AuthAPI for OSS {
 
         oss_headers.sort_by(|a, b| a.0.cmp(&b.0));
 
-        let mut canonicalized_oss_headers = oss_headers
+        // 每个header后面都要跟一个换行
+        let canonicalized_oss_headers = oss_headers
             .iter()
-            .map(|(k, v)| format!("{}:{}", k, v))
-            .collect::<Vec<_>>()
-            .join("\n");
+            .map(|(k, v)| format!("{}:{}\n", k, v.trim()))
+            .collect::<String>();
 
-        if oss_headers.len() == 1 {
-            canonicalized_oss_headers = format!("{}\n", canonicalized_oss_headers);
-        }
-
-        let mut canonicalized_resource = self.format_oss_resource_str(self.bucket().as_str(), key.as_ref());
-        if build.parameters.len() > 0 {
+        let bucket = build.bucket.as_deref().unwrap_or(self.bucket_str());
+        let mut canonicalized_resource = self.format_oss_resource_str(bucket, key.as_ref());
+        if !build.parameters.is_empty() {
             let mut params = build
                 .parameters
                 .iter()
                 .collect::<Vec<_>>();
-            params.sort_by(|a, b| a.0.cmp(&b.0));
+            params.sort_by(|a, b| a.0.cmp(b.0));
             canonicalized_resource = format!(
                 "{}?{}",
                 canonicalized_resource,
                 params
                     .into_iter()
-                    .map(|(k, v)| format!("{}={}", k, v))
+                    .map(|(k, v)| {
+                        if v.is_empty() {
+                            k.to_string()
+                        } else {
+                            format!("{}={}", k, v)
+                        }
+                    })
                     .collect::<Vec<_>>()
                     .join("&")
             );
@@ -79,14 +100,82 @@ impl<'a> AuthAPI for OSS {
             canonicalized_resource,
         );
         debug!("oss logsign_str: {}", sign_str);
-        let mut hasher: Hmac<sha1::Sha1> = Hmac::new_from_slice(self.key_secret().as_bytes()).unwrap();
-        hasher.update(sign_str.as_bytes());
+        let signature = util::hmac_sha1(credentials.key_secret.as_bytes(), sign_str.as_bytes());
+        general_purpose::STANDARD.encode(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+    use base64::engine::general_purpose;
+    use reqwest::header::DATE;
+    use crate::auth::AuthAPI;
+    use crate::oss::OSS;
+    use crate::util;
+    use crate::request::{RequestBuilder, RequestType};
 
-        general_purpose::STANDARD.encode(&hasher.finalize().into_bytes())
+    #[test]
+    fn test_sign_with_multiple_oss_headers() {
+        let oss = OSS::new("44CF9590006BF252F707", "OtxrzxIsfpFjA7SwPzILwy8Bw21TLhquhboDYROV", "oss-cn-hangzhou.aliyuncs.com", "oss-example");
+        let mut build = RequestBuilder::new()
+            .with_content_type("text/html")
+            .oss_header_put("X-OSS-Meta-Author", "foo@example.com")
+            .oss_header_put("X-OSS-Magic", "abracadabra");
+        build.method = RequestType::Put;
+        build.content_md5 = Some("ODBGOERFMDMzQTczRUY3NUE3NzA5QzdFNUYzMDQxNEM=".to_string());
+        build.headers.insert(DATE.to_string(), "Thu, 17 Nov 2005 18:49:58 GMT".to_string());
+        // PUT\nODBGOERFMDMzQTczRUY3NUE3NzA5QzdFNUYzMDQxNEM=\ntext/html\nThu, 17 Nov 2005 18:49:58 GMT\n
+        // x-oss-magic:abracadabra\nx-oss-meta-author:foo@example.com\n/oss-example/nelson
+        assert_eq!(oss.sign("/nelson", &build), "fV5fq7DPwNbrrig7nvUSZIVyruI=");
     }
 
-    fn oss_sign<S: AsRef<str>>(&self, object: S, build: &RequestBuilder) -> String {
-        let sign_str_base64 = self.sign(object, build);
-        format!("OSS {}:{}", self.key_id(), sign_str_base64)
+    #[test]
+    fn test_sign_ignores_query() {
+        let oss = OSS::new("44CF9590006BF252F707", "OtxrzxIsfpFjA7SwPzILwy8Bw21TLhquhboDYROV", "oss-cn-hangzhou.aliyuncs.com", "oss-example");
+        let mut build = RequestBuilder::new();
+        build.headers.insert(DATE.to_string(), "Thu, 17 Nov 2005 18:49:58 GMT".to_string());
+        let listed = build
+            .clone()
+            .query_put("list-type", "2")
+            .with_prefix("dir/")
+            .with_start_after("dir/a.txt")
+            .with_max_keys(100);
+        assert_eq!(oss.sign("/", &listed), oss.sign("/", &build));
+        let next_page = build.clone().with_continuation_token("ChR0ZXN0");
+        assert_ne!(oss.sign("/", &next_page), oss.sign("/", &build));
+    }
+
+    #[test]
+    fn test_sign_with_custom_signed_header() {
+        let oss = OSS::new("44CF9590006BF252F707", "OtxrzxIsfpFjA7SwPzILwy8Bw21TLhquhboDYROV", "oss-cn-hangzhou.aliyuncs.com", "oss-example");
+        let mut build = RequestBuilder::new()
+            .oss_header_put("x-oss-date", "Thu, 17 Nov 2005 18:49:58 GMT")
+            .with_signed_header("X-Oss-Custom-Feature", " enabled ")
+            .unwrap();
+        build.headers.insert(DATE.to_string(), "Thu, 17 Nov 2005 18:49:58 GMT".to_string());
+        let expected = general_purpose::STANDARD.encode(util::hmac_sha1(
+            b"OtxrzxIsfpFjA7SwPzILwy8Bw21TLhquhboDYROV",
+            b"GET\n\n\nThu, 17 Nov 2005 18:49:58 GMT\nx-oss-custom-feature:enabled\nx-oss-date:Thu, 17 Nov 2005 18:49:58 GMT\n/oss-example/a.txt",
+        ));
+        assert_eq!(oss.sign("/a.txt", &build), expected);
+        let (_, headers) = oss.build_request("/a.txt", build).unwrap();
+        assert_eq!(headers.get("x-oss-custom-feature").unwrap(), " enabled ");
+        assert!(RequestBuilder::new().with_signed_header("X-Custom-Feature", "enabled").is_err());
+    }
+
+    #[test]
+    fn test_sign_put_with_content_type() {
+        let oss = OSS::new("44CF9590006BF252F707", "OtxrzxIsfpFjA7SwPzILwy8Bw21TLhquhboDYROV", "oss-cn-hangzhou.aliyuncs.com", "oss-example");
+        let mut build = RequestBuilder::new().with_content_type("image/jpeg");
+        build.method = RequestType::Put;
+        build.headers.insert(DATE.to_string(), "Thu, 17 Nov 2005 18:49:58 GMT".to_string());
+        let expected = general_purpose::STANDARD.encode(util::hmac_sha1(
+            b"OtxrzxIsfpFjA7SwPzILwy8Bw21TLhquhboDYROV",
+            b"PUT\n\nimage/jpeg\nThu, 17 Nov 2005 18:49:58 GMT\n/oss-example/a.jpg",
+        ));
+        assert_eq!(oss.sign("/a.jpg", &build), expected);
+        build.content_type = None;
+        assert_ne!(oss.sign("/a.jpg", &build), expected);
     }
 }
@@ -0,0 +1,715 @@
+use std::time::{Duration, Instant};
+use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+use crate::bucket_config::{
+    CorsConfiguration, LifecycleConfiguration, LoggingConfiguration, RefererConfiguration, WebsiteConfiguration,
+};
+use crate::debug;
+use crate::error::OssError;
+use crate::list::{decode_list_body, ListObjectsResult, ObjectSummary};
+use crate::fetch::{task_id_from_xml, AsyncFetchSpec, AsyncFetchTaskInfo};
+use crate::oss::OSS;
+use crate::request::{RequestBuilder, RequestType};
+use crate::stat::BucketStat;
+use crate::worm::WormConfiguration;
+use crate::xml::{element_text, XmlWriter};
+
+impl OSS {
+    /// 开启或关闭bucket的传输加速
+    ///
+    /// 开启后需要把endpoint配置为`oss-accelerate.aliyuncs.com`才能走加速域名访问，开启后约30分钟生效
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let builder = RequestBuilder::new();
+    /// oss.put_bucket_transfer_acceleration(true, builder).unwrap();
+    /// ```
+    pub fn put_bucket_transfer_acceleration(&self, enabled: bool, build: RequestBuilder) -> Result<(), OssError> {
+        let mut build = build.parameters_put("transferAcceleration", "");
+        build.method = RequestType::Put;
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("put bucket transfer acceleration: {} headers: {:?}", url, headers);
+        let mut xml = XmlWriter::new();
+        xml.start("TransferAccelerationConfiguration")
+            .element("Enabled", enabled.to_string());
+        let body = xml.finish();
+        let response = client.put(url)
+            .headers(headers)
+            .body(body)
+            .send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("put bucket transfer acceleration status: {} error: {}", status, result);
+            Err(OssError::from_response("put bucket transfer acceleration", status, result))
+        }
+    }
+
+    /// 获取bucket是否开启了传输加速
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let builder = RequestBuilder::new();
+    /// let enabled = oss.get_bucket_transfer_acceleration(builder).unwrap();
+    /// println!("transfer acceleration: {}", enabled);
+    /// ```
+    pub fn get_bucket_transfer_acceleration(&self, build: RequestBuilder) -> Result<bool, OssError> {
+        let build = build.parameters_put("transferAcceleration", "");
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("get bucket transfer acceleration: {} headers: {:?}", url, headers);
+        let response = client.get(url)
+            .headers(headers)
+            .send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text()?;
+            let enabled = element_text(&result, "Enabled")
+                .ok_or_else(|| OssError::Err(format!("invalid transfer acceleration xml: {}", result)))?;
+            Ok(enabled == "true")
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("get bucket transfer acceleration status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket transfer acceleration", status, result))
+        }
+    }
+
+    /// 创建异步拉取任务，OSS会从指定url拉取文件保存到bucket中，返回TaskId
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::fetch::AsyncFetchSpec;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let spec = AsyncFetchSpec::new("https://cdn.example.com/a.png", "/images/a.png");
+    /// let task_id = oss.create_async_fetch_task(spec, RequestBuilder::new()).unwrap();
+    /// println!("task id: {}", task_id);
+    /// ```
+    pub fn create_async_fetch_task(
+        &self,
+        spec: AsyncFetchSpec,
+        build: RequestBuilder,
+    ) -> Result<String, OssError> {
+        let mut build = build.parameters_put("asyncFetch", "");
+        build.method = RequestType::Post;
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: create async fetch task: {} headers: {:?}", url, headers);
+        let response = client.post(url).headers(headers).body(spec.to_xml()).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text()?;
+            task_id_from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: create async fetch task status: {} error: {}", status, result);
+            Err(OssError::from_response("create async fetch task", status, result))
+        }
+    }
+
+    /// 查询异步拉取任务的状态
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let info = oss.get_async_fetch_task("task id", RequestBuilder::new()).unwrap();
+    /// println!("state: {}", info.state);
+    /// ```
+    pub fn get_async_fetch_task<S: AsRef<str>>(
+        &self,
+        task_id: S,
+        build: RequestBuilder,
+    ) -> Result<AsyncFetchTaskInfo, OssError> {
+        let build = build
+            .parameters_put("asyncFetch", "")
+            .oss_header_put("x-oss-task-id", task_id.as_ref());
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get async fetch task: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text()?;
+            AsyncFetchTaskInfo::from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: get async fetch task status: {} error: {}", status, result);
+            Err(OssError::from_response("get async fetch task", status, result))
+        }
+    }
+
+    /// 创建异步拉取任务并每隔`interval`查询一次，直到任务成功或失败，超过`timeout`返回错误
+    ///
+    /// 任务失败时返回的[AsyncFetchTaskInfo]中包含失败原因
+    /// # 使用例子
+    /// ```rust
+    /// use std::time::Duration;
+    /// use aliyun_oss_rust_sdk::fetch::AsyncFetchSpec;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let spec = AsyncFetchSpec::new("https://cdn.example.com/a.png", "/images/a.png");
+    /// let info = oss
+    ///     .fetch_and_wait(spec, RequestBuilder::new(), Duration::from_secs(2), Duration::from_secs(300))
+    ///     .unwrap();
+    /// println!("state: {} error: {:?}", info.state, info.error_msg);
+    /// ```
+    pub fn fetch_and_wait(
+        &self,
+        spec: AsyncFetchSpec,
+        build: RequestBuilder,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<AsyncFetchTaskInfo, OssError> {
+        let start = Instant::now();
+        let task_id = self.create_async_fetch_task(spec, build.clone())?;
+        loop {
+            let info = self.get_async_fetch_task(&task_id, build.clone())?;
+            if info.state.is_terminal() {
+                return Ok(info);
+            }
+            if start.elapsed() + interval > timeout {
+                return Err(OssError::Err(format!(
+                    "async fetch task {} timeout, state: {}",
+                    task_id, info.state
+                )));
+            }
+            std::thread::sleep(interval);
+        }
+    }
+
+    /// 检查AccessKey、endpoint和bucket配置是否可用，适合在应用启动时调用
+    ///
+    /// 失败时返回[OssError::CheckFailed]说明具体原因，endpoint地域不对时返回[OssError::RegionMismatch]
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// oss.check(&RequestBuilder::new()).unwrap();
+    /// ```
+    pub fn check(&self, build: &RequestBuilder) -> Result<(), OssError> {
+        let build = build.clone().parameters_put("bucketInfo", "");
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: check: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: check status: {} error: {}", status, result);
+            Err(OssError::check_failed(status, result))
+        }
+    }
+
+    /// 列举bucket中的对象(ListObjectsV2)，每次返回一页
+    ///
+    /// 通过[RequestBuilder::with_prefix]、[RequestBuilder::with_max_keys]、[RequestBuilder::with_start_after]、
+    /// [RequestBuilder::with_continuation_token]等控制列举范围
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let build = RequestBuilder::new()
+    ///     .with_prefix("logs/")
+    ///     .with_start_after("logs/2024-01-01.log")
+    ///     .with_max_keys(100);
+    /// let result = oss.list_objects(build).unwrap();
+    /// for object in result.objects {
+    ///     println!("{} {}", object.key, object.size);
+    /// }
+    /// ```
+    pub fn list_objects(&self, build: RequestBuilder) -> Result<ListObjectsResult, OssError> {
+        self.list_objects_page(build, true)
+    }
+
+    /// 列举一页对象，`use_cache`为false时不读也不写列举缓存，用于[OSS::delete_by_prefix]这类会改变列举结果的操作
+    pub(crate) fn list_objects_page(&self, build: RequestBuilder, use_cache: bool) -> Result<ListObjectsResult, OssError> {
+        let build = self.scope_list_query(build.query_put("list-type", "2"));
+        let raw_response = build.raw_response;
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        let list_cache = self.list_cache.as_ref().filter(|_| use_cache);
+        // 需要原始响应时缓存中没有body，直接请求
+        let cached = if raw_response { None } else { list_cache.and_then(|cache| cache.get(&url)) };
+        if let Some(result) = cached {
+            debug!("oss log: list objects from cache: {}", url);
+            return Ok(result.unscope(|key| self.unscope_key(key)));
+        }
+        let cache_key = url.clone();
+        self.acquire_rate_limit();
+        debug!("oss log: list objects: {} headers: {:?}", url, headers);
+        let response = client
+            .get(url)
+            .headers(headers)
+            .header(ACCEPT_ENCODING, "gzip")
+            .send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let content_encoding = response
+                .headers()
+                .get(CONTENT_ENCODING)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string());
+            let body = response.bytes()?;
+            let result = decode_list_body(content_encoding.as_deref(), &body)?;
+            let mut list = ListObjectsResult::from_xml(&result)?;
+            // 缓存由不同作用域的clone共享，缓存原始结果，取出后再按当前作用域去掉前缀
+            if let Some(cache) = list_cache {
+                cache.insert(cache_key, list.clone());
+            }
+            if raw_response {
+                list.raw = Some(result);
+            }
+            Ok(list.unscope(|key| self.unscope_key(key)))
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: list objects status: {} error: {}", status, result);
+            Err(OssError::from_response("list objects", status, result))
+        }
+    }
+
+    /// 填充列举结果中对象的标签数量([ObjectSummary::tagging_count])
+    ///
+    /// ListObjectsV2不返回标签数量，这里对每个对象发起一次HEAD请求([OSS::head_objects])读取`x-oss-tagging-count`，
+    /// 最多同时使用`concurrency`个线程，没有标签的对象填充为`Some(0)`。
+    /// `concurrency`为0时按1处理。单个对象失败不会中断其它对象，失败的对象保持None，返回第一个错误
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let mut result = oss.list_objects(RequestBuilder::new().with_prefix("images/")).unwrap();
+    /// oss.fill_tagging_count(&mut result.objects, 8, RequestBuilder::new()).unwrap();
+    /// for object in result.objects {
+    ///     println!("{} tags: {:?}", object.key, object.tagging_count);
+    /// }
+    /// ```
+    pub fn fill_tagging_count(&self, objects: &mut [ObjectSummary], concurrency: usize, build: RequestBuilder) -> Result<(), OssError> {
+        let keys = objects.iter().map(|object| object.key.to_string()).collect::<Vec<_>>();
+        let results = self.head_objects(&keys, concurrency.max(1), build);
+        let mut first_error = None;
+        for (object, (_, result)) in objects.iter_mut().zip(results) {
+            match result {
+                Ok(metadata) => object.tagging_count = Some(metadata.tagging_count().unwrap_or(0)),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// 获取bucket的存储统计信息，可配合[crate::stat::estimate_monthly_cost]估算存储费用
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let stat = oss.get_bucket_stat(RequestBuilder::new()).unwrap();
+    /// println!("objects: {} storage: {}", stat.object_count, stat.storage);
+    /// ```
+    pub fn get_bucket_stat(&self, build: RequestBuilder) -> Result<BucketStat, OssError> {
+        let build = build.parameters_put("stat", "");
+        let raw_response = build.raw_response;
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket stat: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text()?;
+            let mut stat = BucketStat::from_xml(&result)?;
+            if raw_response {
+                stat.raw = Some(result);
+            }
+            Ok(stat)
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: get bucket stat status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket stat", status, result))
+        }
+    }
+
+    /// 获取bucket的合规保留策略(WORM)，只支持读取，创建、锁定和延长保留策略暂不支持
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let worm = oss.get_bucket_worm(RequestBuilder::new()).unwrap();
+    /// println!("state: {} days: {}", worm.state, worm.retention_period_in_days);
+    /// ```
+    pub fn get_bucket_worm(&self, build: RequestBuilder) -> Result<WormConfiguration, OssError> {
+        let build = build.parameters_put("worm", "");
+        let raw_response = build.raw_response;
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket worm: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text()?;
+            let mut worm = WormConfiguration::from_xml(&result)?;
+            if raw_response {
+                worm.raw = Some(result);
+            }
+            Ok(worm)
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: get bucket worm status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket worm", status, result))
+        }
+    }
+
+    /// 设置bucket的静态网站托管，访问目录时返回默认主页，对象不存在时返回404页
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::bucket_config::WebsiteConfiguration;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let website = WebsiteConfiguration::new("index.html").with_error_document("error.html");
+    /// oss.put_bucket_website(&website, RequestBuilder::new()).unwrap();
+    /// ```
+    pub fn put_bucket_website(
+        &self,
+        website: &WebsiteConfiguration,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("website", "");
+        build.method = RequestType::Put;
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: put bucket website: {} headers: {:?}", url, headers);
+        let response = client.put(url).headers(headers).body(website.to_xml()).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: put bucket website status: {} error: {}", status, result);
+            Err(OssError::from_response("put bucket website", status, result))
+        }
+    }
+
+    /// 获取bucket的静态网站托管配置，没有配置时返回NoSuchWebsiteConfiguration错误
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let website = oss.get_bucket_website(RequestBuilder::new()).unwrap();
+    /// println!("index: {}", website.index_document);
+    /// ```
+    pub fn get_bucket_website(&self, build: RequestBuilder) -> Result<WebsiteConfiguration, OssError> {
+        let build = build.parameters_put("website", "");
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket website: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text()?;
+            WebsiteConfiguration::from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: get bucket website status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket website", status, result))
+        }
+    }
+
+    /// 设置bucket的防盗链(Referer白名单)
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::bucket_config::RefererConfiguration;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let referer = RefererConfiguration::new(false).with_referer("https://*.example.com");
+    /// oss.put_bucket_referer(&referer, RequestBuilder::new()).unwrap();
+    /// ```
+    pub fn put_bucket_referer(
+        &self,
+        referer: &RefererConfiguration,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("referer", "");
+        build.method = RequestType::Put;
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: put bucket referer: {} headers: {:?}", url, headers);
+        let response = client.put(url).headers(headers).body(referer.to_xml()).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: put bucket referer status: {} error: {}", status, result);
+            Err(OssError::from_response("put bucket referer", status, result))
+        }
+    }
+
+    /// 获取bucket的防盗链配置
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let referer = oss.get_bucket_referer(RequestBuilder::new()).unwrap();
+    /// println!("referers: {:?}", referer.referers);
+    /// ```
+    pub fn get_bucket_referer(&self, build: RequestBuilder) -> Result<RefererConfiguration, OssError> {
+        let build = build.parameters_put("referer", "");
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket referer: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text()?;
+            RefererConfiguration::from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: get bucket referer status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket referer", status, result))
+        }
+    }
+
+    /// 开启bucket的访问日志，日志写入`target_bucket`，目标bucket需要和当前bucket在同一地域
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::bucket_config::LoggingConfiguration;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let logging = LoggingConfiguration::new("log-bucket").with_target_prefix("logs/");
+    /// oss.put_bucket_logging(&logging, RequestBuilder::new()).unwrap();
+    /// ```
+    pub fn put_bucket_logging(
+        &self,
+        logging: &LoggingConfiguration,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("logging", "");
+        build.method = RequestType::Put;
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: put bucket logging: {} headers: {:?}", url, headers);
+        let response = client.put(url).headers(headers).body(logging.to_xml()).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: put bucket logging status: {} error: {}", status, result);
+            Err(OssError::from_response("put bucket logging", status, result))
+        }
+    }
+
+    /// 获取bucket的访问日志配置，没有开启时返回None
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let logging = oss.get_bucket_logging(RequestBuilder::new()).unwrap();
+    /// println!("logging: {:?}", logging);
+    /// ```
+    pub fn get_bucket_logging(&self, build: RequestBuilder) -> Result<Option<LoggingConfiguration>, OssError> {
+        let build = build.parameters_put("logging", "");
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket logging: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text()?;
+            LoggingConfiguration::from_logging_status(&result)
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: get bucket logging status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket logging", status, result))
+        }
+    }
+
+    /// 设置bucket的跨域资源共享(CORS)规则，会覆盖已有的全部规则
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::bucket_config::{CorsConfiguration, CorsRule};
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let cors = CorsConfiguration::new()
+    ///     .with_rule(CorsRule::new().with_allowed_origin("*").with_allowed_method("GET"));
+    /// oss.put_bucket_cors(&cors, RequestBuilder::new()).unwrap();
+    /// ```
+    pub fn put_bucket_cors(
+        &self,
+        cors: &CorsConfiguration,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("cors", "");
+        build.method = RequestType::Put;
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: put bucket cors: {} headers: {:?}", url, headers);
+        let response = client.put(url).headers(headers).body(cors.to_xml()).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: put bucket cors status: {} error: {}", status, result);
+            Err(OssError::from_response("put bucket cors", status, result))
+        }
+    }
+
+    /// 获取bucket的跨域资源共享(CORS)规则，没有配置时返回NoSuchCORSConfiguration错误
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let cors = oss.get_bucket_cors(RequestBuilder::new()).unwrap();
+    /// println!("rules: {}", cors.rules.len());
+    /// ```
+    pub fn get_bucket_cors(&self, build: RequestBuilder) -> Result<CorsConfiguration, OssError> {
+        let build = build.parameters_put("cors", "");
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket cors: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text()?;
+            CorsConfiguration::from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: get bucket cors status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket cors", status, result))
+        }
+    }
+
+    /// 设置bucket的生命周期规则，会覆盖已有的全部规则
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::bucket_config::{LifecycleConfiguration, LifecycleRule};
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let lifecycle = LifecycleConfiguration::new()
+    ///     .with_rule(LifecycleRule::new("logs", "logs/").with_expiration_days(30));
+    /// oss.put_bucket_lifecycle(&lifecycle, RequestBuilder::new()).unwrap();
+    /// ```
+    pub fn put_bucket_lifecycle(
+        &self,
+        lifecycle: &LifecycleConfiguration,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("lifecycle", "");
+        build.method = RequestType::Put;
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: put bucket lifecycle: {} headers: {:?}", url, headers);
+        let response = client.put(url).headers(headers).body(lifecycle.to_xml()).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: put bucket lifecycle status: {} error: {}", status, result);
+            Err(OssError::from_response("put bucket lifecycle", status, result))
+        }
+    }
+
+    /// 获取bucket的生命周期规则，没有配置时返回NoSuchLifecycle错误
+    ///
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let lifecycle = oss.get_bucket_lifecycle(RequestBuilder::new()).unwrap();
+    /// println!("rules: {}", lifecycle.rules.len());
+    /// ```
+    pub fn get_bucket_lifecycle(&self, build: RequestBuilder) -> Result<LifecycleConfiguration, OssError> {
+        let build = build.parameters_put("lifecycle", "");
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_bucket_request(build)?;
+        debug!("oss log: get bucket lifecycle: {} headers: {:?}", url, headers);
+        let response = client.get(url).headers(headers).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text()?;
+            LifecycleConfiguration::from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: get bucket lifecycle status: {} error: {}", status, result);
+            Err(OssError::from_response("get bucket lifecycle", status, result))
+        }
+    }
+}
@@ -1 +1,20 @@
-mod object;
\ No newline at end of file
+use crate::oss::OSS;
+
+mod bucket;
+mod object;
+mod multipart;
+mod transfer;
+pub mod reader;
+pub mod writer;
+
+impl OSS {
+    /// 等待限流器放行，未设置限流时直接返回
+    pub(crate) fn acquire_rate_limit(&self) {
+        if let Some(limiter) = &self.rate_limiter {
+            let wait = limiter.reserve();
+            if !wait.is_zero() {
+                std::thread::sleep(wait);
+            }
+        }
+    }
+}
@@ -0,0 +1,394 @@
+use std::io::Read;
+use crate::debug;
+use crate::error::OssError;
+use crate::multipart::{adaptive_part_size, check_part_size, combined_crc64, complete_multipart_xml, copy_part_etag_from_xml, copy_ranges, read_part, upload_id_from_xml, uploaded_part, UploadedPart, COPY_PART_SIZE, MAX_PARTS};
+use crate::util::verify_crc64;
+use crate::oss::{API, OSS};
+use crate::request::{RequestBuilder, RequestType};
+
+impl OSS {
+    /// 初始化分片上传，返回UploadId
+    ///
+    /// 对象的Content-Type、访问权限、元数据等在这里设置，之后的分片请求不需要再设置
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let upload_id = oss.initiate_multipart_upload("/big.bin", RequestBuilder::new()).unwrap();
+    /// println!("upload id: {}", upload_id);
+    /// ```
+    pub fn initiate_multipart_upload<S: AsRef<str>>(
+        &self,
+        key: S,
+        build: RequestBuilder,
+    ) -> Result<String, OssError> {
+        let mut build = build.parameters_put("uploads", "");
+        build.method = RequestType::Post;
+        let key = self.format_key(key);
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: initiate multipart upload: {} headers: {:?}", url, headers);
+        let response = client.post(url).headers(headers).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let result = response.text()?;
+            upload_id_from_xml(&result)
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: initiate multipart upload status: {} error: {}", status, result);
+            Err(OssError::from_response("initiate multipart upload", status, result))
+        }
+    }
+
+    /// 上传一个分片，`part_number`从1开始，返回分片的ETag
+    ///
+    /// 除最后一个分片外，分片大小不能小于[MIN_PART_SIZE](crate::multipart::MIN_PART_SIZE)
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let etag = oss.upload_part("/big.bin", "upload id", 1, vec![0; 100 * 1024], RequestBuilder::new()).unwrap();
+    /// println!("etag: {}", etag);
+    /// ```
+    pub fn upload_part<S: AsRef<str>>(
+        &self,
+        key: S,
+        upload_id: &str,
+        part_number: u32,
+        data: Vec<u8>,
+        build: RequestBuilder,
+    ) -> Result<String, OssError> {
+        let mut build = build
+            .parameters_put("partNumber".to_string(), part_number.to_string())
+            .parameters_put("uploadId", upload_id);
+        build.method = RequestType::Put;
+        let key = self.format_key(key);
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: upload part: {} headers: {:?}", url, headers);
+        let response = client.put(url).headers(headers).body(data).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            let etag = response
+                .headers()
+                .get("etag")
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.trim_matches('"').to_string())
+                .ok_or_else(|| OssError::Err("upload part response has no ETag".to_string()))?;
+            Ok(etag)
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: upload part status: {} error: {}", status, result);
+            Err(OssError::from_response("upload part", status, result))
+        }
+    }
+
+    /// 完成分片上传，`parts`需要按分片号从小到大排列
+    ///
+    /// 设置了[RequestBuilder::with_verify_on_complete]时校验对象的CRC64，每个分片都需要有CRC64
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::multipart::UploadedPart;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let parts = vec![UploadedPart::new(1, "etag")];
+    /// oss.complete_multipart_upload("/big.bin", "upload id", &parts, RequestBuilder::new()).unwrap();
+    /// ```
+    pub fn complete_multipart_upload<S: AsRef<str>>(
+        &self,
+        key: S,
+        upload_id: &str,
+        parts: &[UploadedPart],
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let expected = if build.verify_on_complete { Some(combined_crc64(parts)?) } else { None };
+        self.complete_multipart_upload_crc64(key, upload_id, parts, expected, build)
+    }
+
+    /// 完成分片上传，`expected`不为None时和响应中的CRC64比较
+    fn complete_multipart_upload_crc64<S: AsRef<str>>(
+        &self,
+        key: S,
+        upload_id: &str,
+        parts: &[UploadedPart],
+        expected: Option<u64>,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("uploadId", upload_id);
+        build.method = RequestType::Post;
+        let key = self.format_key(key);
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: complete multipart upload: {} headers: {:?}", url, headers);
+        let response = client
+            .post(url)
+            .headers(headers)
+            .body(complete_multipart_xml(parts))
+            .send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            verify_crc64(expected, response.headers())
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: complete multipart upload status: {} error: {}", status, result);
+            Err(OssError::from_response("complete multipart upload", status, result))
+        }
+    }
+
+    /// 拷贝源对象`source_key`的`range`字节范围(包含两端)作为一个分片(UploadPartCopy)，返回分片的ETag
+    ///
+    /// 用于拷贝超过1GB、不能使用CopyObject的对象，源对象默认在同一个bucket
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let etag = oss.upload_part_copy("/big_copy.bin", "upload id", 1, "/big.bin", (0, 1024 * 1024 - 1), RequestBuilder::new()).unwrap();
+    /// println!("etag: {}", etag);
+    /// ```
+    pub fn upload_part_copy<S: AsRef<str>>(
+        &self,
+        key: S,
+        upload_id: &str,
+        part_number: u32,
+        source_key: S,
+        range: (u64, u64),
+        build: RequestBuilder,
+    ) -> Result<String, OssError> {
+        let copy_source = self.copy_source(source_key, &build);
+        self.upload_part_copy_from(key, upload_id, part_number, &copy_source, range, build)
+    }
+
+    /// 同[OSS::upload_part_copy]，`copy_source`为已经编码好的`/bucket/key`，可以来自其它bucket
+    pub(crate) fn upload_part_copy_from<S: AsRef<str>>(
+        &self,
+        key: S,
+        upload_id: &str,
+        part_number: u32,
+        copy_source: &str,
+        range: (u64, u64),
+        build: RequestBuilder,
+    ) -> Result<String, OssError> {
+        let mut build = build
+            .parameters_put("partNumber".to_string(), part_number.to_string())
+            .parameters_put("uploadId".to_string(), upload_id.to_string())
+            .oss_header_put("x-oss-copy-source", copy_source)
+            .oss_header_put("x-oss-copy-source-range".to_string(), format!("bytes={}-{}", range.0, range.1));
+        build.method = RequestType::Put;
+        let key = self.format_key(key);
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: upload part copy: {} headers: {:?}", url, headers);
+        let response = client.put(url).headers(headers).send()?;
+        self.record_server_time(response.headers());
+        let status = response.status();
+        let result = response.text()?;
+        if status.is_success() {
+            copy_part_etag_from_xml(&result)
+        } else {
+            debug!("oss log: upload part copy status: {} error: {}", status, result);
+            Err(OssError::from_response("upload part copy", status, result))
+        }
+    }
+
+    /// 分片拷贝`size`字节的源对象`copy_source`(编码好的`/bucket/key`)到`key`，任何一步失败都会取消本次分片上传
+    ///
+    /// `build`只用于初始化请求(存储类型、元数据等)，分片和完成请求使用[RequestBuilder::follow_up]。
+    /// 设置了[RequestBuilder::with_verify_on_complete]时，完成后和源对象的CRC64(`source_crc64`)比较
+    pub(crate) fn multipart_copy(
+        &self,
+        key: &str,
+        copy_source: &str,
+        size: u64,
+        source_crc64: Option<u64>,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let expected = match (build.verify_on_complete, source_crc64) {
+            (false, _) => None,
+            (true, Some(crc64)) => Some(crc64),
+            (true, None) => {
+                return Err(OssError::Err(format!("verify on complete requires the crc64 of {}", copy_source)))
+            }
+        };
+        let part_build = build.follow_up();
+        let upload_id = self.initiate_multipart_upload(key, build)?;
+        let mut parts = Vec::new();
+        let mut result = Ok(());
+        for (index, range) in copy_ranges(size, COPY_PART_SIZE).into_iter().enumerate() {
+            let part_number = index as u32 + 1;
+            match self.upload_part_copy_from(key, &upload_id, part_number, copy_source, range, part_build.clone()) {
+                Ok(etag) => parts.push(UploadedPart::new(part_number, etag)),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        let result = match result {
+            Ok(()) => {
+                self.complete_multipart_upload_crc64(key, &upload_id, &parts, expected, part_build.clone())
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            debug!("oss log: multipart copy error: {}, abort upload: {}", e, upload_id);
+            let _ = self.abort_multipart_upload(key, &upload_id, part_build);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// 取消分片上传，已上传的分片会被删除
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// oss.abort_multipart_upload("/big.bin", "upload id", RequestBuilder::new()).unwrap();
+    /// ```
+    pub fn abort_multipart_upload<S: AsRef<str>>(
+        &self,
+        key: S,
+        upload_id: &str,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let mut build = build.parameters_put("uploadId", upload_id);
+        build.method = RequestType::Delete;
+        let key = self.format_key(key);
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: abort multipart upload: {} headers: {:?}", url, headers);
+        let response = client.delete(url).headers(headers).send()?;
+        self.record_server_time(response.headers());
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let result = response.text()?;
+            debug!("oss log: abort multipart upload status: {} error: {}", status, result);
+            Err(OssError::from_response("abort multipart upload", status, result))
+        }
+    }
+
+    /// 从不支持seek的`reader`(管道、解压流等)顺序读取数据并分片上传，不需要预先知道总大小
+    ///
+    /// 每次最多读取`part_size`字节作为一个分片，内存中只保留一个分片，最后一个分片可以小于`part_size`，
+    /// 没有数据时上传一个空对象。`part_size`不能小于[MIN_PART_SIZE](crate::multipart::MIN_PART_SIZE)，
+    /// 分片数量超过[MAX_PARTS](crate::multipart::MAX_PARTS)时失败。
+    /// `build`中的对象设置(Content-Type、元数据等)只用于初始化请求，任何一步失败都会取消本次分片上传
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let reader = std::io::stdin();
+    /// oss.multipart_upload_reader("/backup.tar", reader, 8 * 1024 * 1024, RequestBuilder::new()).unwrap();
+    /// ```
+    pub fn multipart_upload_reader<S: AsRef<str>, R: Read>(
+        &self,
+        key: S,
+        reader: R,
+        part_size: usize,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        self.upload_reader_parts(key.as_ref(), reader, part_size, false, build)
+    }
+
+    /// 从`reader`上传一个不知道总大小的对象，数据不超过一个分片时使用一次PUT上传，否则自动改用分片上传
+    ///
+    /// 先读取最多`part_size`字节，读到末尾时直接上传为普通对象；还有数据时初始化分片上传，
+    /// 内存中只保留一个分片。每上传1000个分片分片大小翻倍，不会因为流太长用完[MAX_PARTS](crate::multipart::MAX_PARTS)。
+    /// `part_size`不能小于[MIN_PART_SIZE](crate::multipart::MIN_PART_SIZE)，分片上传任何一步失败都会取消本次分片上传
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let reader = std::io::stdin();
+    /// oss.put_object_from_reader("/backup.tar", reader, 8 * 1024 * 1024, RequestBuilder::new()).unwrap();
+    /// ```
+    pub fn put_object_from_reader<S: AsRef<str>, R: Read>(
+        &self,
+        key: S,
+        mut reader: R,
+        part_size: usize,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        check_part_size(part_size)?;
+        let mut first = Vec::with_capacity(part_size);
+        let size = read_part(&mut reader, part_size, &mut first)?;
+        if size < part_size {
+            return self.pub_object_from_buffer(key, &first, build);
+        }
+        self.upload_reader_parts(key.as_ref(), first.as_slice().chain(reader), part_size, true, build)
+    }
+
+    /// 顺序读取`reader`并分片上传，`grow`为true时按[adaptive_part_size]加大后面的分片
+    fn upload_reader_parts<R: Read>(
+        &self,
+        key: &str,
+        mut reader: R,
+        part_size: usize,
+        grow: bool,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        check_part_size(part_size)?;
+        let part_build = build.follow_up();
+        let upload_id = self.initiate_multipart_upload(key, build)?;
+        let mut parts = Vec::new();
+        let mut buf = Vec::with_capacity(part_size);
+        let result = loop {
+            if part_build.is_cancelled() {
+                break Err(OssError::Cancelled);
+            }
+            let part_number = parts.len() as u32 + 1;
+            let part_size = if grow { adaptive_part_size(part_size, part_number) } else { part_size };
+            let size = match read_part(&mut reader, part_size, &mut buf) {
+                Ok(size) => size,
+                Err(e) => break Err(e),
+            };
+            // 没有数据时也要上传一个分片，否则无法完成
+            if size == 0 && !parts.is_empty() {
+                break Ok(());
+            }
+            if part_number > MAX_PARTS {
+                break Err(OssError::Err(format!(
+                    "multipart upload exceeds {} parts, use a larger part size",
+                    MAX_PARTS
+                )));
+            }
+            let part = uploaded_part(part_number, String::new(), &buf, &part_build);
+            match self
+                .upload_part(key, &upload_id, part_number, std::mem::take(&mut buf), part_build.clone())
+            {
+                Ok(etag) => parts.push(UploadedPart { etag, ..part }),
+                Err(e) => break Err(e),
+            }
+            if size < part_size {
+                break Ok(());
+            }
+            buf.reserve(part_size);
+        };
+        let result = match result {
+            Ok(()) => self.complete_multipart_upload(key, &upload_id, &parts, part_build.clone()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            debug!("oss log: multipart upload reader error: {}, abort upload: {}", e, upload_id);
+            let _ = self.abort_multipart_upload(key, &upload_id, part_build);
+            return Err(e);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+use std::io::{Read, Seek, SeekFrom};
+use reqwest::header::{IF_MATCH, RANGE};
+use reqwest::StatusCode;
+use crate::debug;
+use crate::entity::ResumeOptions;
+use crate::error::OssError;
+use crate::oss::{API, OSS};
+use crate::request::RequestBuilder;
+
+/// 默认预读1MB
+const DEFAULT_READ_AHEAD: usize = 1024 * 1024;
+
+/// 远程对象的随机读取器，实现了[Read]和[Seek]
+///
+/// 读取时按需发起Range请求，每次至少读取`read_ahead`字节缓存在本地，
+/// 所有Range请求都带上打开时的ETag，对象被修改后读取会返回错误；
+/// Range请求连接出错时按[ResumeOptions]重新请求同一范围，默认使用[ResumeOptions::new]
+#[derive(Debug)]
+pub struct OssSeekReader {
+    oss: OSS,
+    key: String,
+    build: RequestBuilder,
+    etag: Option<String>,
+    size: u64,
+    pos: u64,
+    buf: Vec<u8>,
+    buf_start: u64,
+    read_ahead: usize,
+    resume: ResumeOptions,
+}
+
+impl OssSeekReader {
+    /// 对象大小
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// 设置每次Range请求最少读取的字节数
+    pub fn with_read_ahead(mut self, read_ahead: usize) -> Self {
+        self.read_ahead = read_ahead.max(1);
+        self
+    }
+
+    /// 设置Range请求连接出错时的重试，[ResumeOptions::disabled]时直接返回错误
+    pub fn with_resume(mut self, resume: ResumeOptions) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    fn fill_buf(&mut self, want: usize) -> Result<(), OssError> {
+        let start = self.pos;
+        let end = (start + want.max(self.read_ahead) as u64).min(self.size) - 1;
+        let mut attempts = 0;
+        loop {
+            match self.fetch_range(start, end) {
+                Err(OssError::RequestError(_e)) if attempts < self.resume.max_attempts => {
+                    attempts += 1;
+                    debug!("oss log: read object range error: {}, retry: {}", _e, attempts);
+                    std::thread::sleep(self.resume.delay(attempts));
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn fetch_range(&mut self, start: u64, end: u64) -> Result<(), OssError> {
+        self.oss.acquire_rate_limit();
+        let client = self.oss.http_client(&self.build)?;
+        let (url, headers) = self
+            .oss
+            .build_request(self.key.as_str(), self.build.clone())?;
+        debug!("oss log: read object range: {} {}-{}", url, start, end);
+        let mut request = client
+            .get(url)
+            .headers(headers)
+            .header(RANGE, format!("bytes={}-{}", start, end));
+        if let Some(etag) = &self.etag {
+            request = request.header(IF_MATCH, etag.as_str());
+        }
+        let response = request.send()?;
+        self.oss.record_server_time(response.headers());
+        let status = response.status();
+        if status == StatusCode::PRECONDITION_FAILED {
+            return Err(OssError::ObjectChangedDuringDownload(self.key.clone()));
+        }
+        if status != StatusCode::PARTIAL_CONTENT {
+            let result = response.text()?;
+            debug!("oss log: read object range status: {} error: {}", status, result);
+            return Err(OssError::from_response("read object range", status, result));
+        }
+        self.buf = response.bytes()?.to_vec();
+        self.buf_start = start;
+        if self.buf.is_empty() {
+            return Err(OssError::Err(format!("read object range {}-{} returned empty body", start, end)));
+        }
+        Ok(())
+    }
+}
+
+impl Read for OssSeekReader {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        if out.is_empty() || self.pos >= self.size {
+            return Ok(0);
+        }
+        let buf_end = self.buf_start + self.buf.len() as u64;
+        if self.pos < self.buf_start || self.pos >= buf_end {
+            self.fill_buf(out.len()).map_err(std::io::Error::other)?;
+        }
+        let offset = (self.pos - self.buf_start) as usize;
+        let n = out.len().min(self.buf.len() - offset);
+        out[..n].copy_from_slice(&self.buf[offset..offset + n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for OssSeekReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+        };
+        match pos {
+            Some(pos) => {
+                self.pos = pos;
+                Ok(pos)
+            }
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl OSS {
+    /// 打开远程对象，返回可以随机读取的[OssSeekReader]
+    ///
+    /// 打开时会发起一次HEAD请求获取对象大小和ETag
+    /// # 使用例子
+    /// ```rust
+    /// use std::io::{Read, Seek, SeekFrom};
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let mut reader = oss.open_object("/hello.zip", RequestBuilder::new()).unwrap();
+    /// reader.seek(SeekFrom::End(-22)).unwrap();
+    /// let mut tail = Vec::new();
+    /// reader.read_to_end(&mut tail).unwrap();
+    /// ```
+    pub fn open_object<S: AsRef<str>>(&self, key: S, build: RequestBuilder) -> Result<OssSeekReader, OssError> {
+        let metadata = self.get_object_metadata(key.as_ref(), build.clone())?;
+        // 读取时直接调用build_request，需要自己加上作用域前缀
+        let key = self.format_key(key);
+        let size = metadata
+            .content_length()
+            .and_then(|length| length.parse::<u64>().ok())
+            .ok_or_else(|| OssError::Err(format!("object {} has no content length", key)))?;
+        Ok(OssSeekReader {
+            oss: self.clone(),
+            key,
+            build,
+            // ObjectMetadata去掉了ETag两边的引号，If-Match需要带上
+            etag: metadata.etag().map(|etag| format!("\"{}\"", etag)),
+            size,
+            pos: 0,
+            buf: Vec::new(),
+            buf_start: 0,
+            read_ahead: DEFAULT_READ_AHEAD,
+            resume: ResumeOptions::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Seek, SeekFrom};
+    use std::time::Duration;
+    use crate::entity::ResumeOptions;
+    use crate::mock_server;
+    use crate::oss::OSS;
+    use crate::request::RequestBuilder;
+
+    fn range_response(body: &str, start: usize) -> Vec<u8> {
+        format!(
+            "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/26\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            start,
+            start + body.len() - 1,
+            body
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_open_object_seek_and_read() {
+        let (addr, server) = mock_server::serve(vec![
+            b"HTTP/1.1 200 OK\r\nContent-Length: 26\r\nETag: \"abc\"\r\nConnection: close\r\n\r\n".to_vec(),
+            range_response("uvwx", 20),
+            range_response("abcd", 0),
+            range_response("yz", 24),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let mut reader = oss
+            .open_object("/alphabet.txt", RequestBuilder::new().with_cdn(addr))
+            .unwrap()
+            .with_read_ahead(4);
+        assert_eq!(reader.size(), 26);
+
+        assert_eq!(reader.seek(SeekFrom::End(-6)).unwrap(), 20);
+        let mut buf = [0u8; 2];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"uv");
+        // 仍在缓存范围内，不会发起新的请求
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"wx");
+
+        reader.seek(SeekFrom::Start(0)).unwrap();
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ab");
+
+        reader.seek(SeekFrom::Start(24)).unwrap();
+        let mut tail = Vec::new();
+        reader.read_to_end(&mut tail).unwrap();
+        assert_eq!(tail, b"yz");
+        assert!(reader.seek(SeekFrom::Current(-30)).is_err());
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("head /alphabet.txt "));
+        assert!(requests[1].contains("range: bytes=20-23"));
+        assert!(requests[1].contains("if-match: \"abc\""));
+        assert!(requests[2].contains("range: bytes=0-3"));
+        assert!(requests[3].contains("range: bytes=24-25"));
+    }
+
+    #[test]
+    fn test_open_object_retry_interrupted_range() {
+        let (addr, server) = mock_server::serve(vec![
+            b"HTTP/1.1 200 OK\r\nContent-Length: 26\r\nETag: \"abc\"\r\nConnection: close\r\n\r\n".to_vec(),
+            // 声明4字节只返回2字节就断开
+            b"HTTP/1.1 206 Partial Content\r\nContent-Length: 4\r\nContent-Range: bytes 0-3/26\r\nConnection: close\r\n\r\nab".to_vec(),
+            range_response("abcd", 0),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let mut reader = oss
+            .open_object("/alphabet.txt", RequestBuilder::new().with_cdn(addr))
+            .unwrap()
+            .with_read_ahead(4)
+            .with_resume(ResumeOptions::new().with_backoff(Duration::from_millis(1)));
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"abcd");
+        let requests = server.join().unwrap();
+        assert!(requests[2].contains("range: bytes=0-3"));
+        assert!(requests[2].contains("if-match: \"abc\""));
+    }
+
+    #[test]
+    fn test_open_object_scoped() {
+        let (addr, server) = mock_server::serve(vec![
+            b"HTTP/1.1 200 OK\r\nContent-Length: 26\r\nETag: \"abc\"\r\nConnection: close\r\n\r\n".to_vec(),
+            range_response("abcd", 0),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket").scoped("tenants/a");
+        let mut reader = oss
+            .open_object("/alphabet.txt", RequestBuilder::new().with_cdn(addr))
+            .unwrap()
+            .with_read_ahead(4);
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"abcd");
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("head /tenants/a/alphabet.txt "));
+        assert!(requests[1].starts_with("get /tenants/a/alphabet.txt "));
+    }
+}
@@ -0,0 +1,188 @@
+use reqwest::header::{IF_MATCH, RANGE};
+use reqwest::StatusCode;
+use crate::debug;
+use crate::entity::{tagging_header, verify_transfer, TransferOptions, MAX_COPY_OBJECT_SIZE};
+use crate::error::OssError;
+use crate::metadata::ObjectMetadata;
+use crate::multipart::{copy_ranges, multipart_copy_build, uploaded_part, UploadedPart};
+use crate::oss::{API, OSS};
+use crate::request::RequestBuilder;
+
+impl OSS {
+    /// 把`src`客户端中的`src_key`迁移到当前客户端的`key`，用于跨bucket、跨地域迁移对象
+    ///
+    /// 两个客户端的endpoint相同并且使用同一个AccessKeyId时使用服务端拷贝(超过1GB时分片拷贝)，数据不经过本机；
+    /// 否则经本机中转，按[TransferOptions::part_size]分段下载并分片上传，内存中只保留一个分片，
+    /// 不超过一个分片的对象一次下载、一次上传。分段下载时带上源对象的ETag，源对象中途被修改时失败并取消分片上传。
+    ///
+    /// 默认保留Content-Type等标准header、用户元数据和标签，完成后HEAD目标对象和源对象比较CRC64。
+    /// `build`用于目标对象的请求，源对象的请求使用[TransferOptions::with_source_build]
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::entity::TransferOptions;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let src = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "src-bucket");
+    /// let dest = OSS::new("key_id", "key_secret", "oss-cn-beijing.aliyuncs.com", "dest-bucket");
+    /// let options = TransferOptions::new()
+    ///     .with_part_size(16 * 1024 * 1024)
+    ///     .with_progress(|transferred, total| println!("{}/{}", transferred, total));
+    /// dest.transfer_object(&src, "/data/big.bin", "/data/big.bin", options, RequestBuilder::new()).unwrap();
+    /// ```
+    pub fn transfer_object(
+        &self,
+        src: &OSS,
+        src_key: &str,
+        key: &str,
+        options: TransferOptions,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let source = src.get_object_metadata(src_key, options.source_build.clone())?;
+        let size = source
+            .content_length()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or_default();
+        let server_side = self.can_copy_from(src);
+        if server_side && size <= MAX_COPY_OBJECT_SIZE {
+            // CopyObject默认复制元数据和标签
+            let mut build = build.clone();
+            if !options.preserve_metadata {
+                build = build.oss_header_put("x-oss-metadata-directive", "REPLACE");
+            }
+            if !options.preserve_tags {
+                build = build.oss_header_put("x-oss-tagging-directive", "REPLACE");
+            }
+            let copy_source = src.copy_source(src_key, &options.source_build);
+            self.copy_object_from(key, &copy_source, &build)?;
+            options.report_progress(size, size);
+        } else {
+            let mut target_build = if options.preserve_metadata {
+                multipart_copy_build(&source, &build)
+            } else {
+                build.clone()
+            };
+            if options.preserve_tags && source.tagging_count().unwrap_or_default() > 0 {
+                let tags = src.get_object_tagging(src_key, options.source_build.clone())?;
+                target_build = target_build.oss_header_put("x-oss-tagging".to_string(), tagging_header(&tags));
+            }
+            if server_side {
+                let copy_source = src.copy_source(src_key, &options.source_build);
+                let crc64 = source.crc64().and_then(|crc64| crc64.parse::<u64>().ok());
+                self.multipart_copy(key, &copy_source, size, crc64, target_build)?;
+                options.report_progress(size, size);
+            } else {
+                self.transfer_through_host(src, src_key, key, &source, &options, target_build)?;
+            }
+        }
+        if options.verify {
+            let target = self.get_object_metadata(key, build.follow_up())?;
+            verify_transfer(&source, &target)?;
+        }
+        Ok(())
+    }
+
+    /// 经本机中转迁移对象，`build`中已经带上了需要保留的元数据和标签
+    fn transfer_through_host(
+        &self,
+        src: &OSS,
+        src_key: &str,
+        key: &str,
+        source: &ObjectMetadata,
+        options: &TransferOptions,
+        build: RequestBuilder,
+    ) -> Result<(), OssError> {
+        let size = source
+            .content_length()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or_default();
+        if size <= options.part_size {
+            let data = src.get_object(src_key, options.source_build.clone())?;
+            self.pub_object_from_buffer(key, &data, build)?;
+            options.report_progress(data.len() as u64, size);
+            return Ok(());
+        }
+        let etag = source.etag().map(|etag| format!("\"{}\"", etag));
+        let part_build = build.follow_up();
+        let upload_id = self.initiate_multipart_upload(key, build)?;
+        let mut parts = Vec::new();
+        let mut transferred = 0;
+        let mut result = Ok(());
+        for (index, range) in copy_ranges(size, options.part_size).into_iter().enumerate() {
+            if part_build.is_cancelled() {
+                result = Err(OssError::Cancelled);
+                break;
+            }
+            let part_number = index as u32 + 1;
+            let data = match src.get_object_range(src_key, range, etag.as_deref(), options.source_build.clone()) {
+                Ok(data) => data,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            };
+            transferred += data.len() as u64;
+            let part = uploaded_part(part_number, String::new(), &data, &part_build);
+            match self.upload_part(key, &upload_id, part_number, data, part_build.clone()) {
+                Ok(etag) => parts.push(UploadedPart { etag, ..part }),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+            options.report_progress(transferred, size);
+        }
+        let result = match result {
+            Ok(()) => self.complete_multipart_upload(key, &upload_id, &parts, part_build.clone()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            debug!("oss log: transfer object error: {}, abort upload: {}", e, upload_id);
+            let _ = self.abort_multipart_upload(key, &upload_id, part_build);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// 下载对象`range`字节范围(包含两端)的内容，`etag`不为None时对象被修改后返回[OssError::ObjectChangedDuringDownload]
+    fn get_object_range(
+        &self,
+        key: &str,
+        range: (u64, u64),
+        etag: Option<&str>,
+        build: RequestBuilder,
+    ) -> Result<Vec<u8>, OssError> {
+        let key = self.format_key(key);
+        self.acquire_rate_limit();
+        let client = self.http_client(&build)?;
+        let (url, headers) = self.build_request(key.as_str(), build)?;
+        debug!("oss log: get object range: {} {:?}", url, range);
+        let mut request = client
+            .get(url)
+            .headers(headers)
+            .header(RANGE, format!("bytes={}-{}", range.0, range.1));
+        if let Some(etag) = etag {
+            request = request.header(IF_MATCH, etag);
+        }
+        let response = request.send()?;
+        self.record_server_time(response.headers());
+        let status = response.status();
+        if status == StatusCode::PRECONDITION_FAILED {
+            return Err(OssError::ObjectChangedDuringDownload(key));
+        }
+        if status != StatusCode::PARTIAL_CONTENT {
+            let result = response.text()?;
+            debug!("oss log: get object range status: {} error: {}", status, result);
+            return Err(OssError::from_response("get object range", status, result));
+        }
+        let data = response.bytes()?.to_vec();
+        if data.len() as u64 != range.1 - range.0 + 1 {
+            return Err(OssError::Err(format!(
+                "get object range {}-{} returned {} bytes",
+                range.0,
+                range.1,
+                data.len()
+            )));
+        }
+        Ok(data)
+    }
+}
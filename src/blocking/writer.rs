@@ -0,0 +1,451 @@
+use std::io::Write;
+use crate::{debug, warn};
+use crate::error::OssError;
+use crate::multipart::{adaptive_part_size, check_part_size, uploaded_part, UploadedPart, MAX_PARTS, MIN_PART_SIZE};
+use crate::oss::OSS;
+use crate::request::RequestBuilder;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BatchMode {
+    Append,
+    Multipart,
+}
+
+/// 在内存中累积小块写入，达到`flush_bytes`后一次性上传，减少日志、事件等场景的请求次数
+///
+/// 默认以追加上传(AppendObject)写入追加类型的对象，每次刷新后数据立即可读；
+/// [BatchedWriter::with_multipart]改为分片上传，[BatchedWriter::finish]后才生成一个完整的对象
+///
+/// 写入达到`flush_bytes`时上传，上传失败不影响本次[Write::write]的返回值(数据已经进入缓冲区)，
+/// 错误在下一次write、flush或[BatchedWriter::finish]时返回，缓冲区保留，之后的调用会重试上传
+///
+/// 必须调用[BatchedWriter::finish]上传剩余数据，或者调用[BatchedWriter::abort]放弃写入。
+/// drop时不会发起网络请求，没有finish的数据会丢失，分片模式下已上传的分片需要调用abort或通过生命周期规则清理
+#[derive(Debug)]
+pub struct BatchedWriter {
+    oss: OSS,
+    key: String,
+    build: RequestBuilder,
+    flush_bytes: usize,
+    /// 分片模式下第一个分片的大小，之后的分片按[adaptive_part_size]加大
+    part_size: usize,
+    mode: BatchMode,
+    buf: Vec<u8>,
+    written: u64,
+    /// 追加模式下一次追加的位置，第一次追加前为None，由OSS按对象当前长度决定
+    position: Option<u64>,
+    upload_id: Option<String>,
+    parts: Vec<UploadedPart>,
+    /// 上一次写入时上传失败的错误，下一次write、flush或finish时返回
+    error: Option<OssError>,
+    finished: bool,
+}
+
+impl BatchedWriter {
+    /// 使用分片上传，`flush_bytes`小于[MIN_PART_SIZE]时按[MIN_PART_SIZE]计算
+    ///
+    /// 分片模式下[Write::flush]不会上传数据，因为除最后一个分片外分片不能小于[MIN_PART_SIZE]。
+    /// 每上传1000个分片分片大小翻倍，不知道总大小时(例如`io::copy`管道数据)也不会很快用完[MAX_PARTS]
+    pub fn with_multipart(mut self) -> Self {
+        self.mode = BatchMode::Multipart;
+        self.flush_bytes = self.flush_bytes.max(MIN_PART_SIZE);
+        self.part_size = self.flush_bytes;
+        self
+    }
+
+    /// 缓冲区中还没有上传的字节数
+    pub fn buffered_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// 上传缓冲区中剩余的数据并结束写入，返回对象的总大小
+    ///
+    /// 分片模式下完成分片上传，数据不足一个分片时直接上传为普通对象，失败时取消分片上传
+    pub fn finish(mut self) -> Result<u64, OssError> {
+        self.finished = true;
+        match self.mode {
+            BatchMode::Append => {
+                self.take_error()?;
+                self.upload_buffer()?;
+                Ok(self.position.unwrap_or(0))
+            }
+            BatchMode::Multipart => {
+                if self.upload_id.is_none() {
+                    self.take_error()?;
+                    self.oss.pub_object_from_buffer(self.key.as_str(), &self.buf, self.build.clone())?;
+                    return Ok(self.written);
+                }
+                let result = self.take_error().and_then(|_| self.upload_buffer()).and_then(|_| {
+                    let upload_id = self.upload_id.as_deref().unwrap_or_default();
+                    self.oss
+                        .complete_multipart_upload(self.key.as_str(), upload_id, &self.parts, self.build.follow_up())
+                });
+                if let Err(e) = result {
+                    self.abort_upload();
+                    return Err(e);
+                }
+                Ok(self.written)
+            }
+        }
+    }
+
+    /// 放弃写入，丢弃缓冲区中的数据，分片模式下取消分片上传，已上传的分片会被删除
+    ///
+    /// 追加模式下已经追加的数据不会被删除
+    pub fn abort(mut self) -> Result<(), OssError> {
+        self.finished = true;
+        self.buf.clear();
+        match self.upload_id.take() {
+            Some(upload_id) => self
+                .oss
+                .abort_multipart_upload(self.key.as_str(), &upload_id, self.build.follow_up()),
+            None => Ok(()),
+        }
+    }
+
+    /// 取出上一次写入时上传失败的错误
+    fn take_error(&mut self) -> Result<(), OssError> {
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// 上传缓冲区中的数据，失败时保留缓冲区，可以重试
+    fn upload_buffer(&mut self) -> Result<(), OssError> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        match self.mode {
+            BatchMode::Append => {
+                let next = match self.position {
+                    Some(position) => self.oss.append_object(self.key.as_str(), &self.buf, position, self.build.clone())?,
+                    None => self.oss.append_or_create(self.key.as_str(), &self.buf, self.build.clone())?,
+                };
+                self.position = Some(next);
+            }
+            BatchMode::Multipart => {
+                let upload_id = match &self.upload_id {
+                    Some(upload_id) => upload_id.to_string(),
+                    None => {
+                        let upload_id = self.oss.initiate_multipart_upload(self.key.as_str(), self.build.clone())?;
+                        self.upload_id = Some(upload_id.clone());
+                        upload_id
+                    }
+                };
+                let part_number = self.parts.len() as u32 + 1;
+                if part_number > MAX_PARTS {
+                    return Err(OssError::Err(format!(
+                        "multipart upload exceeds {} parts, use a larger flush size",
+                        MAX_PARTS
+                    )));
+                }
+                let part_build = self.build.follow_up();
+                let part = uploaded_part(part_number, String::new(), &self.buf, &part_build);
+                let etag = self.oss.upload_part(
+                    self.key.as_str(),
+                    &upload_id,
+                    part_number,
+                    self.buf.clone(),
+                    part_build,
+                )?;
+                self.parts.push(UploadedPart { etag, ..part });
+                self.flush_bytes = adaptive_part_size(self.part_size, part_number + 1);
+            }
+        }
+        self.buf.clear();
+        Ok(())
+    }
+
+    fn abort_upload(&mut self) {
+        if let Some(upload_id) = self.upload_id.take() {
+            debug!("oss log: batched writer abort multipart upload: {}", upload_id);
+            let _ = self
+                .oss
+                .abort_multipart_upload(self.key.as_str(), &upload_id, self.build.follow_up());
+        }
+    }
+}
+
+impl Write for BatchedWriter {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        // 返回错误时不写入数据，符合Write的约定
+        self.take_error().map_err(std::io::Error::other)?;
+        self.buf.extend_from_slice(data);
+        self.written += data.len() as u64;
+        if self.buf.len() >= self.flush_bytes {
+            // 数据已经进入缓冲区，上传失败留到下一次调用时返回
+            if let Err(e) = self.upload_buffer() {
+                self.error = Some(e);
+            }
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.take_error().map_err(std::io::Error::other)?;
+        if self.mode == BatchMode::Append {
+            self.upload_buffer().map_err(std::io::Error::other)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for BatchedWriter {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let _key = &self.key;
+        let buffered = self.buf.len();
+        if buffered > 0 {
+            warn!("oss log: batched writer for {} dropped without finish, {} buffered bytes lost", _key, buffered);
+        }
+        if let Some(_upload_id) = &self.upload_id {
+            warn!("oss log: batched writer for {} dropped without finish, multipart upload {} is left incomplete", _key, _upload_id);
+        }
+    }
+}
+
+impl OSS {
+    /// 创建[BatchedWriter]，缓冲区达到`flush_bytes`时上传一次
+    ///
+    /// 追加模式下对象不存在时创建，存在时必须是追加类型的对象，从当前末尾继续追加
+    /// # 使用例子
+    /// ```rust
+    /// use std::io::Write;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let mut writer = oss.batched_writer("/logs/app.log", 64 * 1024, RequestBuilder::new());
+    /// for i in 0..1000 {
+    ///     writeln!(writer, "event {}", i).unwrap();
+    /// }
+    /// let size = writer.finish().unwrap();
+    /// println!("log size: {}", size);
+    /// ```
+    pub fn batched_writer<S: AsRef<str>>(&self, key: S, flush_bytes: usize, build: RequestBuilder) -> BatchedWriter {
+        BatchedWriter {
+            oss: self.clone(),
+            key: key.as_ref().to_string(),
+            build,
+            flush_bytes: flush_bytes.max(1),
+            part_size: flush_bytes.max(1),
+            mode: BatchMode::Append,
+            buf: Vec::new(),
+            written: 0,
+            position: None,
+            upload_id: None,
+            parts: Vec::new(),
+            error: None,
+            finished: false,
+        }
+    }
+
+    /// 边生成边上传归档(tar、zip等)，归档不落本地磁盘，返回对象的总大小
+    ///
+    /// `build_fn`向分片模式的[BatchedWriter]写入归档内容，每满`part_size`上传一个分片，内存中只保留一个分片，
+    /// 数据不足一个分片时直接上传为普通对象。`build_fn`返回错误或上传失败时取消分片上传，
+    /// 写入时上传失败的错误会原样返回。`part_size`不能小于[MIN_PART_SIZE]
+    /// # 使用例子
+    /// ```rust
+    /// use std::io::Write;
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::from_env();
+    /// let size = oss.put_archive("/backup.tar", 8 * 1024 * 1024, RequestBuilder::new(), |writer| {
+    ///     // 例如 tar::Builder::new(writer).append_dir_all("data", "./data")
+    ///     writer.write_all(b"archive content")
+    /// }).unwrap();
+    /// println!("archive size: {}", size);
+    /// ```
+    pub fn put_archive<S, F>(&self, key: S, part_size: usize, build: RequestBuilder, build_fn: F) -> Result<u64, OssError>
+    where
+        S: AsRef<str>,
+        F: FnOnce(&mut BatchedWriter) -> std::io::Result<()>,
+    {
+        check_part_size(part_size)?;
+        let mut writer = self.batched_writer(key, part_size, build).with_multipart();
+        if let Err(e) = build_fn(&mut writer) {
+            let _ = writer.abort();
+            return Err(e.downcast::<OssError>().unwrap_or_else(OssError::from));
+        }
+        writer.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use crate::error::{OssError, OssErrorCode};
+    use crate::mock_server;
+    use crate::multipart::MIN_PART_SIZE;
+    use crate::oss::OSS;
+    use crate::request::RequestBuilder;
+
+    #[test]
+    fn test_batched_writer_append() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("404 Not Found", "", ""),
+            mock_server::response("200 OK", "x-oss-next-append-position: 4\r\n", ""),
+            mock_server::response("200 OK", "x-oss-next-append-position: 5\r\n", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let mut writer = oss.batched_writer("/app.log", 4, RequestBuilder::new().with_cdn(addr));
+        writer.write_all(b"ab").unwrap();
+        assert_eq!(writer.buffered_len(), 2);
+        writer.write_all(b"cd").unwrap();
+        assert_eq!(writer.buffered_len(), 0);
+        writer.write_all(b"e").unwrap();
+        assert_eq!(writer.finish().unwrap(), 5);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("head /app.log "));
+        assert!(requests[1].starts_with("post /app.log?append&position=0 "));
+        assert!(requests[1].ends_with("\r\n\r\nabcd"));
+        assert!(requests[2].starts_with("post /app.log?append&position=4 "));
+        assert!(requests[2].ends_with("\r\n\r\ne"));
+    }
+
+    #[test]
+    fn test_batched_writer_multipart() {
+        let initiate = "<InitiateMultipartUploadResult><UploadId>upload-1</UploadId></InitiateMultipartUploadResult>";
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "", initiate),
+            mock_server::response("200 OK", "ETag: \"e1\"\r\n", ""),
+            mock_server::response("200 OK", "ETag: \"e2\"\r\n", ""),
+            mock_server::response("200 OK", "", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let mut writer = oss
+            .batched_writer("/events.bin", 1, RequestBuilder::new().with_cdn(addr))
+            .with_multipart();
+        writer.write_all(&vec![b'a'; MIN_PART_SIZE - 1]).unwrap();
+        // 分片模式下flush不会上传不足一个分片的数据
+        writer.flush().unwrap();
+        writer.write_all(b"bc").unwrap();
+        assert_eq!(writer.buffered_len(), 0);
+        writer.write_all(b"rest").unwrap();
+        assert_eq!(writer.finish().unwrap(), MIN_PART_SIZE as u64 + 5);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("post /events.bin?uploads "));
+        assert!(requests[1].starts_with("put /events.bin?partnumber=1&uploadid=upload-1 "));
+        assert!(requests[2].starts_with("put /events.bin?partnumber=2&uploadid=upload-1 "));
+        assert!(requests[2].ends_with("\r\n\r\nrest"));
+        assert!(requests[3].starts_with("post /events.bin?uploadid=upload-1 "));
+        assert!(requests[3].contains("<partnumber>2</partnumber><etag>&quot;e2&quot;</etag>"));
+    }
+
+    #[test]
+    fn test_batched_writer_abort_multipart() {
+        let initiate = "<InitiateMultipartUploadResult><UploadId>upload-1</UploadId></InitiateMultipartUploadResult>";
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "", initiate),
+            mock_server::response("200 OK", "ETag: \"e1\"\r\n", ""),
+            mock_server::response("204 No Content", "", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let mut writer = oss
+            .batched_writer("/events.bin", MIN_PART_SIZE, RequestBuilder::new().with_cdn(addr))
+            .with_multipart();
+        writer.write_all(&vec![b'a'; MIN_PART_SIZE]).unwrap();
+        writer.abort().unwrap();
+        let requests = server.join().unwrap();
+        assert!(requests[2].starts_with("delete /events.bin?uploadid=upload-1 "));
+    }
+
+    #[test]
+    fn test_batched_writer_deferred_error() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("404 Not Found", "", ""),
+            mock_server::response("500 Internal Server Error", "", ""),
+            mock_server::response("404 Not Found", "", ""),
+            mock_server::response("200 OK", "x-oss-next-append-position: 5\r\n", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let mut writer = oss.batched_writer("/app.log", 4, RequestBuilder::new().with_cdn(addr));
+        // 上传失败时数据已经进入缓冲区，本次写入仍然成功
+        assert_eq!(writer.write(b"abcd").unwrap(), 4);
+        assert_eq!(writer.buffered_len(), 4);
+        // 错误在下一次调用时返回，这次的数据没有写入
+        assert!(writer.write(b"e").is_err());
+        assert_eq!(writer.buffered_len(), 4);
+        writer.write_all(b"e").unwrap();
+        assert_eq!(writer.finish().unwrap(), 5);
+        let requests = server.join().unwrap();
+        assert!(requests[3].starts_with("post /app.log?append&position=0 "));
+        assert!(requests[3].ends_with("\r\n\r\nabcde"));
+    }
+
+    #[test]
+    fn test_batched_writer_multipart_deferred_error() {
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("500 Internal Server Error", "", "<Error><Code>InternalError</Code></Error>"),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let mut writer = oss
+            .batched_writer("/events.bin", MIN_PART_SIZE, RequestBuilder::new().with_cdn(addr))
+            .with_multipart();
+        // 初始化分片上传失败，错误留到finish时返回，不能再把缓冲区当作普通对象上传
+        writer.write_all(&vec![b'a'; MIN_PART_SIZE]).unwrap();
+        let err = writer.finish().unwrap_err();
+        assert_eq!(err.code(), Some(OssErrorCode::Other("InternalError".to_string())));
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("post /events.bin?uploads "));
+    }
+
+    #[test]
+    fn test_put_archive() {
+        let initiate = "<InitiateMultipartUploadResult><UploadId>upload-1</UploadId></InitiateMultipartUploadResult>";
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "", initiate),
+            mock_server::response("200 OK", "ETag: \"e1\"\r\n", ""),
+            mock_server::response("200 OK", "ETag: \"e2\"\r\n", ""),
+            mock_server::response("200 OK", "", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let size = oss
+            .put_archive("/backup.tar", MIN_PART_SIZE, RequestBuilder::new().with_cdn(addr), |writer| {
+                for _ in 0..MIN_PART_SIZE / 1024 {
+                    writer.write_all(&[b'a'; 1024])?;
+                }
+                writer.write_all(b"tail")
+            })
+            .unwrap();
+        assert_eq!(size, MIN_PART_SIZE as u64 + 4);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("post /backup.tar?uploads "));
+        assert!(requests[1].starts_with("put /backup.tar?partnumber=1&uploadid=upload-1 "));
+        assert!(requests[2].ends_with("\r\n\r\ntail"));
+        assert!(requests[3].starts_with("post /backup.tar?uploadid=upload-1 "));
+    }
+
+    #[test]
+    fn test_put_archive_build_error() {
+        let initiate = "<InitiateMultipartUploadResult><UploadId>upload-1</UploadId></InitiateMultipartUploadResult>";
+        let (addr, server) = mock_server::serve(vec![
+            mock_server::response("200 OK", "", initiate),
+            mock_server::response("200 OK", "ETag: \"e1\"\r\n", ""),
+            mock_server::response("204 No Content", "", ""),
+        ]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let result = oss.put_archive("/backup.tar", MIN_PART_SIZE, RequestBuilder::new().with_cdn(addr), |writer| {
+            writer.write_all(&vec![b'a'; MIN_PART_SIZE])?;
+            Err(std::io::Error::other("archive failed"))
+        });
+        assert!(matches!(result, Err(OssError::IoError(_))));
+        let requests = server.join().unwrap();
+        assert!(requests[2].starts_with("delete /backup.tar?uploadid=upload-1 "));
+        assert!(oss.put_archive("/backup.tar", 1024, RequestBuilder::new(), |_| Ok(())).is_err());
+    }
+
+    #[test]
+    fn test_batched_writer_drop_without_requests() {
+        let (addr, server) = mock_server::serve(vec![]);
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let mut writer = oss.batched_writer("/app.log", 1024, RequestBuilder::new().with_cdn(addr));
+        writer.write_all(b"lost").unwrap();
+        drop(writer);
+        assert!(server.join().unwrap().is_empty());
+    }
+}
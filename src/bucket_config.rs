@@ -0,0 +1,555 @@
+use serde::{Deserialize, Serialize};
+use crate::entity::StorageClass;
+use crate::error::OssError;
+use crate::xml::{element_text, elements, unescape, XmlWriter};
+
+fn invalid(name: &str, xml: &str) -> OssError {
+    OssError::Err(format!("invalid {} xml: {}", name, xml))
+}
+
+/// 获取所有`<tag>`元素的文本内容(已还原转义)
+fn element_texts(xml: &str, tag: &str) -> Vec<String> {
+    elements(xml, tag).into_iter().map(unescape).collect()
+}
+
+fn parse_storage_class(value: &str) -> Option<StorageClass> {
+    match value {
+        "Standard" => Some(StorageClass::Standard),
+        "IA" => Some(StorageClass::IA),
+        "Archive" => Some(StorageClass::Archive),
+        "ColdArchive" => Some(StorageClass::ColdArchive),
+        "DeepColdArchive" => Some(StorageClass::DeepColdArchive),
+        _ => None,
+    }
+}
+
+/// bucket的静态网站托管配置(PutBucketWebsite/GetBucketWebsite)
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::bucket_config::WebsiteConfiguration;
+/// let website = WebsiteConfiguration::new("index.html").with_error_document("error.html");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebsiteConfiguration {
+    /// 默认主页，访问以`/`结尾的目录时返回该文件
+    pub index_document: String,
+    /// 默认404页
+    pub error_document: Option<String>,
+}
+
+impl WebsiteConfiguration {
+    pub fn new<S: AsRef<str>>(index_document: S) -> Self {
+        Self {
+            index_document: index_document.as_ref().to_string(),
+            error_document: None,
+        }
+    }
+    pub fn with_error_document<S: AsRef<str>>(mut self, error_document: S) -> Self {
+        self.error_document = Some(error_document.as_ref().to_string());
+        self
+    }
+
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = XmlWriter::new();
+        xml.start("WebsiteConfiguration")
+            .start("IndexDocument")
+            .element("Suffix", &self.index_document)
+            .end();
+        if let Some(error_document) = &self.error_document {
+            xml.start("ErrorDocument").element("Key", error_document).end();
+        }
+        xml.finish()
+    }
+
+    pub(crate) fn from_xml(xml: &str) -> Result<Self, OssError> {
+        let index = elements(xml, "IndexDocument");
+        Ok(Self {
+            index_document: index
+                .first()
+                .and_then(|index| element_text(index, "Suffix"))
+                .ok_or_else(|| invalid("website configuration", xml))?,
+            error_document: elements(xml, "ErrorDocument")
+                .first()
+                .and_then(|error| element_text(error, "Key")),
+        })
+    }
+}
+
+/// bucket的防盗链配置(PutBucketReferer/GetBucketReferer)
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::bucket_config::RefererConfiguration;
+/// let referer = RefererConfiguration::new(false)
+///     .with_referer("https://www.example.com")
+///     .with_referer("https://*.example.com");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefererConfiguration {
+    /// 是否允许Referer为空的请求访问
+    pub allow_empty_referer: bool,
+    /// Referer白名单，支持`*`和`?`通配符，为空时不限制
+    pub referers: Vec<String>,
+}
+
+impl RefererConfiguration {
+    pub fn new(allow_empty_referer: bool) -> Self {
+        Self {
+            allow_empty_referer,
+            referers: Vec::new(),
+        }
+    }
+    pub fn with_referer<S: AsRef<str>>(mut self, referer: S) -> Self {
+        self.referers.push(referer.as_ref().to_string());
+        self
+    }
+
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = XmlWriter::new();
+        xml.start("RefererConfiguration")
+            .element("AllowEmptyReferer", self.allow_empty_referer.to_string())
+            .start("RefererList");
+        for referer in &self.referers {
+            xml.element("Referer", referer);
+        }
+        xml.finish()
+    }
+
+    pub(crate) fn from_xml(xml: &str) -> Result<Self, OssError> {
+        Ok(Self {
+            allow_empty_referer: element_text(xml, "AllowEmptyReferer")
+                .ok_or_else(|| invalid("referer configuration", xml))?
+                == "true",
+            // 只取白名单，RefererBlacklist中也有Referer元素
+            referers: elements(xml, "RefererList")
+                .first()
+                .map(|list| element_texts(list, "Referer"))
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// bucket的访问日志配置(PutBucketLogging/GetBucketLogging)，日志会按小时写入`target_bucket`
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::bucket_config::LoggingConfiguration;
+/// let logging = LoggingConfiguration::new("log-bucket").with_target_prefix("logs/");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoggingConfiguration {
+    /// 存放访问日志的bucket
+    pub target_bucket: String,
+    /// 日志文件名前缀
+    pub target_prefix: Option<String>,
+}
+
+impl LoggingConfiguration {
+    pub fn new<S: AsRef<str>>(target_bucket: S) -> Self {
+        Self {
+            target_bucket: target_bucket.as_ref().to_string(),
+            target_prefix: None,
+        }
+    }
+    pub fn with_target_prefix<S: AsRef<str>>(mut self, target_prefix: S) -> Self {
+        self.target_prefix = Some(target_prefix.as_ref().to_string());
+        self
+    }
+
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = XmlWriter::new();
+        xml.start("BucketLoggingStatus")
+            .start("LoggingEnabled")
+            .element("TargetBucket", &self.target_bucket);
+        if let Some(target_prefix) = &self.target_prefix {
+            xml.element("TargetPrefix", target_prefix);
+        }
+        xml.finish()
+    }
+
+    pub(crate) fn from_xml(xml: &str) -> Result<Self, OssError> {
+        Ok(Self {
+            target_bucket: element_text(xml, "TargetBucket")
+                .ok_or_else(|| invalid("logging configuration", xml))?,
+            target_prefix: element_text(xml, "TargetPrefix").filter(|prefix| !prefix.is_empty()),
+        })
+    }
+
+    /// 没有开启访问日志时响应中没有`LoggingEnabled`，返回None
+    pub(crate) fn from_logging_status(xml: &str) -> Result<Option<Self>, OssError> {
+        if elements(xml, "LoggingEnabled").is_empty() {
+            return Ok(None);
+        }
+        Self::from_xml(xml).map(Some)
+    }
+}
+
+/// 跨域资源共享(CORS)规则
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::bucket_config::CorsRule;
+/// let rule = CorsRule::new()
+///     .with_allowed_origin("https://www.example.com")
+///     .with_allowed_method("GET")
+///     .with_allowed_header("*")
+///     .with_max_age_seconds(600);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorsRule {
+    pub allowed_origins: Vec<String>,
+    /// 允许的方法，取值GET、PUT、DELETE、POST、HEAD
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    /// 允许浏览器读取的响应头
+    pub expose_headers: Vec<String>,
+    /// 浏览器缓存预检(OPTIONS)结果的秒数
+    pub max_age_seconds: Option<u32>,
+}
+
+impl CorsRule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_allowed_origin<S: AsRef<str>>(mut self, origin: S) -> Self {
+        self.allowed_origins.push(origin.as_ref().to_string());
+        self
+    }
+    pub fn with_allowed_method<S: AsRef<str>>(mut self, method: S) -> Self {
+        self.allowed_methods.push(method.as_ref().to_string());
+        self
+    }
+    pub fn with_allowed_header<S: AsRef<str>>(mut self, header: S) -> Self {
+        self.allowed_headers.push(header.as_ref().to_string());
+        self
+    }
+    pub fn with_expose_header<S: AsRef<str>>(mut self, header: S) -> Self {
+        self.expose_headers.push(header.as_ref().to_string());
+        self
+    }
+    pub fn with_max_age_seconds(mut self, max_age_seconds: u32) -> Self {
+        self.max_age_seconds = Some(max_age_seconds);
+        self
+    }
+
+    fn write_xml(&self, xml: &mut XmlWriter) {
+        xml.start("CORSRule");
+        for origin in &self.allowed_origins {
+            xml.element("AllowedOrigin", origin);
+        }
+        for method in &self.allowed_methods {
+            xml.element("AllowedMethod", method);
+        }
+        for header in &self.allowed_headers {
+            xml.element("AllowedHeader", header);
+        }
+        for header in &self.expose_headers {
+            xml.element("ExposeHeader", header);
+        }
+        if let Some(max_age_seconds) = self.max_age_seconds {
+            xml.element("MaxAgeSeconds", max_age_seconds.to_string());
+        }
+        xml.end();
+    }
+
+    fn from_xml(xml: &str) -> Self {
+        Self {
+            allowed_origins: element_texts(xml, "AllowedOrigin"),
+            allowed_methods: element_texts(xml, "AllowedMethod"),
+            allowed_headers: element_texts(xml, "AllowedHeader"),
+            expose_headers: element_texts(xml, "ExposeHeader"),
+            max_age_seconds: element_text(xml, "MaxAgeSeconds").and_then(|v| v.parse().ok()),
+        }
+    }
+}
+
+/// bucket的跨域资源共享配置(PutBucketCors/GetBucketCors)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorsConfiguration {
+    pub rules: Vec<CorsRule>,
+    /// 为true时响应中返回`Vary: Origin`
+    pub response_vary: bool,
+}
+
+impl CorsConfiguration {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_rule(mut self, rule: CorsRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+    pub fn with_response_vary(mut self, response_vary: bool) -> Self {
+        self.response_vary = response_vary;
+        self
+    }
+
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = XmlWriter::new();
+        xml.start("CORSConfiguration");
+        for rule in &self.rules {
+            rule.write_xml(&mut xml);
+        }
+        xml.element("ResponseVary", self.response_vary.to_string());
+        xml.finish()
+    }
+
+    pub(crate) fn from_xml(xml: &str) -> Result<Self, OssError> {
+        if !xml.contains("<CORSConfiguration") {
+            return Err(invalid("cors configuration", xml));
+        }
+        Ok(Self {
+            rules: elements(xml, "CORSRule").into_iter().map(CorsRule::from_xml).collect(),
+            response_vary: element_text(xml, "ResponseVary").is_some_and(|v| v == "true"),
+        })
+    }
+}
+
+/// 生命周期规则中的存储类型转换，对象最后修改`days`天后转换为`storage_class`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LifecycleTransition {
+    pub days: u32,
+    pub storage_class: StorageClass,
+}
+
+/// 生命周期规则，作用于`prefix`开头的对象
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::bucket_config::LifecycleRule;
+/// use aliyun_oss_rust_sdk::entity::StorageClass;
+/// let rule = LifecycleRule::new("logs", "logs/")
+///     .with_transition(30, StorageClass::IA)
+///     .with_expiration_days(180)
+///     .with_abort_multipart_upload_days(7);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LifecycleRule {
+    pub id: String,
+    pub prefix: String,
+    /// 规则是否启用，默认true
+    pub enabled: bool,
+    /// 对象最后修改多少天后删除
+    pub expiration_days: Option<u32>,
+    pub transitions: Vec<LifecycleTransition>,
+    /// 分片上传初始化多少天后删除未完成的分片
+    pub abort_multipart_upload_days: Option<u32>,
+}
+
+impl LifecycleRule {
+    pub fn new<S: AsRef<str>>(id: S, prefix: S) -> Self {
+        Self {
+            id: id.as_ref().to_string(),
+            prefix: prefix.as_ref().to_string(),
+            enabled: true,
+            expiration_days: None,
+            transitions: Vec::new(),
+            abort_multipart_upload_days: None,
+        }
+    }
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+    pub fn with_expiration_days(mut self, days: u32) -> Self {
+        self.expiration_days = Some(days);
+        self
+    }
+    pub fn with_transition(mut self, days: u32, storage_class: StorageClass) -> Self {
+        self.transitions.push(LifecycleTransition { days, storage_class });
+        self
+    }
+    pub fn with_abort_multipart_upload_days(mut self, days: u32) -> Self {
+        self.abort_multipart_upload_days = Some(days);
+        self
+    }
+
+    fn write_xml(&self, xml: &mut XmlWriter) {
+        xml.start("Rule")
+            .element("ID", &self.id)
+            .element("Prefix", &self.prefix)
+            .element("Status", if self.enabled { "Enabled" } else { "Disabled" });
+        if let Some(days) = self.expiration_days {
+            xml.start("Expiration").element("Days", days.to_string()).end();
+        }
+        for transition in &self.transitions {
+            xml.start("Transition")
+                .element("Days", transition.days.to_string())
+                .element("StorageClass", transition.storage_class.to_string())
+                .end();
+        }
+        if let Some(days) = self.abort_multipart_upload_days {
+            xml.start("AbortMultipartUpload").element("Days", days.to_string()).end();
+        }
+        xml.end();
+    }
+
+    fn from_xml(xml: &str) -> Option<Self> {
+        let days = |tag: &str| {
+            elements(xml, tag)
+                .first()
+                .and_then(|element| element_text(element, "Days"))
+                .and_then(|days| days.parse().ok())
+        };
+        let transitions = elements(xml, "Transition")
+            .into_iter()
+            .map(|transition| {
+                Some(LifecycleTransition {
+                    days: element_text(transition, "Days")?.parse().ok()?,
+                    storage_class: parse_storage_class(&element_text(transition, "StorageClass")?)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            id: element_text(xml, "ID")?,
+            prefix: element_text(xml, "Prefix").unwrap_or_default(),
+            enabled: element_text(xml, "Status")? == "Enabled",
+            expiration_days: days("Expiration"),
+            transitions,
+            abort_multipart_upload_days: days("AbortMultipartUpload"),
+        })
+    }
+}
+
+/// bucket的生命周期配置(PutBucketLifecycle/GetBucketLifecycle)，put时会覆盖已有的全部规则
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LifecycleConfiguration {
+    pub rules: Vec<LifecycleRule>,
+}
+
+impl LifecycleConfiguration {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_rule(mut self, rule: LifecycleRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = XmlWriter::new();
+        xml.start("LifecycleConfiguration");
+        for rule in &self.rules {
+            rule.write_xml(&mut xml);
+        }
+        xml.finish()
+    }
+
+    pub(crate) fn from_xml(xml: &str) -> Result<Self, OssError> {
+        Ok(Self {
+            rules: elements(xml, "Rule")
+                .into_iter()
+                .map(LifecycleRule::from_xml)
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| invalid("lifecycle configuration", xml))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use crate::bucket_config::{
+        CorsConfiguration, CorsRule, LifecycleConfiguration, LifecycleRule, LoggingConfiguration,
+        RefererConfiguration, WebsiteConfiguration,
+    };
+    use crate::entity::StorageClass;
+    use crate::error::OssError;
+
+    /// put时生成的xml被get解析后要得到相同的配置，serde序列化后也要能还原
+    fn assert_round_trip<T: PartialEq + Debug + Serialize + DeserializeOwned>(
+        config: T,
+        to_xml: fn(&T) -> String,
+        from_xml: fn(&str) -> Result<T, OssError>,
+    ) {
+        let xml = to_xml(&config);
+        assert_eq!(from_xml(&xml).unwrap(), config, "xml: {}", xml);
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(serde_json::from_value::<T>(value).unwrap(), config);
+    }
+
+    #[test]
+    fn test_website_round_trip() {
+        assert_round_trip(
+            WebsiteConfiguration::new("index.html").with_error_document("errors/404&.html"),
+            WebsiteConfiguration::to_xml,
+            WebsiteConfiguration::from_xml,
+        );
+        assert_round_trip(
+            WebsiteConfiguration::new("index.html"),
+            WebsiteConfiguration::to_xml,
+            WebsiteConfiguration::from_xml,
+        );
+    }
+
+    #[test]
+    fn test_referer_round_trip() {
+        assert_round_trip(
+            RefererConfiguration::new(false)
+                .with_referer("https://www.example.com/?a=1&b=2")
+                .with_referer("https://*.example.com"),
+            RefererConfiguration::to_xml,
+            RefererConfiguration::from_xml,
+        );
+        assert_round_trip(RefererConfiguration::new(true), RefererConfiguration::to_xml, RefererConfiguration::from_xml);
+    }
+
+    #[test]
+    fn test_logging_round_trip() {
+        assert_round_trip(
+            LoggingConfiguration::new("log-bucket").with_target_prefix("logs/"),
+            LoggingConfiguration::to_xml,
+            LoggingConfiguration::from_xml,
+        );
+        assert_round_trip(
+            LoggingConfiguration::new("log-bucket"),
+            LoggingConfiguration::to_xml,
+            LoggingConfiguration::from_xml,
+        );
+        let disabled = "<BucketLoggingStatus></BucketLoggingStatus>";
+        assert_eq!(LoggingConfiguration::from_logging_status(disabled).unwrap(), None);
+    }
+
+    #[test]
+    fn test_cors_round_trip() {
+        let config = CorsConfiguration::new()
+            .with_rule(
+                CorsRule::new()
+                    .with_allowed_origin("https://www.example.com")
+                    .with_allowed_method("GET")
+                    .with_allowed_method("PUT")
+                    .with_allowed_header("*")
+                    .with_expose_header("x-oss-request-id")
+                    .with_max_age_seconds(600),
+            )
+            .with_rule(CorsRule::new().with_allowed_origin("*").with_allowed_method("HEAD"))
+            .with_response_vary(true);
+        assert_round_trip(config, CorsConfiguration::to_xml, CorsConfiguration::from_xml);
+        assert_round_trip(CorsConfiguration::new(), CorsConfiguration::to_xml, CorsConfiguration::from_xml);
+    }
+
+    #[test]
+    fn test_lifecycle_round_trip() {
+        let config = LifecycleConfiguration::new()
+            .with_rule(
+                LifecycleRule::new("logs", "logs/")
+                    .with_transition(30, StorageClass::IA)
+                    .with_transition(90, StorageClass::Archive)
+                    .with_expiration_days(180)
+                    .with_abort_multipart_upload_days(7),
+            )
+            .with_rule(LifecycleRule::new("tmp", "").with_enabled(false).with_expiration_days(1));
+        assert_round_trip(config, LifecycleConfiguration::to_xml, LifecycleConfiguration::from_xml);
+        let unknown = "<LifecycleConfiguration><Rule><ID>a</ID><Prefix></Prefix><Status>Enabled</Status>\
+            <Transition><Days>1</Days><StorageClass>Unknown</StorageClass></Transition></Rule></LifecycleConfiguration>";
+        assert!(LifecycleConfiguration::from_xml(unknown).is_err());
+    }
+}
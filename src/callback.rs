@@ -0,0 +1,349 @@
+//! 上传回调的签名校验，供接收OSS回调的应用服务器使用
+//!
+//! OSS回调应用服务器时会带上`x-oss-pub-key-url`和`Authorization`两个头，
+//! 应用服务器需要下载公钥并用RSA校验签名，确认请求确实来自OSS
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use base64::engine::general_purpose;
+use base64::Engine;
+use md5::{Digest, Md5};
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{Pkcs1v15Sign, RsaPublicKey};
+use crate::error::OssError;
+
+/// OSS回调公钥只能从这个域名下载，防止伪造的回调指定自己的公钥
+const PUB_KEY_HOST: &str = "gosspublic.alicdn.com";
+
+/// 缓存的公钥超过这个时间后重新下载，OSS更换公钥后旧的缓存不会一直生效
+const PUB_KEY_TTL: Duration = Duration::from_secs(3600);
+/// 最多缓存的公钥个数，`x-oss-pub-key-url`由请求方提供，不限制的话缓存会无限增长
+const PUB_KEY_CAPACITY: usize = 16;
+
+/// 按url缓存的PEM公钥，过期的条目不会返回，满了之后先清理过期条目，再淘汰最早缓存的条目
+#[derive(Debug, Default)]
+struct PubKeyCache {
+    entries: HashMap<String, (Instant, String)>,
+}
+
+impl PubKeyCache {
+    fn get(&self, url: &str, now: Instant) -> Option<String> {
+        self.entries
+            .get(url)
+            .filter(|(inserted, _)| now.saturating_duration_since(*inserted) < PUB_KEY_TTL)
+            .map(|(_, pem)| pem.to_string())
+    }
+
+    fn insert(&mut self, url: String, pem: String, now: Instant) {
+        if self.entries.len() >= PUB_KEY_CAPACITY && !self.entries.contains_key(&url) {
+            self.entries
+                .retain(|_, (inserted, _)| now.saturating_duration_since(*inserted) < PUB_KEY_TTL);
+            if self.entries.len() >= PUB_KEY_CAPACITY {
+                let oldest = self
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, (inserted, _))| *inserted)
+                    .map(|(url, _)| url.to_string());
+                if let Some(oldest) = oldest {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+        self.entries.insert(url, (now, pem));
+    }
+}
+
+fn pub_key_cache() -> &'static Mutex<PubKeyCache> {
+    static CACHE: OnceLock<Mutex<PubKeyCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(PubKeyCache::default()))
+}
+
+/// `x-oss-pub-key-url`头的值是base64编码的url，这里两种格式都接受
+fn pub_key_url(pub_key_url: &str) -> Result<String, OssError> {
+    let url = if pub_key_url.starts_with("http://") || pub_key_url.starts_with("https://") {
+        pub_key_url.to_string()
+    } else {
+        let decoded = general_purpose::STANDARD.decode(pub_key_url.trim())?;
+        String::from_utf8(decoded)
+            .map_err(|e| OssError::Err(format!("invalid pub key url: {}", e)))?
+    };
+    let host = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .and_then(|rest| rest.split('/').next())
+        .unwrap_or_default();
+    if host != PUB_KEY_HOST {
+        return Err(OssError::Err(format!("untrusted pub key url: {}", url)));
+    }
+    Ok(url)
+}
+
+/// 待签名内容：url解码后的path + query(如有) + `\n` + body
+fn sign_content(path_and_query: &str, body: &[u8]) -> Result<Vec<u8>, OssError> {
+    let (path, query) = match path_and_query.find('?') {
+        Some(index) => path_and_query.split_at(index),
+        None => (path_and_query, ""),
+    };
+    let path = urlencoding::decode(path)
+        .map_err(|e| OssError::Err(format!("invalid callback path: {}", e)))?;
+    let mut content = Vec::with_capacity(path.len() + query.len() + 1 + body.len());
+    content.extend_from_slice(path.as_bytes());
+    content.extend_from_slice(query.as_bytes());
+    content.push(b'\n');
+    content.extend_from_slice(body);
+    Ok(content)
+}
+
+/// 使用已获取的PEM公钥校验回调签名
+///
+/// 签名不匹配时返回`Ok(false)`，公钥或签名格式错误时返回`Err`
+/// # 使用例子
+/// ```rust
+/// use aliyun_oss_rust_sdk::callback;
+/// let pem = "-----BEGIN PUBLIC KEY-----...";
+/// let ok = callback::verify_with_pem(pem, "base64 authorization", "/callback?a=1", b"bucket=b").unwrap();
+/// ```
+pub fn verify_with_pem(
+    pem: &str,
+    authorization_b64: &str,
+    path_and_query: &str,
+    body: &[u8],
+) -> Result<bool, OssError> {
+    let digest = Md5::digest(sign_content(path_and_query, body)?);
+    verify_digest(pem, authorization_b64, &digest)
+}
+
+/// 用公钥校验签名内容的MD5摘要
+fn verify_digest(pem: &str, authorization_b64: &str, digest: &[u8]) -> Result<bool, OssError> {
+    let public_key = RsaPublicKey::from_public_key_pem(pem.trim())
+        .map_err(|e| OssError::Err(format!("invalid pub key: {}", e)))?;
+    let signature = general_purpose::STANDARD.decode(authorization_b64.trim())?;
+    Ok(public_key
+        .verify(Pkcs1v15Sign::new::<Md5>(), digest, &signature)
+        .is_ok())
+}
+
+/// 校验OSS回调请求的签名，公钥会按url缓存一小时
+///
+/// # 使用例子
+/// ```rust
+/// use aliyun_oss_rust_sdk::callback;
+/// let ok = callback::verify(
+///     "x-oss-pub-key-url header",
+///     "authorization header",
+///     "/callback?a=1",
+///     b"bucket=b&object=a.txt",
+/// ).await.unwrap();
+/// ```
+#[cfg(not(feature = "blocking"))]
+pub async fn verify(
+    pub_key_url: &str,
+    authorization_b64: &str,
+    path_and_query: &str,
+    body: &[u8],
+) -> Result<bool, OssError> {
+    let url = self::pub_key_url(pub_key_url)?;
+    let cached = pub_key_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&url, Instant::now());
+    let pem = match cached {
+        Some(pem) => pem,
+        None => {
+            let response = reqwest::Client::new().get(&url).send().await?;
+            if !response.status().is_success() {
+                return Err(OssError::Err(format!(
+                    "get pub key error status: {}",
+                    response.status()
+                )));
+            }
+            let pem = response.text().await?;
+            pub_key_cache()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(url, pem.clone(), Instant::now());
+            pem
+        }
+    };
+    verify_with_pem(&pem, authorization_b64, path_and_query, body)
+}
+
+/// 校验OSS回调请求的签名，公钥会按url缓存一小时
+///
+/// # 使用例子
+/// ```rust
+/// use aliyun_oss_rust_sdk::callback;
+/// let ok = callback::verify(
+///     "x-oss-pub-key-url header",
+///     "authorization header",
+///     "/callback?a=1",
+///     b"bucket=b&object=a.txt",
+/// ).unwrap();
+/// ```
+#[cfg(feature = "blocking")]
+pub fn verify(
+    pub_key_url: &str,
+    authorization_b64: &str,
+    path_and_query: &str,
+    body: &[u8],
+) -> Result<bool, OssError> {
+    let url = self::pub_key_url(pub_key_url)?;
+    let cached = pub_key_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&url, Instant::now());
+    let pem = match cached {
+        Some(pem) => pem,
+        None => {
+            let response = reqwest::blocking::Client::new().get(&url).send()?;
+            if !response.status().is_success() {
+                return Err(OssError::Err(format!(
+                    "get pub key error status: {}",
+                    response.status()
+                )));
+            }
+            let pem = response.text()?;
+            pub_key_cache()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(url, pem.clone(), Instant::now());
+            pem
+        }
+    };
+    verify_with_pem(&pem, authorization_b64, path_and_query, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use crate::callback::{
+        pub_key_cache, pub_key_url, sign_content, verify_digest, verify_with_pem, PubKeyCache, PUB_KEY_CAPACITY,
+        PUB_KEY_TTL,
+    };
+    use crate::util::base64_encode;
+
+    /// OSS文档回调示例中的`x-oss-pub-key-url`头
+    const DOC_PUB_KEY_URL: &str = "aHR0cDovL2dvc3NwdWJsaWMuYWxpY2RuLmNvbS9jYWxsYmFja19wdWJfa2V5X3YxLnBlbQ==";
+    /// `http://gosspublic.alicdn.com/callback_pub_key_v1.pem`的内容
+    const DOC_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MFwwDQYJKoZIhvcNAQEBBQADSwAwSAJBAKs/JBGzwUB2aVht4crBx3oIPBLNsjGs
+C0fTXv+nvlmklvkcolvpvXLTjaxUHR3W9LXxQ2EHXAJfCB+6H2YF1k8CAwEAAQ==
+-----END PUBLIC KEY-----";
+    /// OSS文档回调示例中的`authorization`头
+    const DOC_AUTHORIZATION: &str = "kKQeGTRccDKyHB3H9vF+xYMSrmhMZjzzl2/kdD1ktNVgbWEfYTQG0G2SU/RaHBovRCE8OkQDjC3uG33esH2txA==";
+    /// [DOC_AUTHORIZATION]签名的MD5摘要
+    const DOC_DIGEST: [u8; 16] = [
+        0x5b, 0x74, 0xfc, 0x97, 0xcc, 0x38, 0x5b, 0x45, 0xc6, 0xc8, 0xb7, 0x78, 0x2d, 0x5a, 0x21, 0x40,
+    ];
+
+    const PEM: &str = "-----BEGIN PUBLIC KEY-----
+MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQDHw9jIjBjLABrgZbSTgdEgwqjQ
+yMBdzyz1YdDgzCqTL06fuBlyupkIc2yXH3z0OVSMqfnpeNdZqFvVjv8c2Brp18rI
+A160txlx4Cm17Fk+gAEeya3LwGHM0NEQUQCHEBpoXbDqlErfbbpiQM7Y7kYhbeye
+mI0Fu1JGnXGb6JIKvwIDAQAB
+-----END PUBLIC KEY-----";
+    const AUTHORIZATION: &str = "cCC5T+BCx0xo/txW1UOCbLPyeEtiqhDqvBYIBFtkrJwEEKL3bhoBr02E/6M6dHZSdPnUExZLKfua34tJWW/eIWlOqX2q2f0Pe1kUVFyzKqwVQR3O6r4JEkX39hdrBtc61c20p+giiFQ3EDLBmZOAgpKkFrMlYK/o8dzwbyo2nB8=";
+    const PATH: &str = "/callback/notify%20me?from=oss";
+    const BODY: &[u8] = b"bucket=examplebucket&object=exampleobject.txt&size=1024";
+
+    #[test]
+    fn test_sign_content() {
+        let content = sign_content(PATH, b"a=1").unwrap();
+        assert_eq!(content, b"/callback/notify me?from=oss\na=1");
+        let content = sign_content("/cb", b"").unwrap();
+        assert_eq!(content, b"/cb\n");
+    }
+
+    #[test]
+    fn test_pub_key_url() {
+        let url = "https://gosspublic.alicdn.com/callback_pub_key_v1.pem";
+        assert_eq!(pub_key_url(url).unwrap(), url);
+        assert_eq!(pub_key_url(&base64_encode(url)).unwrap(), url);
+        assert!(pub_key_url("https://example.com/callback_pub_key_v1.pem").is_err());
+        assert!(pub_key_url("https://gosspublic.alicdn.com.evil.com/key.pem").is_err());
+        assert!(pub_key_url("https://gosspublic.alicdn.com@evil.com/key.pem").is_err());
+        assert_eq!(
+            pub_key_url(DOC_PUB_KEY_URL).unwrap(),
+            "http://gosspublic.alicdn.com/callback_pub_key_v1.pem"
+        );
+    }
+
+    #[test]
+    fn test_verify_doc_sample() {
+        assert!(verify_digest(DOC_PEM, DOC_AUTHORIZATION, &DOC_DIGEST).unwrap());
+        let mut digest = DOC_DIGEST;
+        digest[0] ^= 1;
+        assert!(!verify_digest(DOC_PEM, DOC_AUTHORIZATION, &digest).unwrap());
+        assert!(!verify_with_pem(DOC_PEM, DOC_AUTHORIZATION, "/index.html", b"bucket=other").unwrap());
+    }
+
+    #[test]
+    fn test_verify_with_pem() {
+        assert!(verify_with_pem(PEM, AUTHORIZATION, PATH, BODY).unwrap());
+        assert!(!verify_with_pem(PEM, AUTHORIZATION, PATH, b"bucket=examplebucket").unwrap());
+        assert!(!verify_with_pem(PEM, AUTHORIZATION, "/callback/other?from=oss", BODY).unwrap());
+        assert!(verify_with_pem(PEM, "not base64!", PATH, BODY).is_err());
+        assert!(verify_with_pem("not a pem", AUTHORIZATION, PATH, BODY).is_err());
+    }
+
+    #[test]
+    fn test_pub_key_cache_ttl_and_capacity() {
+        let start = Instant::now();
+        let mut cache = PubKeyCache::default();
+        cache.insert("a".to_string(), PEM.to_string(), start);
+        assert_eq!(cache.get("a", start + PUB_KEY_TTL - Duration::from_secs(1)).as_deref(), Some(PEM));
+        assert_eq!(cache.get("a", start + PUB_KEY_TTL), None);
+        for i in 1..=PUB_KEY_CAPACITY {
+            cache.insert(i.to_string(), PEM.to_string(), start + Duration::from_secs(i as u64));
+        }
+        // 满了之后淘汰最早缓存的条目
+        assert_eq!(cache.entries.len(), PUB_KEY_CAPACITY);
+        assert!(cache.get("a", start).is_none());
+        assert!(cache.get("1", start).is_some());
+        // 有过期条目时先清理过期条目
+        let later = start + PUB_KEY_TTL + Duration::from_secs(3);
+        cache.insert("b".to_string(), PEM.to_string(), later);
+        assert_eq!(cache.entries.len(), PUB_KEY_CAPACITY - 2);
+        assert!(cache.get("b", later).is_some());
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    #[tokio::test]
+    async fn test_verify_cached_pub_key() {
+        let url = "https://gosspublic.alicdn.com/test_cached_pub_key.pem";
+        pub_key_cache().lock().unwrap().insert(url.to_string(), PEM.to_string(), Instant::now());
+        pub_key_cache()
+            .lock()
+            .unwrap()
+            .insert(
+                "http://gosspublic.alicdn.com/callback_pub_key_v1.pem".to_string(),
+                DOC_PEM.to_string(),
+                Instant::now(),
+            );
+        assert!(!crate::callback::verify(DOC_PUB_KEY_URL, DOC_AUTHORIZATION, "/index.html", b"bucket=other")
+            .await
+            .unwrap());
+        let ok = crate::callback::verify(&base64_encode(url), AUTHORIZATION, PATH, BODY)
+            .await
+            .unwrap();
+        assert!(ok);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_verify_cached_pub_key() {
+        let url = "https://gosspublic.alicdn.com/test_cached_pub_key.pem";
+        pub_key_cache().lock().unwrap().insert(url.to_string(), PEM.to_string(), Instant::now());
+        pub_key_cache()
+            .lock()
+            .unwrap()
+            .insert(
+                "http://gosspublic.alicdn.com/callback_pub_key_v1.pem".to_string(),
+                DOC_PEM.to_string(),
+                Instant::now(),
+            );
+        assert!(!crate::callback::verify(DOC_PUB_KEY_URL, DOC_AUTHORIZATION, "/index.html", b"bucket=other").unwrap());
+        let ok = crate::callback::verify(&base64_encode(url), AUTHORIZATION, PATH, BODY).unwrap();
+        assert!(ok);
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt::Debug;
+use std::sync::RwLock;
+use std::time::Duration;
+use crate::request::Seconds;
+
+/// AccessKey凭证
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub key_id: String,
+    pub key_secret: String,
+    /// STS临时凭证的SecurityToken，设置后请求会带上`x-oss-security-token`
+    pub security_token: Option<String>,
+}
+
+impl Credentials {
+    pub fn new<S: Into<String>>(key_id: S, key_secret: S) -> Self {
+        Self {
+            key_id: key_id.into(),
+            key_secret: key_secret.into(),
+            security_token: None,
+        }
+    }
+    pub fn with_security_token<S: Into<String>>(mut self, security_token: S) -> Self {
+        self.security_token = Some(security_token.into());
+        self
+    }
+}
+
+/// 凭证来源，每次签名都会调用一次[CredentialProvider::credentials]，使用同一份凭证完成签名
+pub trait CredentialProvider: Debug + Send + Sync {
+    fn credentials(&self) -> Credentials;
+}
+
+impl CredentialProvider for Credentials {
+    fn credentials(&self) -> Credentials {
+        self.clone()
+    }
+}
+
+/// 可以在运行时替换的凭证，适合定期刷新的STS临时凭证
+///
+/// # 使用例子
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use aliyun_oss_rust_sdk::config::{Credentials, OssConfig, RotatingCredentials};
+/// use aliyun_oss_rust_sdk::oss::OSS;
+/// let credentials = Arc::new(RotatingCredentials::new(Credentials::new("key_id", "key_secret")));
+/// let oss = OSS::with_credential_provider(credentials.clone(), OssConfig::new("oss-cn-hangzhou.aliyuncs.com", "my-bucket"));
+/// // 刷新任务拿到新的STS凭证后替换，之后发起的请求立即使用新凭证
+/// credentials.set(Credentials::new("sts_key_id", "sts_key_secret").with_security_token("token"));
+/// ```
+#[derive(Debug)]
+pub struct RotatingCredentials {
+    current: RwLock<Credentials>,
+}
+
+impl RotatingCredentials {
+    pub fn new(credentials: Credentials) -> Self {
+        Self {
+            current: RwLock::new(credentials),
+        }
+    }
+    /// 替换凭证，已经签名的请求不受影响
+    pub fn set(&self, credentials: Credentials) {
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = credentials;
+    }
+}
+
+impl CredentialProvider for RotatingCredentials {
+    fn credentials(&self) -> Credentials {
+        self.current.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// OSS实例的全局配置，配合[crate::oss::OSS::with_config]使用
+///
+/// 连接相关的配置(超时、User-Agent、TCP选项)作用于内部的http client，
+/// 请求相关的配置(过期时间、https、签名版本)作为[crate::oss::OSS::request]返回的RequestBuilder的初始值，
+/// 单次请求在RequestBuilder上的设置优先于这里的配置
+///
+/// # 使用例子
+///
+/// ```rust
+/// use std::time::Duration;
+/// use aliyun_oss_rust_sdk::config::{Credentials, OssConfig};
+/// use aliyun_oss_rust_sdk::oss::OSS;
+/// let config = OssConfig::new("oss-cn-hangzhou.aliyuncs.com", "my-bucket")
+///     .with_timeout(Duration::from_secs(30))
+///     .with_user_agent("my-app/1.0")
+///     .with_expire(600);
+/// let oss = OSS::with_config(Credentials::new("key_id", "key_secret"), config);
+/// assert_eq!(oss.request().expire, 600);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OssConfig {
+    pub endpoint: String,
+    /// 默认的bucket，为空时只能进行服务级别的操作
+    pub bucket: String,
+    /// 整个请求的超时时间，默认不限制
+    pub timeout: Option<Duration>,
+    /// 建立连接的超时时间，默认不限制
+    pub connect_timeout: Option<Duration>,
+    pub user_agent: Option<String>,
+    pub tcp_keepalive: Option<Duration>,
+    pub tcp_nodelay: Option<bool>,
+    /// 每秒最多发起的请求数，0按1处理，见[crate::oss::OSS::with_rate_limit]
+    pub rate_limit: Option<u32>,
+    /// 签名URL的默认过期时间(秒)，默认60秒
+    pub expire: Seconds,
+    /// endpoint没有写明协议时是否使用https，默认为true，对签名URL和API请求都生效
+    pub https: bool,
+    /// 签名URL默认使用OSS2签名版本，只对签名URL生效，API请求仍然使用V1签名
+    pub signature_version2: bool,
+    /// 使用`endpoint/bucket/key`形式的URL，而不是`bucket.endpoint/key`
+    pub path_style: bool,
+    /// endpoint是绑定到bucket的自定义域名(CNAME)，使用`endpoint/key`形式的URL，签名仍然使用配置的bucket
+    pub cname: bool,
+}
+
+impl Default for OssConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "oss-cn-hangzhou.aliyuncs.com".to_string(),
+            bucket: String::new(),
+            timeout: None,
+            connect_timeout: None,
+            user_agent: None,
+            tcp_keepalive: None,
+            tcp_nodelay: None,
+            rate_limit: None,
+            expire: 60,
+            https: true,
+            signature_version2: false,
+            path_style: false,
+            cname: false,
+        }
+    }
+}
+
+impl OssConfig {
+    pub fn new<S: Into<String>>(endpoint: S, bucket: S) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            bucket: bucket.into(),
+            ..Self::default()
+        }
+    }
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+    pub fn with_user_agent<S: AsRef<str>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.as_ref().to_string());
+        self
+    }
+    pub fn with_tcp_keepalive(mut self, keepalive: Duration) -> Self {
+        self.tcp_keepalive = Some(keepalive);
+        self
+    }
+    pub fn with_tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp_nodelay = Some(nodelay);
+        self
+    }
+    pub fn with_rate_limit(mut self, rps: u32) -> Self {
+        self.rate_limit = Some(rps);
+        self
+    }
+    pub fn with_expire(mut self, expire: Seconds) -> Self {
+        self.expire = expire;
+        self
+    }
+    pub fn with_https(mut self, https: bool) -> Self {
+        self.https = https;
+        self
+    }
+    pub fn with_signature_version2(mut self, signature_version2: bool) -> Self {
+        self.signature_version2 = signature_version2;
+        self
+    }
+    pub fn with_path_style(mut self, path_style: bool) -> Self {
+        self.path_style = path_style;
+        self
+    }
+    /// endpoint设置为绑定到bucket的自定义域名时开启，例如`https://static.example.com`
+    pub fn with_cname(mut self, cname: bool) -> Self {
+        self.cname = cname;
+        self
+    }
+}
@@ -0,0 +1,74 @@
+//! 签名、签名URL和上传Policy中使用的时间格式
+use chrono::{DateTime, Datelike, NaiveDateTime, Timelike, Utc};
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// HTTP Date头的格式，例如`Sun, 22 Nov 2015 08:16:38 GMT`
+///
+/// OSS要求英文的星期和月份，这里不用`%a`/`%b`，避免开启chrono的locale后格式化成其它语言导致签名失败
+pub(crate) fn http_date(t: DateTime<Utc>) -> String {
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        WEEKDAYS[t.weekday().num_days_from_monday() as usize],
+        t.day(),
+        MONTHS[t.month0() as usize],
+        t.year(),
+        t.hour(),
+        t.minute(),
+        t.second()
+    )
+}
+
+/// 解析响应中的HTTP Date头，格式不对时返回None
+pub(crate) fn parse_http_date(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(s).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// 签名URL中`Expires`参数使用的Unix时间戳(秒)
+pub(crate) fn expires_epoch(t: DateTime<Utc>) -> i64 {
+    t.timestamp()
+}
+
+/// 上传Policy中`expiration`使用的ISO8601 UTC时间，例如`2015-11-22T08:16:38.000Z`
+pub(crate) fn iso8601(t: DateTime<Utc>) -> String {
+    t.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+/// 解析XML响应中的ISO8601时间，例如`2024-01-01T00:00:00.000Z`，没有时区的`2020-10-15T15:50:32`按UTC处理
+pub(crate) fn parse_iso8601(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f").map(|t| t.and_utc()))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use crate::date::{expires_epoch, http_date, iso8601, parse_http_date, parse_iso8601};
+
+    #[test]
+    fn test_known_instant() {
+        let t = Utc.with_ymd_and_hms(2015, 11, 22, 8, 16, 38).unwrap();
+        assert_eq!(http_date(t), "Sun, 22 Nov 2015 08:16:38 GMT");
+        assert_eq!(expires_epoch(t), 1448180198);
+        assert_eq!(iso8601(t), "2015-11-22T08:16:38.000Z");
+        assert_eq!(parse_http_date("Sun, 22 Nov 2015 08:16:38 GMT"), Some(t));
+        assert_eq!(parse_http_date("2015-11-22T08:16:38Z"), None);
+        assert_eq!(parse_iso8601("2015-11-22T08:16:38.000Z"), Some(t));
+        assert_eq!(parse_iso8601("2015-11-22T08:16:38"), Some(t));
+        assert_eq!(parse_iso8601("Sun, 22 Nov 2015 08:16:38 GMT"), None);
+    }
+
+    #[test]
+    fn test_http_date_english_names() {
+        let t = Utc.with_ymd_and_hms(2024, 2, 5, 3, 4, 5).unwrap();
+        assert_eq!(http_date(t), "Mon, 05 Feb 2024 03:04:05 GMT");
+        // 每个星期和月份都和chrono默认(英文)的输出一致
+        for day in 0..366 {
+            let t = Utc.with_ymd_and_hms(2023, 1, 1, 23, 59, 59).unwrap() + chrono::Duration::days(day);
+            assert_eq!(http_date(t), t.format("%a, %d %b %Y %T GMT").to_string());
+        }
+    }
+}
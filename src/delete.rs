@@ -0,0 +1,54 @@
+use crate::xml::{element_text, elements, XmlWriter};
+
+/// 一次DeleteMultipleObjects请求最多删除的对象数量
+pub(crate) const MAX_DELETE_OBJECTS: usize = 1000;
+
+/// DeleteMultipleObjects的请求body，key去掉开头的`/`
+pub(crate) fn delete_objects_xml<S: AsRef<str>>(keys: &[S], quiet: bool) -> String {
+    let mut xml = XmlWriter::new();
+    xml.start("Delete").element("Quiet", quiet.to_string());
+    for key in keys {
+        xml.start("Object")
+            .element("Key", key.as_ref().trim_start_matches('/'))
+            .end();
+    }
+    xml.finish()
+}
+
+/// 解析DeleteMultipleObjects响应中删除成功的key，quiet模式下OSS不返回body，结果为空
+pub(crate) fn deleted_keys_from_xml(xml: &str) -> Vec<String> {
+    elements(xml, "Deleted")
+        .into_iter()
+        .filter_map(|deleted| element_text(deleted, "Key"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::delete::{deleted_keys_from_xml, delete_objects_xml};
+
+    #[test]
+    fn test_delete_objects_xml() {
+        assert_eq!(
+            delete_objects_xml(&["/a.txt", "b&c.txt"], false),
+            "<Delete><Quiet>false</Quiet><Object><Key>a.txt</Key></Object>\
+<Object><Key>b&amp;c.txt</Key></Object></Delete>"
+        );
+        assert!(delete_objects_xml(&["a.txt"], true).starts_with("<Delete><Quiet>true</Quiet>"));
+    }
+
+    #[test]
+    fn test_deleted_keys_from_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<DeleteResult>
+  <Deleted>
+    <Key>a.txt</Key>
+  </Deleted>
+  <Deleted>
+    <Key>b&amp;c.txt</Key>
+  </Deleted>
+</DeleteResult>"#;
+        assert_eq!(deleted_keys_from_xml(xml), vec!["a.txt", "b&c.txt"]);
+        assert!(deleted_keys_from_xml("").is_empty());
+    }
+}
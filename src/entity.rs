@@ -1,7 +1,18 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
-use crate::request::Seconds;
+use crate::error::OssError;
+use crate::metadata::ObjectMetadata;
+use crate::multipart::{MAX_PART_SIZE, MIN_PART_SIZE};
+use crate::request::{RequestBuilder, Seconds};
+use crate::util::base64_encode;
+use crate::xml::{element_text, elements};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PolicyResp {
     pub access_id: String,
     pub host: String,
@@ -59,3 +70,728 @@ impl PolicyBuilder {
         self
     }
 }
+
+/// 存储类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageClass {
+    Standard,
+    IA,
+    Archive,
+    ColdArchive,
+    DeepColdArchive,
+}
+
+impl StorageClass {
+    /// 归档类型的对象需要解冻(restore)后才能读取
+    pub fn is_cold(&self) -> bool {
+        matches!(self, StorageClass::Archive | StorageClass::ColdArchive | StorageClass::DeepColdArchive)
+    }
+}
+
+impl Display for StorageClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageClass::Standard => write!(f, "Standard"),
+            StorageClass::IA => write!(f, "IA"),
+            StorageClass::Archive => write!(f, "Archive"),
+            StorageClass::ColdArchive => write!(f, "ColdArchive"),
+            StorageClass::DeepColdArchive => write!(f, "DeepColdArchive"),
+        }
+    }
+}
+
+/// 拷贝对象时元数据或标签的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Directive {
+    /// 沿用源对象的
+    Copy,
+    /// 使用请求中指定的
+    Replace,
+}
+
+impl Display for Directive {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Directive::Copy => write!(f, "COPY"),
+            Directive::Replace => write!(f, "REPLACE"),
+        }
+    }
+}
+
+/// 对象的访问权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectAcl {
+    /// 继承bucket的访问权限
+    Default,
+    Private,
+    PublicRead,
+    PublicReadWrite,
+}
+
+impl Display for ObjectAcl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectAcl::Default => write!(f, "default"),
+            ObjectAcl::Private => write!(f, "private"),
+            ObjectAcl::PublicRead => write!(f, "public-read"),
+            ObjectAcl::PublicReadWrite => write!(f, "public-read-write"),
+        }
+    }
+}
+
+impl FromStr for ObjectAcl {
+    type Err = OssError;
+
+    fn from_str(acl: &str) -> Result<Self, Self::Err> {
+        match acl {
+            "default" => Ok(ObjectAcl::Default),
+            "private" => Ok(ObjectAcl::Private),
+            "public-read" => Ok(ObjectAcl::PublicRead),
+            "public-read-write" => Ok(ObjectAcl::PublicReadWrite),
+            other => Err(OssError::Err(format!("unknown object acl: {}", other))),
+        }
+    }
+}
+
+/// 拷贝对象的选项
+///
+/// 指定了元数据或标签时，对应的directive会自动设置为[Directive::Replace]
+#[derive(Debug, Clone, Default)]
+pub struct CopyOptions {
+    pub metadata_directive: Option<Directive>,
+    /// 用户元数据，key不需要带`x-oss-meta-`前缀
+    pub metadata: Vec<(String, String)>,
+    pub tagging_directive: Option<Directive>,
+    pub tags: Vec<(String, String)>,
+    pub acl: Option<ObjectAcl>,
+    pub storage_class: Option<StorageClass>,
+    pub if_match: Option<String>,
+    pub if_none_match: Option<String>,
+    pub if_modified_since: Option<String>,
+    pub if_unmodified_since: Option<String>,
+}
+
+impl CopyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_metadata_directive(mut self, directive: Directive) -> Self {
+        self.metadata_directive = Some(directive);
+        self
+    }
+    pub fn with_metadata<S: AsRef<str>>(mut self, key: S, value: S) -> Self {
+        self.metadata_directive = Some(Directive::Replace);
+        self.metadata.push((key.as_ref().to_string(), value.as_ref().to_string()));
+        self
+    }
+    pub fn with_tagging_directive(mut self, directive: Directive) -> Self {
+        self.tagging_directive = Some(directive);
+        self
+    }
+    pub fn with_tag<S: AsRef<str>>(mut self, key: S, value: S) -> Self {
+        self.tagging_directive = Some(Directive::Replace);
+        self.tags.push((key.as_ref().to_string(), value.as_ref().to_string()));
+        self
+    }
+    pub fn with_acl(mut self, acl: ObjectAcl) -> Self {
+        self.acl = Some(acl);
+        self
+    }
+    pub fn with_storage_class(mut self, storage_class: StorageClass) -> Self {
+        self.storage_class = Some(storage_class);
+        self
+    }
+    /// 源对象的ETag匹配时才拷贝
+    pub fn with_if_match<S: AsRef<str>>(mut self, etag: S) -> Self {
+        self.if_match = Some(etag.as_ref().to_string());
+        self
+    }
+    /// 源对象的ETag不匹配时才拷贝
+    pub fn with_if_none_match<S: AsRef<str>>(mut self, etag: S) -> Self {
+        self.if_none_match = Some(etag.as_ref().to_string());
+        self
+    }
+    /// 源对象在指定时间(GMT格式)之后修改过才拷贝
+    pub fn with_if_modified_since<S: AsRef<str>>(mut self, date: S) -> Self {
+        self.if_modified_since = Some(date.as_ref().to_string());
+        self
+    }
+    /// 源对象在指定时间(GMT格式)之后没有修改过才拷贝
+    pub fn with_if_unmodified_since<S: AsRef<str>>(mut self, date: S) -> Self {
+        self.if_unmodified_since = Some(date.as_ref().to_string());
+        self
+    }
+
+    /// 把选项转换成拷贝请求的oss头
+    pub(crate) fn apply(&self, mut build: RequestBuilder) -> RequestBuilder {
+        if let Some(directive) = self.metadata_directive {
+            build = build.oss_header_put("x-oss-metadata-directive".to_string(), directive.to_string());
+        }
+        for (key, value) in &self.metadata {
+            build = build.oss_header_put(format!("x-oss-meta-{}", key), value.to_string());
+        }
+        if let Some(directive) = self.tagging_directive {
+            build = build.oss_header_put("x-oss-tagging-directive".to_string(), directive.to_string());
+        }
+        if !self.tags.is_empty() {
+            build = build.oss_header_put("x-oss-tagging".to_string(), tagging_header(&self.tags));
+        }
+        if let Some(acl) = self.acl {
+            build = build.oss_header_put("x-oss-object-acl".to_string(), acl.to_string());
+        }
+        if let Some(storage_class) = self.storage_class {
+            build = build.oss_header_put("x-oss-storage-class".to_string(), storage_class.to_string());
+        }
+        let conditions = [
+            ("x-oss-copy-source-if-match", &self.if_match),
+            ("x-oss-copy-source-if-none-match", &self.if_none_match),
+            ("x-oss-copy-source-if-modified-since", &self.if_modified_since),
+            ("x-oss-copy-source-if-unmodified-since", &self.if_unmodified_since),
+        ];
+        for (key, value) in conditions {
+            if let Some(value) = value {
+                build = build.oss_header_put(key, value.as_str());
+            }
+        }
+        build
+    }
+}
+
+/// 取消传输的令牌，clone后共享同一个状态
+///
+/// 通过[RequestBuilder::with_cancel_token](crate::request::RequestBuilder::with_cancel_token)传给请求，
+/// 流式下载在每次读取数据块之间检查，取消后返回[OssError::Cancelled]
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::entity::CancelToken;
+/// let token = CancelToken::new();
+/// let handle = token.clone();
+/// handle.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// 下载中断后自动续传的选项
+#[derive(Debug, Clone, Copy)]
+pub struct ResumeOptions {
+    /// 最多续传次数，为0时不续传
+    pub max_attempts: u32,
+    /// 第一次续传前的等待时间，之后每次翻倍
+    pub backoff: Duration,
+}
+
+impl Default for ResumeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResumeOptions {
+    pub fn new() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
+    /// 不续传，连接断开直接返回错误
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 0,
+            backoff: Duration::ZERO,
+        }
+    }
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+    /// 第attempt次(从1开始)续传前的等待时间
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        self.backoff * 2u32.saturating_pow(attempt.saturating_sub(1))
+    }
+}
+
+/// 上传回调参数，OSS上传完成后会请求`url`通知应用服务器
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::entity::Callback;
+/// use aliyun_oss_rust_sdk::request::RequestBuilder;
+/// let callback = Callback::new("https://example.com/callback", "bucket=${bucket}&object=${object}&uid=${x:uid}")
+///     .with_var("x:uid", "10086")
+///     .unwrap();
+/// let build = RequestBuilder::new().with_callback(&callback);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Callback {
+    #[serde(rename = "callbackUrl")]
+    pub url: String,
+    #[serde(rename = "callbackHost", skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// 回调请求的body，可以使用`${bucket}`、`${object}`等系统变量和`${x:xxx}`自定义变量
+    #[serde(rename = "callbackBody")]
+    pub body: String,
+    #[serde(rename = "callbackBodyType", skip_serializing_if = "Option::is_none")]
+    pub body_type: Option<String>,
+    #[serde(rename = "callbackSNI", skip_serializing_if = "Option::is_none")]
+    pub sni: Option<bool>,
+    /// 自定义变量，key必须以`x:`开头
+    #[serde(skip)]
+    pub vars: BTreeMap<String, String>,
+}
+
+impl Callback {
+    pub fn new<S: AsRef<str>>(url: S, body: S) -> Self {
+        Self {
+            url: url.as_ref().to_string(),
+            host: None,
+            body: body.as_ref().to_string(),
+            body_type: None,
+            sni: None,
+            vars: BTreeMap::new(),
+        }
+    }
+    pub fn with_host<S: AsRef<str>>(mut self, host: S) -> Self {
+        self.host = Some(host.as_ref().to_string());
+        self
+    }
+    /// 例如`application/json`，默认为`application/x-www-form-urlencoded`
+    pub fn with_body_type<S: AsRef<str>>(mut self, body_type: S) -> Self {
+        self.body_type = Some(body_type.as_ref().to_string());
+        self
+    }
+    pub fn with_sni(mut self, sni: bool) -> Self {
+        self.sni = Some(sni);
+        self
+    }
+    /// 添加自定义变量，key必须以`x:`开头，例如`x:uid`，否则返回错误
+    pub fn with_var<S: AsRef<str>>(mut self, key: S, value: S) -> Result<Self, OssError> {
+        let key = key.as_ref();
+        if !key.starts_with("x:") || key.len() <= 2 {
+            return Err(OssError::Err(format!("callback var must start with x: but got {}", key)));
+        }
+        self.vars.insert(key.to_string(), value.as_ref().to_string());
+        Ok(self)
+    }
+
+    /// `x-oss-callback`头的值
+    pub(crate) fn callback_header(&self) -> String {
+        base64_encode(serde_json::to_string(self).unwrap_or_default())
+    }
+
+    /// `x-oss-callback-var`头的值，没有自定义变量时为None
+    pub(crate) fn callback_var_header(&self) -> Option<String> {
+        if self.vars.is_empty() {
+            return None;
+        }
+        Some(base64_encode(serde_json::to_string(&self.vars).unwrap_or_default()))
+    }
+}
+
+/// `x-oss-tagging`头的值，key和value都需要url编码
+pub(crate) fn tagging_header(tags: &[(String, String)]) -> String {
+    tags.iter()
+        .map(|(k, v)| format!("{}={}", urlencoding::encode(k), urlencoding::encode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// 解析GetObjectTagging返回的xml，按xml中的顺序返回标签
+pub(crate) fn tags_from_xml(xml: &str) -> Vec<(String, String)> {
+    elements(xml, "Tag")
+        .into_iter()
+        .map(|tag| {
+            (
+                element_text(tag, "Key").unwrap_or_default(),
+                element_text(tag, "Value").unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+/// 迁移进度回调，参数为已迁移的字节数和对象总大小
+pub type TransferProgress = Arc<dyn Fn(u64, u64) + Send + Sync>;
+
+/// 迁移对象([transfer_object](crate::oss::OSS::transfer_object))的选项
+#[derive(Clone)]
+pub struct TransferOptions {
+    /// 经本机中转时每个分片的大小，不超过一个分片的对象一次下载、一次上传
+    pub part_size: u64,
+    /// 保留Content-Type等标准header和用户元数据
+    pub preserve_metadata: bool,
+    /// 保留对象标签
+    pub preserve_tags: bool,
+    /// 完成后HEAD目标对象，和源对象比较CRC64，源对象没有CRC64时比较大小
+    pub verify: bool,
+    /// 源对象请求使用的RequestBuilder，目标对象的请求使用`transfer_object`的`build`参数
+    pub source_build: RequestBuilder,
+    pub progress: Option<TransferProgress>,
+}
+
+impl std::fmt::Debug for TransferOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransferOptions")
+            .field("part_size", &self.part_size)
+            .field("preserve_metadata", &self.preserve_metadata)
+            .field("preserve_tags", &self.preserve_tags)
+            .field("verify", &self.verify)
+            .field("source_build", &self.source_build)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl Default for TransferOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransferOptions {
+    /// 默认8MB分片，保留元数据和标签，完成后校验
+    pub fn new() -> Self {
+        Self {
+            part_size: 8 * 1024 * 1024,
+            preserve_metadata: true,
+            preserve_tags: true,
+            verify: true,
+            source_build: RequestBuilder::new(),
+            progress: None,
+        }
+    }
+    /// `part_size`小于[MIN_PART_SIZE]时按[MIN_PART_SIZE]计算，大于5GB时按5GB计算
+    pub fn with_part_size(mut self, part_size: u64) -> Self {
+        self.part_size = part_size.clamp(MIN_PART_SIZE as u64, MAX_PART_SIZE);
+        self
+    }
+    pub fn with_preserve_metadata(mut self, preserve_metadata: bool) -> Self {
+        self.preserve_metadata = preserve_metadata;
+        self
+    }
+    pub fn with_preserve_tags(mut self, preserve_tags: bool) -> Self {
+        self.preserve_tags = preserve_tags;
+        self
+    }
+    pub fn with_verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+    pub fn with_source_build(mut self, source_build: RequestBuilder) -> Self {
+        self.source_build = source_build;
+        self
+    }
+    /// 每迁移完一个分片(服务端拷贝时为整个对象)调用一次
+    pub fn with_progress<F: Fn(u64, u64) + Send + Sync + 'static>(mut self, progress: F) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+    pub(crate) fn report_progress(&self, transferred: u64, total: u64) {
+        if let Some(progress) = &self.progress {
+            progress(transferred, total);
+        }
+    }
+}
+
+/// 比较迁移前后的对象，源对象有CRC64时比较CRC64，否则比较大小
+pub(crate) fn verify_transfer(source: &ObjectMetadata, target: &ObjectMetadata) -> Result<(), OssError> {
+    if let Some(expected) = source.crc64().and_then(|crc64| crc64.parse::<u64>().ok()) {
+        let actual = target.crc64();
+        if actual.as_deref().and_then(|crc64| crc64.parse::<u64>().ok()) != Some(expected) {
+            return Err(OssError::Crc64Mismatch { expected, actual });
+        }
+        return Ok(());
+    }
+    if source.content_length() != target.content_length() {
+        return Err(OssError::Err(format!(
+            "transfer size mismatch, source: {:?}, target: {:?}",
+            source.content_length(),
+            target.content_length()
+        )));
+    }
+    Ok(())
+}
+
+/// CopyObject支持的最大对象大小，更大的对象需要分片拷贝
+pub(crate) const MAX_COPY_OBJECT_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// 用户自定义元数据(`x-oss-meta-*`，包括header名称和值)的总大小上限
+pub(crate) const MAX_USER_METADATA_SIZE: usize = 8 * 1024;
+
+/// 批量转换存储类型的结果
+#[derive(Debug, Default)]
+pub struct TransitionReport {
+    /// 转换成功的key
+    pub succeeded: Vec<String>,
+    /// 已经是目标存储类型而跳过的key
+    pub skipped: Vec<String>,
+    /// 转换失败的key及原因
+    pub failed: Vec<(String, OssError)>,
+    /// 转换成功但变成归档类型的key，读取前需要先解冻
+    pub cold: Vec<String>,
+}
+
+impl TransitionReport {
+    /// 记录单个key的转换结果，`Ok(false)`表示已经是目标存储类型
+    pub(crate) fn record(&mut self, key: &str, target: StorageClass, result: Result<bool, OssError>) {
+        match result {
+            Ok(true) => {
+                self.succeeded.push(key.to_string());
+                if target.is_cold() {
+                    self.cold.push(key.to_string());
+                }
+            }
+            Ok(false) => self.skipped.push(key.to_string()),
+            Err(e) => self.failed.push((key.to_string(), e)),
+        }
+    }
+}
+
+/// 按前缀删除([delete_by_prefix](crate::oss::OSS::delete_by_prefix))的结果
+#[derive(Debug, Default)]
+pub struct DeleteReport {
+    /// 删除成功的对象数量
+    pub deleted: u64,
+    /// 删除失败的key及原因
+    pub failed: Vec<(String, OssError)>,
+}
+
+impl DeleteReport {
+    /// 删除失败的对象数量
+    pub fn failed_count(&self) -> usize {
+        self.failed.len()
+    }
+
+    /// 记录一批key的删除结果，OSS没有返回删除成功的key视为失败，整批请求失败时每个key都记录该错误
+    pub(crate) fn record(&mut self, keys: Vec<String>, result: Result<Vec<String>, OssError>) {
+        match result {
+            Ok(deleted) => {
+                let deleted = deleted.into_iter().collect::<HashSet<_>>();
+                for key in keys {
+                    if deleted.contains(&key) {
+                        self.deleted += 1;
+                    } else {
+                        let message = format!("{} was not reported as deleted", key);
+                        self.failed.push((key, OssError::Err(message)));
+                    }
+                }
+            }
+            Err(e) => {
+                for key in keys {
+                    self.failed.push((key, batch_error(&e)));
+                }
+            }
+        }
+    }
+}
+
+/// 整批删除失败时每个key使用的错误，OSS错误响应保留错误码
+fn batch_error(e: &OssError) -> OssError {
+    match e {
+        OssError::Service { operation, status, code, body } => OssError::Service {
+            operation: operation.to_string(),
+            status: status.to_string(),
+            code: code.clone(),
+            body: body.to_string(),
+        },
+        e => OssError::Err(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use reqwest::header::HeaderMap;
+    use crate::entity::{tagging_header, tags_from_xml, verify_transfer, Callback, CopyOptions, DeleteReport, Directive, ObjectAcl, PolicyResp, ResumeOptions, StorageClass, TransferOptions, TransitionReport};
+    use crate::metadata::ObjectMetadata;
+    use crate::multipart::{MAX_PART_SIZE, MIN_PART_SIZE};
+    use crate::request::RequestBuilder;
+    use crate::error::OssError;
+
+    #[test]
+    fn test_policy_resp_serde_round_trip() {
+        let policy = PolicyResp {
+            access_id: "my_key_id".to_string(),
+            host: "https://my_bucket.oss-cn-shanghai.aliyuncs.com".to_string(),
+            policy: "eyJleHBpcmF0aW9uIjoiIn0=".to_string(),
+            signature: "c2lnbmF0dXJl".to_string(),
+            success_action_status: 200,
+        };
+        let value = serde_json::to_value(&policy).unwrap();
+        assert_eq!(value, serde_json::json!({
+            "access_id": "my_key_id",
+            "host": "https://my_bucket.oss-cn-shanghai.aliyuncs.com",
+            "policy": "eyJleHBpcmF0aW9uIjoiIn0=",
+            "signature": "c2lnbmF0dXJl",
+            "success_action_status": 200,
+        }));
+        let decoded: PolicyResp = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, policy);
+    }
+
+    #[test]
+    fn test_transition_report_record() {
+        let mut report = TransitionReport::default();
+        report.record("a.txt", StorageClass::Archive, Ok(true));
+        report.record("b.txt", StorageClass::Archive, Ok(false));
+        report.record("c.txt", StorageClass::Archive, Err(OssError::Err("InvalidObjectState".to_string())));
+        assert_eq!(report.succeeded, vec!["a.txt"]);
+        assert_eq!(report.cold, vec!["a.txt"]);
+        assert_eq!(report.skipped, vec!["b.txt"]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, "c.txt");
+
+        let mut report = TransitionReport::default();
+        report.record("a.txt", StorageClass::IA, Ok(true));
+        assert!(report.cold.is_empty());
+    }
+
+    #[test]
+    fn test_delete_report_record() {
+        let mut report = DeleteReport::default();
+        let keys = vec!["a.txt".to_string(), "b.txt".to_string()];
+        report.record(keys.clone(), Ok(vec!["a.txt".to_string()]));
+        report.record(keys, Err(OssError::from_response("delete multiple objects", reqwest::StatusCode::FORBIDDEN, "<Error><Code>AccessDenied</Code></Error>".to_string())));
+        assert_eq!(report.deleted, 1);
+        assert_eq!(report.failed_count(), 3);
+        assert_eq!(report.failed[0].0, "b.txt");
+        assert!(report.failed[1..].iter().all(|(_, e)| e.code() == Some(crate::error::OssErrorCode::AccessDenied)));
+    }
+
+    #[test]
+    fn test_copy_options_apply() {
+        let options = CopyOptions::new()
+            .with_metadata("author", "lake")
+            .with_tag("project", "oss sdk")
+            .with_tag("env", "prod&test")
+            .with_acl(ObjectAcl::PublicRead)
+            .with_storage_class(StorageClass::IA)
+            .with_if_match("\"5B3C1A2E053D763E1B002CC607C5A0FE\"");
+        let build = options.apply(RequestBuilder::new());
+        let header = |key: &str| build.oss_headers.get(key).map(|v| v.as_str());
+        assert_eq!(header("x-oss-metadata-directive"), Some("REPLACE"));
+        assert_eq!(header("x-oss-meta-author"), Some("lake"));
+        assert_eq!(header("x-oss-tagging-directive"), Some("REPLACE"));
+        assert_eq!(header("x-oss-tagging"), Some("project=oss%20sdk&env=prod%26test"));
+        assert_eq!(header("x-oss-object-acl"), Some("public-read"));
+        assert_eq!(header("x-oss-storage-class"), Some("IA"));
+        assert_eq!(header("x-oss-copy-source-if-match"), Some("\"5B3C1A2E053D763E1B002CC607C5A0FE\""));
+        assert_eq!(header("x-oss-copy-source-if-none-match"), None);
+
+        let build = CopyOptions::new()
+            .with_metadata_directive(Directive::Copy)
+            .apply(RequestBuilder::new());
+        assert_eq!(build.oss_headers.len(), 1);
+        assert_eq!(build.oss_headers.get("x-oss-metadata-directive").unwrap(), "COPY");
+    }
+
+    #[test]
+    fn test_resume_options_delay() {
+        let options = ResumeOptions::new().with_backoff(Duration::from_millis(100));
+        assert_eq!(options.delay(1), Duration::from_millis(100));
+        assert_eq!(options.delay(2), Duration::from_millis(200));
+        assert_eq!(options.delay(3), Duration::from_millis(400));
+        assert_eq!(ResumeOptions::disabled().max_attempts, 0);
+    }
+
+    #[test]
+    fn test_object_acl_round_trip() {
+        for acl in [ObjectAcl::Default, ObjectAcl::Private, ObjectAcl::PublicRead, ObjectAcl::PublicReadWrite] {
+            assert_eq!(acl.to_string().parse::<ObjectAcl>().unwrap(), acl);
+        }
+        assert!("public".parse::<ObjectAcl>().is_err());
+    }
+
+    #[test]
+    fn test_callback_headers() {
+        let callback = Callback::new("https://example.com/cb", "object=${object}&uid=${x:uid}")
+            .with_body_type("application/x-www-form-urlencoded")
+            .with_var("x:uid", "10086")
+            .and_then(|callback| callback.with_var("x:ctx", "upload"))
+            .unwrap();
+        let build = RequestBuilder::new().with_callback(&callback);
+        let decode = |key: &str| {
+            let value = build.oss_headers.get(key).unwrap();
+            let json = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, value).unwrap();
+            serde_json::from_slice::<serde_json::Value>(&json).unwrap()
+        };
+        assert_eq!(decode("x-oss-callback"), serde_json::json!({
+            "callbackUrl": "https://example.com/cb",
+            "callbackBody": "object=${object}&uid=${x:uid}",
+            "callbackBodyType": "application/x-www-form-urlencoded",
+        }));
+        assert_eq!(decode("x-oss-callback-var"), serde_json::json!({
+            "x:ctx": "upload",
+            "x:uid": "10086",
+        }));
+
+        let build = RequestBuilder::new().with_callback(&Callback::new("https://example.com/cb", "a=1"));
+        assert!(!build.oss_headers.contains_key("x-oss-callback-var"));
+    }
+
+    #[test]
+    fn test_callback_var_requires_prefix() {
+        assert!(Callback::new("https://example.com/cb", "a=1").with_var("uid", "10086").is_err());
+        assert!(Callback::new("https://example.com/cb", "a=1").with_var("x:", "10086").is_err());
+    }
+
+    #[test]
+    fn test_tags_from_xml() {
+        let xml = "<Tagging><TagSet><Tag><Key>env</Key><Value>prod &amp; test</Value></Tag>\
+            <Tag><Key>empty</Key><Value></Value></Tag></TagSet></Tagging>";
+        let tags = tags_from_xml(xml);
+        assert_eq!(tags, vec![
+            ("env".to_string(), "prod & test".to_string()),
+            ("empty".to_string(), "".to_string()),
+        ]);
+        assert_eq!(tagging_header(&tags), "env=prod%20%26%20test&empty=");
+        assert!(tags_from_xml("<Tagging><TagSet></TagSet></Tagging>").is_empty());
+    }
+
+    #[test]
+    fn test_verify_transfer() {
+        let metadata = |headers: &[(&'static str, &str)]| {
+            let mut map = HeaderMap::new();
+            for (name, value) in headers {
+                map.insert(*name, value.parse().unwrap());
+            }
+            ObjectMetadata::new(&map)
+        };
+        let source = metadata(&[("x-oss-hash-crc64ecma", "42"), ("content-length", "5")]);
+        assert!(verify_transfer(&source, &metadata(&[("x-oss-hash-crc64ecma", "42")])).is_ok());
+        assert!(matches!(
+            verify_transfer(&source, &metadata(&[("x-oss-hash-crc64ecma", "7")])),
+            Err(OssError::Crc64Mismatch { expected: 42, .. })
+        ));
+        // 源对象没有CRC64时比较大小
+        let source = metadata(&[("content-length", "5")]);
+        assert!(verify_transfer(&source, &metadata(&[("content-length", "5")])).is_ok());
+        assert!(verify_transfer(&source, &metadata(&[("content-length", "6")])).is_err());
+    }
+
+    #[test]
+    fn test_transfer_options_part_size() {
+        assert_eq!(TransferOptions::new().with_part_size(1).part_size, MIN_PART_SIZE as u64);
+        assert_eq!(TransferOptions::new().with_part_size(u64::MAX).part_size, MAX_PART_SIZE);
+    }
+}
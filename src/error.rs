@@ -1,3 +1,6 @@
+use std::fmt::{Display, Formatter};
+use crate::xml;
+
 #[derive(Debug, thiserror::Error)]
 pub enum OssError {
     #[error("http error: {0}")]
@@ -6,8 +9,281 @@ pub enum OssError {
     IoError(#[from] std::io::Error),
     #[error("json error: {0}")]
     JsonError(#[from] serde_json::Error),
+    #[error("invalid header value: {0}")]
+    InvalidHeaderValue(#[from] reqwest::header::InvalidHeaderValue),
     #[error("base64 decode error: {0}")]
     DecodeError(#[from] base64::DecodeError),
+    #[error("invalid oss uri: {0}")]
+    InvalidUri(String),
+    #[error("object changed during download: {0}")]
+    ObjectChangedDuringDownload(String),
+    #[error("download interrupted after {received} bytes: {message}")]
+    DownloadInterrupted { received: u64, message: String },
+    #[error("request cancelled")]
+    Cancelled,
+    #[error("content md5 mismatch, expected: {expected} actual: {actual}")]
+    ContentMd5Mismatch { expected: String, actual: String },
+    #[error("crc64 mismatch, expected: {expected} actual: {}", actual.as_deref().unwrap_or("none"))]
+    Crc64Mismatch { expected: u64, actual: Option<String> },
+    /// `expected`为本地计算的SHA-256，`actual`为OSS返回的`x-oss-hash-sha256`
+    #[error("sha256 mismatch, expected: {expected} actual: {}", actual.as_deref().unwrap_or("none"))]
+    Sha256Mismatch { expected: String, actual: Option<String> },
+    #[error("bucket is not in the region of the configured endpoint, use endpoint: {expected_endpoint}")]
+    RegionMismatch { expected_endpoint: String },
+    #[error("append position is not equal to object length, next append position: {next_position}")]
+    PositionNotEqualToLength { next_position: u64 },
+    #[error("user metadata (x-oss-meta-*) is {size} bytes, exceeds the {limit} bytes limit")]
+    MetadataTooLarge { size: usize, limit: usize },
+    /// OSS返回的带有错误码的错误响应
+    #[error("{operation} status: {status} error: {body}")]
+    Service { operation: String, status: String, code: OssErrorCode, body: String },
+    /// 重命名时拷贝已经成功，但之后的步骤失败，源对象和目标对象都保留，需要人工处理
+    #[error("rename {source_key} to {target_key} failed at {step}, both objects are kept: {message}")]
+    PartialRename { step: RenameStep, source_key: String, target_key: String, message: String },
+    #[error("oss config check failed ({kind}): {message}")]
+    CheckFailed { kind: CheckFailure, message: String },
     #[error("{0}")]
     Err(String),
 }
+
+/// OSS错误响应中的错误码(`<Code>`)
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::error::{OssError, OssErrorCode};
+/// fn not_found(err: &OssError) -> bool {
+///     matches!(err.code(), Some(OssErrorCode::NoSuchKey | OssErrorCode::NoSuchBucket))
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OssErrorCode {
+    NoSuchKey,
+    NoSuchBucket,
+    NoSuchUpload,
+    AccessDenied,
+    InvalidAccessKeyId,
+    SignatureDoesNotMatch,
+    RequestTimeTooSkewed,
+    InvalidArgument,
+    InvalidObjectName,
+    InvalidObjectState,
+    EntityTooLarge,
+    EntityTooSmall,
+    FileAlreadyExists,
+    PositionNotEqualToLength,
+    ObjectNotAppendable,
+    PreconditionFailed,
+    RestoreAlreadyInProgress,
+    BucketAlreadyExists,
+    NoSuchWORMConfiguration,
+    /// 未列出的错误码
+    Other(String),
+}
+
+impl From<&str> for OssErrorCode {
+    fn from(code: &str) -> Self {
+        match code {
+            "NoSuchKey" => OssErrorCode::NoSuchKey,
+            "NoSuchBucket" => OssErrorCode::NoSuchBucket,
+            "NoSuchUpload" => OssErrorCode::NoSuchUpload,
+            "AccessDenied" => OssErrorCode::AccessDenied,
+            "InvalidAccessKeyId" => OssErrorCode::InvalidAccessKeyId,
+            "SignatureDoesNotMatch" => OssErrorCode::SignatureDoesNotMatch,
+            "RequestTimeTooSkewed" => OssErrorCode::RequestTimeTooSkewed,
+            "InvalidArgument" => OssErrorCode::InvalidArgument,
+            "InvalidObjectName" => OssErrorCode::InvalidObjectName,
+            "InvalidObjectState" => OssErrorCode::InvalidObjectState,
+            "EntityTooLarge" => OssErrorCode::EntityTooLarge,
+            "EntityTooSmall" => OssErrorCode::EntityTooSmall,
+            "FileAlreadyExists" => OssErrorCode::FileAlreadyExists,
+            "PositionNotEqualToLength" => OssErrorCode::PositionNotEqualToLength,
+            "ObjectNotAppendable" => OssErrorCode::ObjectNotAppendable,
+            "PreconditionFailed" => OssErrorCode::PreconditionFailed,
+            "RestoreAlreadyInProgress" => OssErrorCode::RestoreAlreadyInProgress,
+            "BucketAlreadyExists" => OssErrorCode::BucketAlreadyExists,
+            "NoSuchWORMConfiguration" => OssErrorCode::NoSuchWORMConfiguration,
+            other => OssErrorCode::Other(other.to_string()),
+        }
+    }
+}
+
+impl Display for OssErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let code = match self {
+            OssErrorCode::NoSuchKey => "NoSuchKey",
+            OssErrorCode::NoSuchBucket => "NoSuchBucket",
+            OssErrorCode::NoSuchUpload => "NoSuchUpload",
+            OssErrorCode::AccessDenied => "AccessDenied",
+            OssErrorCode::InvalidAccessKeyId => "InvalidAccessKeyId",
+            OssErrorCode::SignatureDoesNotMatch => "SignatureDoesNotMatch",
+            OssErrorCode::RequestTimeTooSkewed => "RequestTimeTooSkewed",
+            OssErrorCode::InvalidArgument => "InvalidArgument",
+            OssErrorCode::InvalidObjectName => "InvalidObjectName",
+            OssErrorCode::InvalidObjectState => "InvalidObjectState",
+            OssErrorCode::EntityTooLarge => "EntityTooLarge",
+            OssErrorCode::EntityTooSmall => "EntityTooSmall",
+            OssErrorCode::FileAlreadyExists => "FileAlreadyExists",
+            OssErrorCode::PositionNotEqualToLength => "PositionNotEqualToLength",
+            OssErrorCode::ObjectNotAppendable => "ObjectNotAppendable",
+            OssErrorCode::PreconditionFailed => "PreconditionFailed",
+            OssErrorCode::RestoreAlreadyInProgress => "RestoreAlreadyInProgress",
+            OssErrorCode::BucketAlreadyExists => "BucketAlreadyExists",
+            OssErrorCode::NoSuchWORMConfiguration => "NoSuchWORMConfiguration",
+            OssErrorCode::Other(code) => code,
+        };
+        write!(f, "{}", code)
+    }
+}
+
+/// 重命名([OSS::rename_object](crate::oss::OSS))失败的步骤
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameStep {
+    /// 拷贝后HEAD确认目标对象失败
+    VerifyTarget,
+    /// 删除源对象失败
+    DeleteSource,
+}
+
+impl Display for RenameStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenameStep::VerifyTarget => write!(f, "verify target"),
+            RenameStep::DeleteSource => write!(f, "delete source"),
+        }
+    }
+}
+
+/// [OSS::check](crate::oss::OSS)失败的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckFailure {
+    /// AccessKeyId不存在或已禁用
+    InvalidAccessKey,
+    /// AccessKeySecret错误
+    SignatureMismatch,
+    /// bucket不存在
+    NoSuchBucket,
+    /// 没有访问bucket的权限
+    AccessDenied,
+    Other,
+}
+
+impl Display for CheckFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckFailure::InvalidAccessKey => write!(f, "invalid access key id"),
+            CheckFailure::SignatureMismatch => write!(f, "signature mismatch, check access key secret"),
+            CheckFailure::NoSuchBucket => write!(f, "no such bucket"),
+            CheckFailure::AccessDenied => write!(f, "access denied"),
+            CheckFailure::Other => write!(f, "other"),
+        }
+    }
+}
+
+impl OssError {
+    /// OSS返回的错误码，不是OSS错误响应(例如网络错误)时返回None
+    pub fn code(&self) -> Option<OssErrorCode> {
+        match self {
+            OssError::Service { code, .. } => Some(code.clone()),
+            OssError::PositionNotEqualToLength { .. } => Some(OssErrorCode::PositionNotEqualToLength),
+            _ => None,
+        }
+    }
+
+    /// 根据OSS返回的错误响应生成错误，endpoint和bucket所在地域不匹配时返回[OssError::RegionMismatch]，
+    /// 响应中有错误码时返回[OssError::Service]
+    pub(crate) fn from_response<S: Display>(operation: &str, status: S, body: String) -> OssError {
+        let code = xml::element_text(&body, "Code");
+        if matches!(code.as_deref(), Some("AccessDenied" | "PermanentRedirect")) {
+            if let Some(expected_endpoint) = xml::element_text(&body, "Endpoint") {
+                return OssError::RegionMismatch { expected_endpoint };
+            }
+        }
+        match code {
+            Some(code) => OssError::Service {
+                operation: operation.to_string(),
+                status: status.to_string(),
+                code: OssErrorCode::from(code.as_str()),
+                body,
+            },
+            None => OssError::Err(format!("{} status: {} error: {}", operation, status, body)),
+        }
+    }
+
+    /// 把配置检查的错误响应按原因分类，地域不匹配仍返回[OssError::RegionMismatch]
+    pub(crate) fn check_failed<S: Display>(status: S, body: String) -> OssError {
+        let code = xml::element_text(&body, "Code").unwrap_or_default();
+        let kind = match code.as_str() {
+            "InvalidAccessKeyId" => CheckFailure::InvalidAccessKey,
+            "SignatureDoesNotMatch" => CheckFailure::SignatureMismatch,
+            "NoSuchBucket" => CheckFailure::NoSuchBucket,
+            "AccessDenied" => CheckFailure::AccessDenied,
+            _ => CheckFailure::Other,
+        };
+        match OssError::from_response("check", status, body) {
+            OssError::Err(message) => OssError::CheckFailed { kind, message },
+            err @ OssError::Service { .. } => OssError::CheckFailed { kind, message: err.to_string() },
+            err => err,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{CheckFailure, OssError, OssErrorCode};
+
+    #[test]
+    fn test_from_response_region_mismatch() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>AccessDenied</Code>
+  <Message>The bucket you are attempting to access must be addressed using the specified endpoint. Please send all future requests to this endpoint.</Message>
+  <RequestId>5C3D9175B6FC201293AD****</RequestId>
+  <HostId>examplebucket.oss-cn-hangzhou.aliyuncs.com</HostId>
+  <Bucket>examplebucket</Bucket>
+  <Endpoint>oss-cn-beijing.aliyuncs.com</Endpoint>
+</Error>"#;
+        let err = OssError::from_response("get object", 403, body.to_string());
+        assert!(matches!(
+            err,
+            OssError::RegionMismatch { ref expected_endpoint } if expected_endpoint == "oss-cn-beijing.aliyuncs.com"
+        ));
+    }
+
+    #[test]
+    fn test_from_response_other_error() {
+        let body = "<Error><Code>AccessDenied</Code><Message>denied</Message></Error>";
+        let err = OssError::from_response("get object", 403, body.to_string());
+        assert_eq!(err.to_string(), format!("get object status: 403 error: {}", body));
+        let err = OssError::from_response("get object", 404, "".to_string());
+        assert!(matches!(err, OssError::Err(_)));
+    }
+
+    #[test]
+    fn test_from_response_error_code() {
+        let body = "<Error><Code>NoSuchKey</Code><Message>The specified key does not exist.</Message></Error>";
+        let err = OssError::from_response("get object", 404, body.to_string());
+        assert_eq!(err.code(), Some(OssErrorCode::NoSuchKey));
+        let body = "<Error><Code>SomethingNew</Code></Error>";
+        let err = OssError::from_response("get object", 400, body.to_string());
+        assert_eq!(err.code(), Some(OssErrorCode::Other("SomethingNew".to_string())));
+        assert_eq!(OssErrorCode::from("RequestTimeTooSkewed").to_string(), "RequestTimeTooSkewed");
+        assert_eq!(OssErrorCode::from("NoSuchWORMConfiguration"), OssErrorCode::NoSuchWORMConfiguration);
+        assert_eq!(OssError::from_response("get object", 404, "".to_string()).code(), None);
+    }
+
+    #[test]
+    fn test_check_failed() {
+        let kind = |body: &str| match OssError::check_failed(403, body.to_string()) {
+            OssError::CheckFailed { kind, .. } => Some(kind),
+            _ => None,
+        };
+        assert_eq!(kind("<Error><Code>InvalidAccessKeyId</Code></Error>"), Some(CheckFailure::InvalidAccessKey));
+        assert_eq!(kind("<Error><Code>SignatureDoesNotMatch</Code></Error>"), Some(CheckFailure::SignatureMismatch));
+        assert_eq!(kind("<Error><Code>NoSuchBucket</Code></Error>"), Some(CheckFailure::NoSuchBucket));
+        assert_eq!(kind("<Error><Code>AccessDenied</Code></Error>"), Some(CheckFailure::AccessDenied));
+        assert_eq!(kind(""), Some(CheckFailure::Other));
+        let body = "<Error><Code>AccessDenied</Code><Endpoint>oss-cn-beijing.aliyuncs.com</Endpoint></Error>";
+        assert!(matches!(OssError::check_failed(403, body.to_string()), OssError::RegionMismatch { .. }));
+    }
+}
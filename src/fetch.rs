@@ -0,0 +1,213 @@
+use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
+use crate::error::OssError;
+use crate::util::base64_encode;
+use crate::xml::{element_text, XmlWriter};
+
+/// 异步拉取任务(asyncFetch)参数，OSS会从`url`下载文件并保存为`object`
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::fetch::AsyncFetchSpec;
+/// let spec = AsyncFetchSpec::new("https://cdn.example.com/a.png", "/images/a.png")
+///     .with_ignore_same_key(false);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsyncFetchSpec {
+    pub url: String,
+    pub object: String,
+    /// 拉取源站时使用的Host头
+    pub host: Option<String>,
+    /// 源文件的Content-MD5，设置后OSS会校验拉取到的文件
+    pub content_md5: Option<String>,
+    /// 拉取完成后的回调参数(JSON)，发送时会自动base64编码
+    pub callback: Option<String>,
+    /// 为false时，如果object已存在则任务失败
+    pub ignore_same_key: bool,
+}
+
+impl AsyncFetchSpec {
+    pub fn new<S: AsRef<str>>(url: S, object: S) -> Self {
+        Self {
+            url: url.as_ref().to_string(),
+            object: object.as_ref().trim_start_matches('/').to_string(),
+            host: None,
+            content_md5: None,
+            callback: None,
+            ignore_same_key: true,
+        }
+    }
+    pub fn with_host<S: AsRef<str>>(mut self, host: S) -> Self {
+        self.host = Some(host.as_ref().to_string());
+        self
+    }
+    pub fn with_content_md5<S: AsRef<str>>(mut self, content_md5: S) -> Self {
+        self.content_md5 = Some(content_md5.as_ref().to_string());
+        self
+    }
+    pub fn with_callback<S: AsRef<str>>(mut self, callback: S) -> Self {
+        self.callback = Some(callback.as_ref().to_string());
+        self
+    }
+    pub fn with_ignore_same_key(mut self, ignore_same_key: bool) -> Self {
+        self.ignore_same_key = ignore_same_key;
+        self
+    }
+
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = XmlWriter::new();
+        xml.start("AsyncFetchTaskConfiguration")
+            .element("Url", &self.url)
+            .element("Object", &self.object);
+        if let Some(host) = &self.host {
+            xml.element("Host", host);
+        }
+        if let Some(content_md5) = &self.content_md5 {
+            xml.element("ContentMD5", content_md5);
+        }
+        if let Some(callback) = &self.callback {
+            xml.element("Callback", base64_encode(callback));
+        }
+        xml.element("IgnoreSameKey", self.ignore_same_key.to_string());
+        xml.finish()
+    }
+}
+
+/// 异步拉取任务状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AsyncFetchState {
+    Running,
+    Retry,
+    Success,
+    Failed,
+    /// 未知的状态
+    Other(String),
+}
+
+impl AsyncFetchState {
+    /// 任务已经结束(成功或失败)
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, AsyncFetchState::Success | AsyncFetchState::Failed)
+    }
+}
+
+impl From<&str> for AsyncFetchState {
+    fn from(state: &str) -> Self {
+        match state {
+            "Running" => AsyncFetchState::Running,
+            "Retry" => AsyncFetchState::Retry,
+            "Success" => AsyncFetchState::Success,
+            "Failed" => AsyncFetchState::Failed,
+            other => AsyncFetchState::Other(other.to_string()),
+        }
+    }
+}
+
+impl Display for AsyncFetchState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AsyncFetchState::Running => write!(f, "Running"),
+            AsyncFetchState::Retry => write!(f, "Retry"),
+            AsyncFetchState::Success => write!(f, "Success"),
+            AsyncFetchState::Failed => write!(f, "Failed"),
+            AsyncFetchState::Other(state) => write!(f, "{}", state),
+        }
+    }
+}
+
+/// 异步拉取任务信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AsyncFetchTaskInfo {
+    pub task_id: String,
+    pub state: AsyncFetchState,
+    /// 任务失败的原因
+    pub error_msg: Option<String>,
+    pub url: Option<String>,
+    pub object: Option<String>,
+}
+
+impl AsyncFetchTaskInfo {
+    pub(crate) fn from_xml(xml: &str) -> Result<Self, OssError> {
+        let task_id = element_text(xml, "TaskId")
+            .ok_or_else(|| OssError::Err(format!("invalid async fetch task xml: {}", xml)))?;
+        let state = element_text(xml, "State")
+            .ok_or_else(|| OssError::Err(format!("invalid async fetch task xml: {}", xml)))?;
+        Ok(Self {
+            task_id,
+            state: AsyncFetchState::from(state.as_str()),
+            error_msg: element_text(xml, "ErrorMsg").filter(|msg| !msg.is_empty()),
+            url: element_text(xml, "Url"),
+            object: element_text(xml, "Object"),
+        })
+    }
+}
+
+/// 解析创建任务返回的TaskId
+pub(crate) fn task_id_from_xml(xml: &str) -> Result<String, OssError> {
+    element_text(xml, "TaskId")
+        .ok_or_else(|| OssError::Err(format!("invalid async fetch task xml: {}", xml)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fetch::{task_id_from_xml, AsyncFetchSpec, AsyncFetchState, AsyncFetchTaskInfo};
+
+    #[test]
+    fn test_spec_to_xml() {
+        let spec = AsyncFetchSpec::new("https://cdn.example.com/a.png?x=1&y=2", "/images/a.png")
+            .with_host("cdn.example.com")
+            .with_callback("{\"callbackUrl\":\"https://example.com/cb\"}")
+            .with_ignore_same_key(false);
+        assert_eq!(
+            spec.to_xml(),
+            "<AsyncFetchTaskConfiguration>\
+<Url>https://cdn.example.com/a.png?x=1&amp;y=2</Url>\
+<Object>images/a.png</Object>\
+<Host>cdn.example.com</Host>\
+<Callback>eyJjYWxsYmFja1VybCI6Imh0dHBzOi8vZXhhbXBsZS5jb20vY2IifQ==</Callback>\
+<IgnoreSameKey>false</IgnoreSameKey>\
+</AsyncFetchTaskConfiguration>"
+        );
+    }
+
+    #[test]
+    fn test_task_info_from_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<AsyncFetchTaskInfo>
+  <TaskId>MTk2NDY0NzE3MjEyNTcyNQ==</TaskId>
+  <State>Failed</State>
+  <ErrorMsg>source file not found</ErrorMsg>
+  <TaskInfo>
+    <Url>https://cdn.example.com/a.png</Url>
+    <Object>images/a.png</Object>
+    <IgnoreSameKey>true</IgnoreSameKey>
+  </TaskInfo>
+</AsyncFetchTaskInfo>"#;
+        let info = AsyncFetchTaskInfo::from_xml(xml).unwrap();
+        assert_eq!(info.task_id, "MTk2NDY0NzE3MjEyNTcyNQ==");
+        assert_eq!(info.state, AsyncFetchState::Failed);
+        assert!(info.state.is_terminal());
+        assert_eq!(info.error_msg.as_deref(), Some("source file not found"));
+        assert_eq!(info.object.as_deref(), Some("images/a.png"));
+        assert!(!AsyncFetchState::from("Retry").is_terminal());
+        assert!(task_id_from_xml("<AsyncFetchTaskResult></AsyncFetchTaskResult>").is_err());
+    }
+
+    #[test]
+    fn test_task_info_serde_round_trip() {
+        let info = AsyncFetchTaskInfo::from_xml("<AsyncFetchTaskInfo><TaskId>task-1</TaskId><State>Running</State>\
+<TaskInfo><Url>https://cdn.example.com/a.png</Url><Object>images/a.png</Object></TaskInfo></AsyncFetchTaskInfo>")
+        .unwrap();
+        let value = serde_json::to_value(&info).unwrap();
+        assert_eq!(value, serde_json::json!({
+            "task_id": "task-1",
+            "state": "Running",
+            "error_msg": null,
+            "url": "https://cdn.example.com/a.png",
+            "object": "images/a.png",
+        }));
+        let decoded: AsyncFetchTaskInfo = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, info);
+    }
+}
@@ -1,10 +1,29 @@
 #![doc = include_str!("../README.md")]
 pub mod auth;
+pub mod config;
 pub mod oss;
 pub mod request;
 pub mod url;
 pub mod metadata;
+pub mod process;
+pub mod fetch;
+pub mod list;
+pub mod stat;
+pub mod worm;
+pub mod bucket_config;
+pub mod multipart;
+pub mod access_log;
+#[cfg(feature = "callback-verify")]
+pub mod callback;
 mod util;
+mod date;
+mod xml;
+mod delete;
+mod rate_limit;
+mod list_cache;
+mod restore_cache;
+#[cfg(test)]
+mod mock_server;
 
 #[cfg(feature = "blocking")]
 pub mod blocking;
@@ -0,0 +1,180 @@
+use std::io::Read;
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use crate::date;
+use crate::error::OssError;
+use crate::xml::{element_text, elements};
+
+/// 列举结果中的对象
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectSummary {
+    pub key: String,
+    /// 最后修改时间，响应中没有或格式不对时为None
+    pub last_modified: Option<DateTime<Utc>>,
+    pub etag: String,
+    pub size: u64,
+    pub storage_class: String,
+    /// 标签数量，ListObjectsV2不返回该字段，列举结果中总是None，
+    /// 需要时调用[fill_tagging_count](crate::oss::OSS::fill_tagging_count)逐个HEAD获取
+    pub tagging_count: Option<u32>,
+}
+
+/// 列举对象(ListObjectsV2)的结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListObjectsResult {
+    pub name: String,
+    pub prefix: String,
+    pub start_after: Option<String>,
+    pub max_keys: u32,
+    pub key_count: u32,
+    pub is_truncated: bool,
+    /// 结果被截断时，用于[with_continuation_token](crate::request::RequestBuilder::with_continuation_token)获取下一页
+    pub next_continuation_token: Option<String>,
+    pub objects: Vec<ObjectSummary>,
+    /// 设置了delimiter时按delimiter分组的key前缀
+    pub common_prefixes: Vec<String>,
+    /// 原始响应body，只有设置了[with_raw_response](crate::request::RequestBuilder::with_raw_response)时才有
+    pub raw: Option<String>,
+}
+
+impl ListObjectsResult {
+    pub(crate) fn from_xml(xml: &str) -> Result<Self, OssError> {
+        let invalid = || OssError::Err(format!("invalid list objects xml: {}", xml));
+        let number = |tag: &str| element_text(xml, tag).and_then(|v| v.parse::<u32>().ok()).unwrap_or_default();
+        // Contents和CommonPrefixes中也有Prefix/Key等元素，列表外的字段只从头部取
+        let head = &xml[..xml
+            .find("<Contents>")
+            .or_else(|| xml.find("<CommonPrefixes>"))
+            .unwrap_or(xml.len())];
+        let objects = elements(xml, "Contents")
+            .into_iter()
+            .map(|content| {
+                Ok(ObjectSummary {
+                    key: element_text(content, "Key").ok_or_else(invalid)?,
+                    last_modified: element_text(content, "LastModified").and_then(|v| date::parse_iso8601(&v)),
+                    etag: element_text(content, "ETag").unwrap_or_default().trim_matches('"').to_string(),
+                    size: element_text(content, "Size").and_then(|v| v.parse().ok()).unwrap_or_default(),
+                    storage_class: element_text(content, "StorageClass").unwrap_or_default(),
+                    tagging_count: None,
+                })
+            })
+            .collect::<Result<Vec<_>, OssError>>()?;
+        let common_prefixes = elements(xml, "CommonPrefixes")
+            .into_iter()
+            .filter_map(|prefix| element_text(prefix, "Prefix"))
+            .collect();
+        Ok(Self {
+            name: element_text(head, "Name").ok_or_else(invalid)?,
+            prefix: element_text(head, "Prefix").unwrap_or_default(),
+            start_after: element_text(head, "StartAfter"),
+            max_keys: number("MaxKeys"),
+            key_count: number("KeyCount"),
+            is_truncated: element_text(head, "IsTruncated").as_deref() == Some("true"),
+            next_continuation_token: element_text(head, "NextContinuationToken").filter(|v| !v.is_empty()),
+            objects,
+            common_prefixes,
+            raw: None,
+        })
+    }
+}
+
+impl ListObjectsResult {
+    /// 把结果中的key和前缀转换为作用域内的相对路径
+    pub(crate) fn unscope<F: Fn(String) -> String>(mut self, unscope: F) -> Self {
+        self.prefix = unscope(self.prefix);
+        self.start_after = self.start_after.map(&unscope);
+        for object in &mut self.objects {
+            object.key = unscope(std::mem::take(&mut object.key));
+        }
+        self.common_prefixes = self.common_prefixes.into_iter().map(&unscope).collect();
+        self
+    }
+}
+
+/// 列举请求带上`Accept-Encoding: gzip`，响应被压缩时先解压再解析
+pub(crate) fn decode_list_body(content_encoding: Option<&str>, body: &[u8]) -> Result<String, OssError> {
+    let mut xml = String::new();
+    if content_encoding.is_some_and(|encoding| encoding.eq_ignore_ascii_case("gzip")) {
+        GzDecoder::new(body).read_to_string(&mut xml)?;
+    } else {
+        xml = String::from_utf8_lossy(body).into_owned();
+    }
+    Ok(xml)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use crate::list::ListObjectsResult;
+
+    #[test]
+    fn test_list_objects_from_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult>
+  <Name>examplebucket</Name>
+  <Prefix>dir/</Prefix>
+  <StartAfter>dir/a.txt</StartAfter>
+  <MaxKeys>2</MaxKeys>
+  <Delimiter>/</Delimiter>
+  <IsTruncated>true</IsTruncated>
+  <NextContinuationToken>ChR0ZXN0</NextContinuationToken>
+  <Contents>
+    <Key>dir/b&amp;c.txt</Key>
+    <LastModified>2024-01-01T00:00:00.000Z</LastModified>
+    <ETag>"5B3C1A2E053D763E1B002CC607C5A0FE"</ETag>
+    <Type>Normal</Type>
+    <Size>344606</Size>
+    <StorageClass>Standard</StorageClass>
+  </Contents>
+  <CommonPrefixes>
+    <Prefix>dir/sub/</Prefix>
+  </CommonPrefixes>
+  <KeyCount>2</KeyCount>
+</ListBucketResult>"#;
+        let result = ListObjectsResult::from_xml(xml).unwrap();
+        assert_eq!(result.name, "examplebucket");
+        assert_eq!(result.prefix, "dir/");
+        assert_eq!(result.start_after.as_deref(), Some("dir/a.txt"));
+        assert_eq!(result.max_keys, 2);
+        assert_eq!(result.key_count, 2);
+        assert!(result.is_truncated);
+        assert_eq!(result.next_continuation_token.as_deref(), Some("ChR0ZXN0"));
+        assert_eq!(result.objects.len(), 1);
+        assert_eq!(result.objects[0].key, "dir/b&c.txt");
+        assert_eq!(result.objects[0].etag, "5B3C1A2E053D763E1B002CC607C5A0FE");
+        assert_eq!(result.objects[0].size, 344606);
+        assert_eq!(result.objects[0].last_modified, Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()));
+        assert_eq!(result.common_prefixes, vec!["dir/sub/"]);
+    }
+
+    #[test]
+    fn test_list_objects_serde_round_trip() {
+        let xml = "<ListBucketResult><Name>examplebucket</Name><Prefix>dir/</Prefix><MaxKeys>100</MaxKeys>\
+<IsTruncated>false</IsTruncated><Contents><Key>dir/a.txt</Key><LastModified>2024-01-01T00:00:00.000Z</LastModified>\
+<ETag>\"abc\"</ETag><Size>3</Size><StorageClass>IA</StorageClass></Contents><KeyCount>1</KeyCount></ListBucketResult>";
+        let result = ListObjectsResult::from_xml(xml).unwrap();
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(value, serde_json::json!({
+            "name": "examplebucket",
+            "prefix": "dir/",
+            "start_after": null,
+            "max_keys": 100,
+            "key_count": 1,
+            "is_truncated": false,
+            "next_continuation_token": null,
+            "objects": [{
+                "key": "dir/a.txt",
+                "last_modified": "2024-01-01T00:00:00Z",
+                "etag": "abc",
+                "size": 3,
+                "storage_class": "IA",
+                "tagging_count": null,
+            }],
+            "common_prefixes": [],
+            "raw": null,
+        }));
+        let decoded: ListObjectsResult = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, result);
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::list::ListObjectsResult;
+
+/// 列举结果缓存，以列举请求的URL(包含bucket和所有查询参数)为key，所有clone出来的OSS实例共享
+///
+/// 缓存的是没有去掉作用域前缀的原始结果，[crate::oss::OSS::scoped]得到的实例取出后各自去掉前缀
+///
+/// 缓存满时先清理过期的结果，仍然满时淘汰最早写入的结果
+#[derive(Debug)]
+pub(crate) struct ListCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<String, (Instant, ListObjectsResult)>>,
+}
+
+impl ListCache {
+    /// `capacity`为0时按1处理
+    pub(crate) fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity: capacity.max(1),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<ListObjectsResult> {
+        self.get_at(key, Instant::now())
+    }
+
+    pub(crate) fn insert(&self, key: String, result: ListObjectsResult) {
+        self.insert_at(key, result, Instant::now())
+    }
+
+    /// 清空缓存，写入或删除对象后调用可以让下一次列举拿到最新结果
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    fn get_at(&self, key: &str, now: Instant) -> Option<ListObjectsResult> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(key)
+            .filter(|(inserted, _)| now.saturating_duration_since(*inserted) < self.ttl)
+            .map(|(_, result)| result.clone())
+    }
+
+    fn insert_at(&self, key: String, result: ListObjectsResult, now: Instant) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            entries.retain(|_, (inserted, _)| now.saturating_duration_since(*inserted) < self.ttl);
+            if entries.len() >= self.capacity {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, (inserted, _))| *inserted)
+                    .map(|(key, _)| key.to_string());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(key, (now, result));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use crate::list::ListObjectsResult;
+    use crate::list_cache::ListCache;
+
+    fn result(name: &str) -> ListObjectsResult {
+        ListObjectsResult::from_xml(&format!("<ListBucketResult><Name>{}</Name></ListBucketResult>", name)).unwrap()
+    }
+
+    #[test]
+    fn test_list_cache_ttl() {
+        let cache = ListCache::new(Duration::from_secs(5), 10);
+        let now = Instant::now();
+        cache.insert_at("a".to_string(), result("a"), now);
+        assert_eq!(cache.get_at("a", now + Duration::from_secs(4)).unwrap().name, "a");
+        assert!(cache.get_at("a", now + Duration::from_secs(5)).is_none());
+        assert!(cache.get_at("b", now).is_none());
+        cache.clear();
+        assert!(cache.get_at("a", now).is_none());
+    }
+
+    #[test]
+    fn test_list_cache_bounded() {
+        let cache = ListCache::new(Duration::from_secs(60), 2);
+        let now = Instant::now();
+        cache.insert_at("a".to_string(), result("a"), now);
+        cache.insert_at("b".to_string(), result("b"), now + Duration::from_secs(1));
+        cache.insert_at("c".to_string(), result("c"), now + Duration::from_secs(2));
+        let later = now + Duration::from_secs(3);
+        assert!(cache.get_at("a", later).is_none());
+        assert!(cache.get_at("b", later).is_some());
+        assert!(cache.get_at("c", later).is_some());
+    }
+
+    #[test]
+    fn test_list_cache_zero_capacity() {
+        let cache = ListCache::new(Duration::from_secs(60), 0);
+        let now = Instant::now();
+        cache.insert_at("a".to_string(), result("a"), now);
+        assert!(cache.get_at("a", now).is_some());
+    }
+}
@@ -15,6 +15,23 @@ macro_rules! error {
     ($name:expr $(,$arg:tt)+ $(,)?) => {};
 }
 
+#[cfg(feature = "debug-print")]
+#[macro_export]
+macro_rules! warn {
+    ($name:expr)=>{
+        tracing::warn!($name);
+    };
+    ($name:expr $(,$arg:tt)+ $(,)?) => {
+        tracing::warn!($name, $($arg),*);
+    };
+}
+#[cfg(not(feature = "debug-print"))]
+#[macro_export]
+macro_rules! warn {
+    ($name:expr)=>{};
+    ($name:expr $(,$arg:tt)+ $(,)?) => {};
+}
+
 #[cfg(feature = "debug-print")]
 #[macro_export]
 macro_rules! debug {
@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
 use crate::debug;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ObjectMetadata {
     pub(crate) metadata: HashMap<String, String>,
+    /// 用户自定义元数据，key去掉了`x-oss-meta-`前缀
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub(crate) user_metadata: HashMap<String, String>,
 }
 
 unsafe impl Send for ObjectMetadata {}
@@ -20,8 +24,8 @@ impl ObjectMetadata {
         for (key, value) in headers.iter() {
             let key = key.as_str().to_string().to_lowercase();
             let value = value.to_str().unwrap().to_string();
-            if key.starts_with("x-oss-meta-") {
-                user_metadata.insert(key[11..].to_string(), value);
+            if let Some(meta_key) = key.strip_prefix("x-oss-meta-") {
+                user_metadata.insert(meta_key.to_string(), value);
             } else if key == "etag" {
                 let value = value.trim_matches('"').to_string();
                 metadata.insert("etag".to_string(), value);
@@ -32,43 +36,57 @@ impl ObjectMetadata {
 
         ObjectMetadata {
             metadata,
+            user_metadata,
         }
     }
 
     pub fn last_modified(&self) -> Option<DateTime<Utc>> {
-        let val = self.metadata.get("last-modified");
-        if val.is_none() {
-            debug!("Can't find <last-modified>.");
-            return None;
-        }
-        let result = chrono::DateTime::parse_from_rfc2822(val.unwrap()).map(|dt| dt.with_timezone(&chrono::Utc));
-        return match result {
-            Ok(date) => {
-                Some(date)
+        let val = match self.metadata.get("last-modified") {
+            Some(val) => val,
+            None => {
+                debug!("Can't find <last-modified>.");
+                return None;
             }
-            Err(e) => {
-                debug!("Last modified parsed failed.{}", e);
+        };
+        match chrono::DateTime::parse_from_rfc2822(val) {
+            Ok(date) => Some(date.with_timezone(&Utc)),
+            Err(_e) => {
+                debug!("Last modified parsed failed.{}", _e);
                 None
             }
-        };
+        }
     }
 
     pub fn expiration_time(&self) -> Option<DateTime<Utc>> {
-        let val = self.metadata.get("x-oss-expiration");
-        if val.is_none() {
-            debug!("Can't find <x-oss-expiration>.");
-            return None;
-        }
-        let result = chrono::DateTime::parse_from_rfc2822(val.unwrap()).map(|dt| dt.with_timezone(&Utc));
-        return match result {
-            Ok(date) => {
-                Some(date)
+        let val = match self.metadata.get("x-oss-expiration") {
+            Some(val) => val,
+            None => {
+                debug!("Can't find <x-oss-expiration>.");
+                return None;
             }
-            Err(e) => {
-                debug!("Expiration time parsed failed.{}", e);
+        };
+        match chrono::DateTime::parse_from_rfc2822(val) {
+            Ok(date) => Some(date.with_timezone(&Utc)),
+            Err(_e) => {
+                debug!("Expiration time parsed failed.{}", _e);
                 None
             }
-        };
+        }
+    }
+    /// 上传时设置的HTTP缓存头`Expires`
+    pub fn expires(&self) -> Option<DateTime<Utc>> {
+        let val = self.metadata.get("expires")?;
+        match chrono::DateTime::parse_from_rfc2822(val) {
+            Ok(date) => Some(date.with_timezone(&Utc)),
+            Err(_e) => {
+                debug!("Expires parsed failed.{}", _e);
+                None
+            }
+        }
+    }
+    /// 用户自定义元数据(`x-oss-meta-*`)，key为去掉前缀后的小写名称
+    pub fn user_metadata(&self) -> &HashMap<String, String> {
+        &self.user_metadata
     }
     pub fn content_md5(&self) -> Option<String> {
         self.metadata.get("content-md5").map(|s| s.to_string())
@@ -97,7 +115,108 @@ impl ObjectMetadata {
     pub fn server_side_encryption(&self) -> Option<String> {
         self.metadata.get("x-oss-server-side-encryption").map(|s| s.to_string())
     }
+    pub fn storage_class(&self) -> Option<String> {
+        self.metadata.get("x-oss-storage-class").map(|s| s.to_string())
+    }
     pub fn object_type(&self) -> Option<String> {
         self.metadata.get("x-oss-object-type").map(|s| s.to_string())
     }
-}
\ No newline at end of file
+    /// 对象的标签数量(`x-oss-tagging-count`)，对象没有标签时OSS不返回该header
+    pub fn tagging_count(&self) -> Option<u32> {
+        self.metadata.get("x-oss-tagging-count")?.parse().ok()
+    }
+    /// 解冻状态，例如`ongoing-request="true"`或`ongoing-request="false", expiry-date="..."`
+    pub fn restore(&self) -> Option<String> {
+        self.metadata.get("x-oss-restore").map(|s| s.to_string())
+    }
+    /// 对象当前是否可以读取：已经解冻完成，或者不是归档类型
+    pub fn is_readable(&self) -> bool {
+        match self.metadata.get("x-oss-restore") {
+            Some(restore) => restore.contains("ongoing-request=\"false\""),
+            None => !matches!(
+                self.metadata.get("x-oss-storage-class").map(|s| s.as_str()),
+                Some("Archive" | "ColdArchive" | "DeepColdArchive")
+            ),
+        }
+    }
+    /// 是否为符号链接(`x-oss-object-type: Symlink`)
+    pub fn is_symlink(&self) -> bool {
+        self.metadata.get("x-oss-object-type").is_some_and(|s| s == "Symlink")
+    }
+    /// 符号链接指向的目标对象，OSS返回的是URL编码后的值，这里已解码
+    ///
+    /// 需要配合[crate::request::RequestBuilder::with_no_follow_symlink]获取，否则返回的是目标对象的元信息
+    pub fn symlink_target(&self) -> Option<String> {
+        self.metadata.get("x-oss-symlink-target").map(|s| {
+            urlencoding::decode(s).map(|s| s.into_owned()).unwrap_or_else(|_| s.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderMap;
+    use crate::metadata::ObjectMetadata;
+
+    #[test]
+    fn test_object_metadata_serde_round_trip() {
+        let mut headers = HeaderMap::new();
+        headers.insert("ETag", "\"5B3C1A2E053D763E1B002CC607C5A0FE\"".parse().unwrap());
+        headers.insert("Content-Length", "344606".parse().unwrap());
+        headers.insert("Last-Modified", "Fri, 24 Feb 2012 06:07:48 GMT".parse().unwrap());
+        let metadata = ObjectMetadata::new(&headers);
+        let value = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(value, serde_json::json!({
+            "metadata": {
+                "etag": "5B3C1A2E053D763E1B002CC607C5A0FE",
+                "content-length": "344606",
+                "last-modified": "Fri, 24 Feb 2012 06:07:48 GMT",
+            }
+        }));
+        let decoded: ObjectMetadata = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, metadata);
+        assert_eq!(decoded.last_modified(), metadata.last_modified());
+    }
+
+    #[test]
+    fn test_expires() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Expires", "Tue, 01 Jan 2030 00:00:00 GMT".parse().unwrap());
+        let expires = ObjectMetadata::new(&headers).expires().unwrap();
+        assert_eq!(expires.to_rfc3339(), "2030-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_tagging_count() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(ObjectMetadata::new(&headers).tagging_count(), None);
+        headers.insert("x-oss-tagging-count", "3".parse().unwrap());
+        assert_eq!(ObjectMetadata::new(&headers).tagging_count(), Some(3));
+    }
+
+    #[test]
+    fn test_is_readable() {
+        let mut headers = HeaderMap::new();
+        assert!(ObjectMetadata::new(&headers).is_readable());
+        headers.insert("x-oss-storage-class", "Archive".parse().unwrap());
+        assert!(!ObjectMetadata::new(&headers).is_readable());
+        headers.insert("x-oss-restore", "ongoing-request=\"true\"".parse().unwrap());
+        assert!(!ObjectMetadata::new(&headers).is_readable());
+        headers.insert(
+            "x-oss-restore",
+            "ongoing-request=\"false\", expiry-date=\"Sun, 16 Apr 2017 08:12:33 GMT\"".parse().unwrap(),
+        );
+        assert!(ObjectMetadata::new(&headers).is_readable());
+    }
+
+    #[test]
+    fn test_symlink_target() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-oss-object-type", "Symlink".parse().unwrap());
+        headers.insert("x-oss-symlink-target", "dir/%E7%9B%AE%E6%A0%87.txt".parse().unwrap());
+        let metadata = ObjectMetadata::new(&headers);
+        assert!(metadata.is_symlink());
+        assert_eq!(metadata.symlink_target().as_deref(), Some("dir/目标.txt"));
+        assert!(!ObjectMetadata::new(&HeaderMap::new()).is_symlink());
+    }
+}
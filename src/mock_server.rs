@@ -0,0 +1,65 @@
+//! 测试用的本地http服务，按顺序为每个连接返回一个预先准备好的原始响应
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// 启动服务，返回可用于`with_cdn`的地址和收到的原始请求(请求头和请求体)
+///
+/// 响应写完后连接会被直接关闭，所以响应体比`Content-Length`短时可以模拟传输中途断开
+pub(crate) fn serve(responses: Vec<Vec<u8>>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            // 读完Content-Length指定的请求体，避免客户端还在发送时连接被关闭
+            let header_end = request.windows(4).position(|w| w == b"\r\n\r\n").map_or(request.len(), |p| p + 4);
+            let content_length = String::from_utf8_lossy(&request[..header_end])
+                .to_lowercase()
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:").and_then(|v| v.trim().parse::<usize>().ok()))
+                .unwrap_or(0);
+            while request.len() < header_end + content_length {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            requests.push(String::from_utf8_lossy(&request).to_lowercase());
+            stream.write_all(&response).unwrap();
+            stream.flush().unwrap();
+        }
+        requests
+    });
+    (addr, handle)
+}
+
+/// 拼接一个完整的原始响应，`extra_headers`的每一行都要以`\r\n`结尾
+pub(crate) fn response(status: &str, extra_headers: &str, body: &str) -> Vec<u8> {
+    format!(
+        "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        extra_headers,
+        body.len(),
+        body
+    )
+    .into_bytes()
+}
+
+/// 返回`body`的200响应
+pub(crate) fn xml_response(body: &str) -> Vec<u8> {
+    response("200 OK", "", body)
+}
@@ -0,0 +1,297 @@
+#[cfg(feature = "blocking")]
+use std::io::Read;
+#[cfg(not(feature = "blocking"))]
+use tokio::io::{AsyncRead, AsyncReadExt};
+use serde::{Deserialize, Serialize};
+use crate::error::OssError;
+use crate::metadata::ObjectMetadata;
+use crate::request::RequestBuilder;
+use crate::util;
+use crate::xml::{element_text, XmlWriter};
+
+/// 分片的最小大小，最后一个分片除外
+pub const MIN_PART_SIZE: usize = 100 * 1024;
+
+/// 一次分片上传最多的分片数量
+pub const MAX_PARTS: u32 = 10000;
+
+/// 单个分片的最大大小
+pub(crate) const MAX_PART_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+
+/// 不知道总大小时每上传这么多个分片把分片大小翻倍，避免很快用完[MAX_PARTS]
+const PART_SIZE_GROWTH_INTERVAL: u32 = 1000;
+
+/// 分片拷贝时每个分片的默认大小
+pub(crate) const COPY_PART_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// 上传成功的分片，完成分片上传时需要按分片号提交
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadedPart {
+    pub part_number: u32,
+    pub etag: String,
+    /// 分片的字节数，只在有`crc64`时使用
+    pub size: u64,
+    /// 分片内容的CRC64，[RequestBuilder::with_verify_on_complete]需要每个分片都有
+    pub crc64: Option<u64>,
+}
+
+impl UploadedPart {
+    pub fn new<S: Into<String>>(part_number: u32, etag: S) -> Self {
+        Self {
+            part_number,
+            etag: etag.into(),
+            size: 0,
+            crc64: None,
+        }
+    }
+    /// 设置分片的字节数和CRC64，用于完成上传时校验整个对象的CRC64
+    pub fn with_crc64(mut self, size: u64, crc64: u64) -> Self {
+        self.size = size;
+        self.crc64 = Some(crc64);
+        self
+    }
+}
+
+/// 上传`data`得到的分片，开启了[RequestBuilder::with_verify_on_complete]时计算CRC64
+pub(crate) fn uploaded_part(part_number: u32, etag: String, data: &[u8], build: &RequestBuilder) -> UploadedPart {
+    let part = UploadedPart::new(part_number, etag);
+    if build.verify_on_complete {
+        part.with_crc64(data.len() as u64, util::crc64(data))
+    } else {
+        part
+    }
+}
+
+/// 按分片号顺序合并各分片的CRC64，得到整个对象的CRC64
+pub(crate) fn combined_crc64(parts: &[UploadedPart]) -> Result<u64, OssError> {
+    parts.iter().try_fold(0u64, |crc, part| {
+        let part_crc = part.crc64.ok_or_else(|| {
+            OssError::Err(format!("verify on complete requires the crc64 of part {}", part.part_number))
+        })?;
+        Ok(util::crc64_combine(crc, part_crc, part.size))
+    })
+}
+
+pub(crate) fn upload_id_from_xml(xml: &str) -> Result<String, OssError> {
+    element_text(xml, "UploadId")
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| OssError::Err(format!("invalid initiate multipart upload xml: {}", xml)))
+}
+
+/// UploadPartCopy返回的分片ETag，去掉了双引号
+pub(crate) fn copy_part_etag_from_xml(xml: &str) -> Result<String, OssError> {
+    element_text(xml, "ETag")
+        .map(|etag| etag.replace("&quot;", "").trim_matches('"').to_string())
+        .filter(|etag| !etag.is_empty())
+        .ok_or_else(|| OssError::Err(format!("invalid upload part copy xml: {}", xml)))
+}
+
+/// 把`size`字节的对象按`part_size`切分为拷贝范围(包含两端)，分片数量超过[MAX_PARTS]时加大分片
+pub(crate) fn copy_ranges(size: u64, part_size: u64) -> Vec<(u64, u64)> {
+    let part_size = part_size.max(size.div_ceil(MAX_PARTS as u64)).max(1);
+    (0..size)
+        .step_by(part_size as usize)
+        .map(|start| (start, (start + part_size).min(size) - 1))
+        .collect()
+}
+
+/// 分片拷贝初始化请求使用的RequestBuilder，带上源对象的Content-Type等标准header和用户元数据
+///
+/// 分片拷贝不像CopyObject那样复制源对象的元数据，需要在初始化时设置，`build`中已经设置的值优先
+pub(crate) fn multipart_copy_build(metadata: &ObjectMetadata, build: &RequestBuilder) -> RequestBuilder {
+    let mut build = build.clone();
+    if build.content_type.is_none() {
+        build.content_type = metadata.content_type();
+    }
+    let headers = [
+        ("Content-Encoding", metadata.content_encoding()),
+        ("Content-Disposition", metadata.content_disposition()),
+        ("Cache-Control", metadata.cache_control()),
+    ];
+    for (name, value) in headers {
+        if let Some(value) = value {
+            build.headers.entry(name.to_string()).or_insert(value);
+        }
+    }
+    for (name, value) in metadata.user_metadata() {
+        build.oss_headers.entry(format!("x-oss-meta-{}", name)).or_insert_with(|| value.to_string());
+    }
+    build
+}
+
+/// CompleteMultipartUpload的请求body，ETag需要带双引号
+pub(crate) fn complete_multipart_xml(parts: &[UploadedPart]) -> String {
+    let mut xml = XmlWriter::new();
+    xml.start("CompleteMultipartUpload");
+    for part in parts {
+        xml.start("Part")
+            .element("PartNumber", part.part_number.to_string())
+            .element("ETag", format!("\"{}\"", part.etag.trim_matches('"')))
+            .end();
+    }
+    xml.finish()
+}
+
+/// 从`reader`读取最多`part_size`字节到`buf`，只有读到末尾时才会少于`part_size`
+#[cfg(feature = "blocking")]
+pub(crate) fn read_part<R: Read>(reader: &mut R, part_size: usize, buf: &mut Vec<u8>) -> Result<usize, OssError> {
+    buf.clear();
+    Ok(reader.by_ref().take(part_size as u64).read_to_end(buf)?)
+}
+
+/// 从`reader`读取最多`part_size`字节到`buf`，只有读到末尾时才会少于`part_size`
+#[cfg(not(feature = "blocking"))]
+pub(crate) async fn read_part<R: AsyncRead + Unpin>(reader: &mut R, part_size: usize, buf: &mut Vec<u8>) -> Result<usize, OssError> {
+    buf.clear();
+    Ok(reader.take(part_size as u64).read_to_end(buf).await?)
+}
+
+/// 不知道总大小时第`part_number`个分片的大小，从`part_size`开始每1000个分片翻倍，不超过[MAX_PART_SIZE]
+///
+/// 以[MIN_PART_SIZE]开始时最多可以上传约100GB，以8MB开始时约8TB
+pub(crate) fn adaptive_part_size(part_size: usize, part_number: u32) -> usize {
+    let doublings = part_number.saturating_sub(1) / PART_SIZE_GROWTH_INTERVAL;
+    let limit = MAX_PART_SIZE.min(usize::MAX as u64) as usize;
+    part_size.saturating_mul(1 << doublings.min(16)).min(limit.max(part_size))
+}
+
+pub(crate) fn check_part_size(part_size: usize) -> Result<(), OssError> {
+    if part_size < MIN_PART_SIZE {
+        return Err(OssError::Err(format!(
+            "part size must be at least {} bytes, got {}",
+            MIN_PART_SIZE, part_size
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multipart::{adaptive_part_size, combined_crc64, complete_multipart_xml, copy_part_etag_from_xml, copy_ranges, read_part, upload_id_from_xml, uploaded_part, UploadedPart, MAX_PARTS, MAX_PART_SIZE, MIN_PART_SIZE};
+    use crate::request::RequestBuilder;
+    use crate::util::crc64;
+
+    #[test]
+    fn test_upload_id_from_xml() {
+        let xml = "<InitiateMultipartUploadResult><Bucket>bucket</Bucket><Key>a.txt</Key>\
+<UploadId>0004B9894A22E5B1888A1E29F823****</UploadId></InitiateMultipartUploadResult>";
+        assert_eq!(upload_id_from_xml(xml).unwrap(), "0004B9894A22E5B1888A1E29F823****");
+        assert!(upload_id_from_xml("<Error></Error>").is_err());
+    }
+
+    #[test]
+    fn test_copy_part_etag_from_xml() {
+        let xml = "<CopyPartResult><LastModified>2014-07-17T06:27:54.000Z</LastModified>\
+<ETag>\"5B3C1A2E053D763E1B002CC607C5****\"</ETag></CopyPartResult>";
+        assert_eq!(copy_part_etag_from_xml(xml).unwrap(), "5B3C1A2E053D763E1B002CC607C5****");
+        assert!(copy_part_etag_from_xml("<CopyPartResult></CopyPartResult>").is_err());
+    }
+
+    #[test]
+    fn test_copy_ranges() {
+        assert_eq!(copy_ranges(10, 4), vec![(0, 3), (4, 7), (8, 9)]);
+        assert_eq!(copy_ranges(8, 4), vec![(0, 3), (4, 7)]);
+        // 分片太多时加大分片
+        let ranges = copy_ranges(MAX_PARTS as u64 * 2 + 1, 1);
+        assert!(ranges.len() <= MAX_PARTS as usize);
+        assert_eq!(ranges.last().unwrap().1, MAX_PARTS as u64 * 2);
+    }
+
+    #[test]
+    fn test_adaptive_part_size() {
+        assert_eq!(adaptive_part_size(MIN_PART_SIZE, 1), MIN_PART_SIZE);
+        assert_eq!(adaptive_part_size(MIN_PART_SIZE, 1000), MIN_PART_SIZE);
+        assert_eq!(adaptive_part_size(MIN_PART_SIZE, 1001), MIN_PART_SIZE * 2);
+        assert_eq!(adaptive_part_size(MIN_PART_SIZE, MAX_PARTS), MIN_PART_SIZE * 512);
+        assert_eq!(adaptive_part_size(1024 * 1024 * 1024, MAX_PARTS), MAX_PART_SIZE as usize);
+        let total: u64 = (1..=MAX_PARTS).map(|n| adaptive_part_size(MIN_PART_SIZE, n) as u64).sum();
+        assert!(total > 100 * 1000 * 1000 * 1000);
+    }
+
+    #[test]
+    fn test_complete_multipart_xml() {
+        let parts = vec![
+            UploadedPart::new(1, "\"A1\""),
+            UploadedPart::new(2, "B2"),
+        ];
+        assert_eq!(
+            complete_multipart_xml(&parts),
+            "<CompleteMultipartUpload><Part><PartNumber>1</PartNumber><ETag>&quot;A1&quot;</ETag></Part>\
+<Part><PartNumber>2</PartNumber><ETag>&quot;B2&quot;</ETag></Part></CompleteMultipartUpload>"
+        );
+    }
+
+    #[test]
+    fn test_combined_crc64() {
+        let build = RequestBuilder::new().with_verify_on_complete();
+        let parts = vec![
+            uploaded_part(1, "A1".to_string(), b"hello ", &build),
+            uploaded_part(2, "B2".to_string(), b"world", &build),
+        ];
+        assert_eq!(combined_crc64(&parts).unwrap(), crc64(b"hello world"));
+        // 没有开启校验时不计算CRC64
+        let part = uploaded_part(1, "A1".to_string(), b"hello", &RequestBuilder::new());
+        assert_eq!(part.crc64, None);
+        assert!(combined_crc64(&[part]).is_err());
+    }
+
+    #[test]
+    fn test_uploaded_part_serde_round_trip() {
+        let part = UploadedPart::new(3, "3C4D").with_crc64(5, 0x1234);
+        let value = serde_json::to_value(&part).unwrap();
+        assert_eq!(value, serde_json::json!({"part_number": 3, "etag": "3C4D", "size": 5, "crc64": 0x1234}));
+        let decoded: UploadedPart = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, part);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_read_part() {
+        // 每次只返回1个字节的reader，read_part也要读满一个分片
+        struct Trickle(Vec<u8>);
+        impl std::io::Read for Trickle {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0.is_empty() || buf.is_empty() {
+                    return Ok(0);
+                }
+                buf[0] = self.0.remove(0);
+                Ok(1)
+            }
+        }
+        let mut reader = Trickle(b"hello world".to_vec());
+        let mut buf = Vec::new();
+        assert_eq!(read_part(&mut reader, 5, &mut buf).unwrap(), 5);
+        assert_eq!(buf, b"hello");
+        assert_eq!(read_part(&mut reader, 5, &mut buf).unwrap(), 5);
+        assert_eq!(read_part(&mut reader, 5, &mut buf).unwrap(), 1);
+        assert_eq!(buf, b"d");
+        assert_eq!(read_part(&mut reader, 5, &mut buf).unwrap(), 0);
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    #[tokio::test]
+    async fn test_read_part() {
+        use std::pin::Pin;
+        use std::task::{Context, Poll};
+        use tokio::io::{AsyncRead, ReadBuf};
+        // 每次只返回1个字节的reader，read_part也要读满一个分片
+        struct Trickle(Vec<u8>);
+        impl AsyncRead for Trickle {
+            fn poll_read(mut self: Pin<&mut Self>, _: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+                if !self.0.is_empty() && buf.remaining() > 0 {
+                    let byte = self.0.remove(0);
+                    buf.put_slice(&[byte]);
+                }
+                Poll::Ready(Ok(()))
+            }
+        }
+        let mut reader = Trickle(b"hello world".to_vec());
+        let mut buf = Vec::new();
+        assert_eq!(read_part(&mut reader, 5, &mut buf).await.unwrap(), 5);
+        assert_eq!(buf, b"hello");
+        assert_eq!(read_part(&mut reader, 5, &mut buf).await.unwrap(), 5);
+        assert_eq!(read_part(&mut reader, 5, &mut buf).await.unwrap(), 1);
+        assert_eq!(buf, b"d");
+        assert_eq!(read_part(&mut reader, 5, &mut buf).await.unwrap(), 0);
+    }
+}
@@ -1,15 +1,53 @@
-use crate::auth::AuthAPI;
+use crate::config::{CredentialProvider, Credentials, OssConfig};
+use crate::entity::MAX_USER_METADATA_SIZE;
 use crate::request::RequestBuilder;
-use chrono::{DateTime, Utc};
-use reqwest::header::{HeaderMap, InvalidHeaderValue, AUTHORIZATION, CONTENT_TYPE, DATE};
+use crate::error::OssError;
+use crate::rate_limit::RateLimiter;
+use crate::list_cache::ListCache;
+use crate::restore_cache::RestoreCache;
+use chrono::Utc;
+use crate::{date, warn};
+use reqwest::header::{HeaderMap, HeaderName, AUTHORIZATION, CONTENT_TYPE, DATE};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(not(feature = "blocking"))]
+pub(crate) type HttpClient = reqwest::Client;
+#[cfg(feature = "blocking")]
+pub(crate) type HttpClient = reqwest::blocking::Client;
+
+/// 本地时间和OSS服务器时间相差超过这个值时输出警告，OSS在相差15分钟时拒绝请求(RequestTimeTooSkewed)
+pub const SERVER_SKEW_WARNING: Duration = Duration::from_secs(5 * 60);
+
+/// 按TCP选项(keepalive, nodelay)缓存的client
+type ClientCache = HashMap<(Option<Duration>, Option<bool>), HttpClient>;
 
 /// OSS配置
+///
+/// 内部的http client(含连接池)在第一次请求时创建，由当前实例及其所有clone共享，
+/// 最后一个实例drop时释放，也可以调用[OSS::close]提前释放
 #[derive(Debug, Clone)]
 pub struct OSS {
-    key_id: String,
-    key_secret: String,
+    credentials: Arc<dyn CredentialProvider>,
+    /// 创建实例时的凭证，只用于已废弃的[OSS::key_id_str]和[OSS::key_secret_str]
+    initial_credentials: Arc<Credentials>,
     endpoint: String,
     bucket: String,
+    pub(crate) rate_limiter: Option<Arc<RateLimiter>>,
+    pub(crate) list_cache: Option<Arc<ListCache>>,
+    /// 等待解冻时的HEAD结果，所有clone共享，见[OSS::restore_object_then]
+    pub(crate) restore_cache: Arc<RestoreCache>,
+    clients: Arc<Mutex<ClientCache>>,
+    /// 最近一次响应的Date头和本地时间的差值，所有clone共享
+    server_skew: Arc<Mutex<Option<Duration>>>,
+    config: Arc<OssConfig>,
+    /// 默认的RequestBuilder，最底层是[OssConfig]中的请求默认值，见[OSS::with_default_builder]
+    default_build: Arc<RequestBuilder>,
+    /// [OSS::new]创建的实例兼容旧版本，endpoint没有写明协议时API请求使用http，签名URL仍然按配置使用https
+    legacy_http: bool,
+    /// [OSS::scoped]设置的key前缀，不含开头和结尾的`/`
+    key_prefix: String,
 }
 
 unsafe impl Send for OSS {}
@@ -44,6 +82,59 @@ pub trait API {
     fn format_oss_resource_str<S: AsRef<str>>(&self, bucket: S, key: S) -> String;
 }
 
+/// [OssConfig]中的请求默认值，作为默认RequestBuilder最底层的值
+fn config_defaults(config: &OssConfig) -> RequestBuilder {
+    let mut build = RequestBuilder::new().with_expire(config.expire);
+    build.tcp_keepalive = config.tcp_keepalive;
+    build.tcp_nodelay = config.tcp_nodelay;
+    build
+}
+
+/// 解析`oss://bucket/key`格式的URI，返回(bucket, key)
+///
+/// bucket会和配置的endpoint拼接成`bucket.endpoint`访问，所以必须是合法的bucket名称
+pub fn parse_oss_uri<S: AsRef<str>>(uri: S) -> Result<(String, String), OssError> {
+    let uri = uri.as_ref();
+    let path = uri
+        .strip_prefix("oss://")
+        .ok_or_else(|| OssError::InvalidUri(format!("{} must start with oss://", uri)))?;
+    let (bucket, key) = path
+        .split_once('/')
+        .ok_or_else(|| OssError::InvalidUri(format!("{} is missing object key", uri)))?;
+    if key.is_empty() {
+        return Err(OssError::InvalidUri(format!("{} is missing object key", uri)));
+    }
+    let valid_bucket = (3..=63).contains(&bucket.len())
+        && bucket
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !bucket.starts_with('-')
+        && !bucket.ends_with('-');
+    if !valid_bucket {
+        return Err(OssError::InvalidUri(format!(
+            "{} has invalid bucket name: {}",
+            uri, bucket
+        )));
+    }
+    Ok((bucket.to_string(), key.to_string()))
+}
+
+/// 在发送前检查用户元数据的总大小，避免OSS返回含义不明确的MetadataTooLarge
+fn check_user_metadata_size(oss_headers: &HashMap<String, String>) -> Result<(), OssError> {
+    let size = oss_headers
+        .iter()
+        .filter(|(k, _)| k.to_lowercase().starts_with("x-oss-meta-"))
+        .map(|(k, v)| k.len() + v.len())
+        .sum::<usize>();
+    if size > MAX_USER_METADATA_SIZE {
+        return Err(OssError::MetadataTooLarge {
+            size,
+            limit: MAX_USER_METADATA_SIZE,
+        });
+    }
+    Ok(())
+}
+
 impl OSSInfo for OSS {
     fn endpoint(&self) -> String {
         self.endpoint.clone()
@@ -53,26 +144,85 @@ impl OSSInfo for OSS {
     }
 
     fn key_id(&self) -> String {
-        self.key_id.clone()
+        self.credentials().key_id
     }
 
     fn key_secret(&self) -> String {
-        self.key_secret.clone()
+        self.credentials().key_secret
     }
 }
 
 impl API for OSS {
     fn format_oss_resource_str<S: AsRef<str>>(&self, bucket: S, key: S) -> String {
         let bucket = bucket.as_ref();
-        if bucket == "" {
-            format!("/{}", bucket)
+        if bucket.is_empty() {
+            // 服务级别的操作(例如列举bucket)，资源固定为`/`
+            "/".to_string()
         } else {
-            format!("/{}{}", bucket, key.as_ref())
+            // key已经由format_key处理过(包含作用域前缀)，这里只补齐开头的`/`
+            let key = key.as_ref();
+            if key.starts_with('/') {
+                format!("/{}{}", bucket, key)
+            } else {
+                format!("/{}/{}", bucket, key)
+            }
+        }
+    }
+
+    /// 补齐开头的`/`，使用[OSS::scoped]时加上作用域前缀
+    fn format_key<S: AsRef<str>>(&self, key: S) -> String {
+        let key = key.as_ref().trim_start_matches('/');
+        if self.key_prefix.is_empty() {
+            format!("/{}", key)
+        } else {
+            format!("/{}/{}", self.key_prefix, key)
         }
     }
 }
 
-impl<'a> OSS {
+impl OSS {
+    /// 同[OSSInfo::endpoint]，返回引用避免每次调用都clone
+    pub fn endpoint_str(&self) -> &str {
+        &self.endpoint
+    }
+    /// 同[OSSInfo::bucket]，返回引用避免每次调用都clone
+    pub fn bucket_str(&self) -> &str {
+        &self.bucket
+    }
+    /// 当前的凭证，需要同时使用AccessKeyId和AccessKeySecret时应该只调用一次，避免取到轮换前后不同的两份凭证
+    pub fn credentials(&self) -> Credentials {
+        self.credentials.credentials()
+    }
+    /// `other`和当前实例的endpoint(地域)相同并且使用同一个AccessKeyId时，可以用服务端拷贝从`other`的bucket拷贝对象
+    pub(crate) fn can_copy_from(&self, other: &OSS) -> bool {
+        self.endpoint == other.endpoint && self.credentials().key_id == other.credentials().key_id
+    }
+    /// 创建实例时的AccessKeyId，使用[CredentialProvider]轮换凭证后不会更新
+    #[deprecated(note = "凭证可能轮换，请使用OSS::credentials")]
+    pub fn key_id_str(&self) -> &str {
+        &self.initial_credentials.key_id
+    }
+    /// 创建实例时的AccessKeySecret，使用[CredentialProvider]轮换凭证后不会更新
+    #[deprecated(note = "凭证可能轮换，请使用OSS::credentials")]
+    pub fn key_secret_str(&self) -> &str {
+        &self.initial_credentials.key_secret
+    }
+
+    /// 解析`oss://bucket/key`格式的URI，同时检查endpoint和凭证不为空
+    ///
+    /// URI只提供bucket，请求还需要配置中的endpoint和凭证，提前检查避免发出签名错误或地址错误的请求
+    pub(crate) fn resolve_oss_uri<S: AsRef<str>>(&self, uri: S) -> Result<(String, String), OssError> {
+        let (bucket, key) = parse_oss_uri(uri)?;
+        if self.endpoint.trim().is_empty() {
+            return Err(OssError::Err("oss uri requires a configured endpoint, endpoint is empty".to_string()));
+        }
+        let credentials = self.credentials();
+        if credentials.key_id.is_empty() || credentials.key_secret.is_empty() {
+            return Err(OssError::Err("oss uri requires credentials, key_id or key_secret is empty".to_string()));
+        }
+        Ok((bucket, key))
+    }
+
     pub fn from_env() -> Self {
         let key_id = std::env::var("OSS_KEY_ID").expect("OSS_KEY_ID not found");
         let key_secret = std::env::var("OSS_KEY_SECRET").expect("OSS_KEY_SECRET not found");
@@ -91,26 +241,270 @@ impl<'a> OSS {
     }
     #[cfg(not(feature = "debug-print"))]
     pub fn open_debug(&self) {}
+    /// 使用默认配置创建实例，endpoint没有写明协议时API请求使用http，需要其它配置时使用[OSS::with_config]
     pub fn new<S: Into<String>>(key_id: S, key_secret: S, endpoint: S, bucket: S) -> Self {
-        OSS {
-            key_id: key_id.into(),
-            key_secret: key_secret.into(),
-            endpoint: endpoint.into(),
-            bucket: bucket.into(),
+        let mut oss = OSS::with_config(Credentials::new(key_id, key_secret), OssConfig::new(endpoint, bucket));
+        oss.legacy_http = true;
+        oss
+    }
+
+    /// 使用[OssConfig]创建实例
+    ///
+    /// # 使用例子
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use aliyun_oss_rust_sdk::config::{Credentials, OssConfig};
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// let config = OssConfig::new("oss-cn-hangzhou.aliyuncs.com", "my-bucket")
+    ///     .with_connect_timeout(Duration::from_secs(5))
+    ///     .with_rate_limit(100);
+    /// let oss = OSS::with_config(Credentials::new("key_id", "key_secret"), config);
+    /// ```
+    pub fn with_config(credentials: Credentials, config: OssConfig) -> Self {
+        OSS::with_credential_provider(Arc::new(credentials), config)
+    }
+
+    /// 使用[CredentialProvider]创建实例，每次请求签名时从`provider`获取凭证，凭证轮换后无需重建实例
+    pub fn with_credential_provider(provider: Arc<dyn CredentialProvider>, config: OssConfig) -> Self {
+        let oss = OSS {
+            initial_credentials: Arc::new(provider.credentials()),
+            credentials: provider,
+            endpoint: config.endpoint.clone(),
+            bucket: config.bucket.clone(),
+            rate_limiter: None,
+            list_cache: None,
+            restore_cache: Arc::new(RestoreCache::default()),
+            clients: Arc::new(Mutex::new(HashMap::new())),
+            server_skew: Arc::new(Mutex::new(None)),
+            default_build: Arc::new(config_defaults(&config)),
+            legacy_http: false,
+            config: Arc::new(config),
+            key_prefix: String::new(),
+        };
+        match oss.config.rate_limit {
+            Some(rps) => oss.with_rate_limit(rps),
+            None => oss,
+        }
+    }
+
+    /// 当前实例的配置
+    pub fn config(&self) -> &OssConfig {
+        &self.config
+    }
+
+    /// 设置默认的RequestBuilder，每次请求的RequestBuilder都以它为基础叠加，只需要设置不同的部分
+    ///
+    /// 合并规则见[RequestBuilder::merge_defaults]，`build`没有设置的过期时间、TCP选项使用[OssConfig]中的值，
+    /// 超时、User-Agent等连接选项在[OssConfig]中设置
+    ///
+    /// # 使用例子
+    ///
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "my-bucket")
+    ///     .with_default_builder(RequestBuilder::new().with_expire(600).oss_header_put("x-oss-storage-class", "IA"));
+    /// // 只覆盖过期时间，x-oss-storage-class仍然生效
+    /// let build = oss.request().with_expire(60);
+    /// ```
+    pub fn with_default_builder(mut self, build: RequestBuilder) -> Self {
+        self.default_build = Arc::new(build.merge_defaults(&config_defaults(&self.config)));
+        self
+    }
+
+    /// 返回限定在`prefix`目录下的实例，适合多租户应用为每个租户分配一个目录
+    ///
+    /// 返回的实例和当前实例共享client、限流器和凭证，所有对象操作的key(包括签名URL、拷贝源、批量删除)
+    /// 都会自动加上前缀；列举对象时prefix和start-after在作用域内解释，返回的key、common_prefixes
+    /// 去掉了作用域前缀。可以嵌套调用，嵌套的前缀依次拼接。异步拉取任务的目标object不受影响
+    ///
+    /// # 使用例子
+    ///
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// use aliyun_oss_rust_sdk::url::UrlApi;
+    /// let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "my-bucket");
+    /// let tenant = oss.scoped("tenants/a");
+    /// let url = tenant.sign_download_url("/avatar.png", &RequestBuilder::new());
+    /// assert!(url.starts_with("https://my-bucket.oss-cn-hangzhou.aliyuncs.com/tenants/a/avatar.png?"));
+    /// ```
+    pub fn scoped(&self, prefix: &str) -> OSS {
+        let prefix = prefix.trim_matches('/');
+        let mut oss = self.clone();
+        oss.key_prefix = match (self.key_prefix.is_empty(), prefix.is_empty()) {
+            (_, true) => self.key_prefix.clone(),
+            (true, false) => prefix.to_string(),
+            (false, false) => format!("{}/{}", self.key_prefix, prefix),
+        };
+        oss
+    }
+
+    /// [OSS::scoped]设置的key前缀，没有时为空字符串
+    pub fn key_prefix(&self) -> &str {
+        &self.key_prefix
+    }
+
+    /// 列举对象时在prefix和start-after前加上作用域前缀
+    pub(crate) fn scope_list_query(&self, mut build: RequestBuilder) -> RequestBuilder {
+        if self.key_prefix.is_empty() {
+            return build;
+        }
+        let scope = format!("{}/", self.key_prefix);
+        let prefix = build.query.remove("prefix").unwrap_or_default();
+        build.query.insert("prefix".to_string(), format!("{}{}", scope, prefix));
+        if let Some(start_after) = build.query.remove("start-after") {
+            build.query.insert("start-after".to_string(), format!("{}{}", scope, start_after));
+        }
+        build
+    }
+
+    /// 去掉key中的作用域前缀，用于列举和批量删除的结果
+    pub(crate) fn unscope_key(&self, key: String) -> String {
+        if self.key_prefix.is_empty() {
+            return key;
+        }
+        match key.strip_prefix(&format!("{}/", self.key_prefix)) {
+            Some(key) => key.to_string(),
+            None => key,
+        }
+    }
+
+    /// 把默认的RequestBuilder(包括[OssConfig]中的请求默认值)合并到`build`
+    pub(crate) fn merge_default_build(&self, build: RequestBuilder) -> RequestBuilder {
+        build.merge_defaults(&self.default_build)
+    }
+
+    /// 返回默认的RequestBuilder，即[OSS::with_default_builder]设置的值叠加在[OssConfig]的请求默认值之上，
+    /// 之后的设置会覆盖默认值
+    pub fn request(&self) -> RequestBuilder {
+        self.default_build.as_ref().clone()
+    }
+
+    /// API请求在endpoint没有写明协议时是否使用https，请求上的设置优先，其次是[OssConfig::https]
+    pub(crate) fn api_https(&self, build: &RequestBuilder) -> bool {
+        build.https.unwrap_or(self.config.https && !self.legacy_http)
+    }
+
+    /// 限制每秒最多发起`rps`个请求(令牌桶，允许`rps`个请求的突发)，`rps`为0时按1处理
+    ///
+    /// 限流器由当前实例及其所有clone共享，同步和异步接口都生效。
+    /// 限流和并发数无关：无论多少个线程/任务同时发起请求，超出的请求都会排队等待令牌，
+    /// 合计请求速率不会超过`rps`，用于避免触发OSS的QPS限制
+    pub fn with_rate_limit(mut self, rps: u32) -> Self {
+        self.rate_limiter = Some(Arc::new(RateLimiter::new(rps)));
+        self
+    }
+
+    /// 缓存列举对象的结果，`ttl`内相同的列举请求(bucket、prefix、分页参数等都相同)直接返回缓存，
+    /// 最多缓存`capacity`个结果，`capacity`为0时按1处理
+    ///
+    /// OSS的列举接口不支持条件请求(If-Modified-Since/ETag)，缓存期间看不到新写入或删除的对象，
+    /// 可以调用[OSS::clear_list_cache]手动清空。缓存由当前实例及其所有clone共享，适合频繁轮询列举的面板
+    pub fn with_list_cache(mut self, ttl: Duration, capacity: usize) -> Self {
+        self.list_cache = Some(Arc::new(ListCache::new(ttl, capacity)));
+        self
+    }
+
+    /// 清空列举结果缓存，没有设置[OSS::with_list_cache]时什么都不做
+    pub fn clear_list_cache(&self) {
+        if let Some(cache) = &self.list_cache {
+            cache.clear();
         }
     }
 
+    /// 获取与请求TCP选项对应的client，同一个OSS实例及其clone共享client和连接池
+    ///
+    /// 请求上的TCP选项优先，没有设置时使用配置中的值
+    pub(crate) fn http_client(&self, build: &RequestBuilder) -> Result<HttpClient, OssError> {
+        let keepalive = build.tcp_keepalive.or(self.default_build.tcp_keepalive);
+        let nodelay = build.tcp_nodelay.or(self.default_build.tcp_nodelay);
+        let key = (keepalive, nodelay);
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(client) = clients.get(&key) {
+            return Ok(client.clone());
+        }
+        let mut builder = HttpClient::builder();
+        if let Some(keepalive) = keepalive {
+            builder = builder.tcp_keepalive(keepalive);
+        }
+        if let Some(nodelay) = nodelay {
+            builder = builder.tcp_nodelay(nodelay);
+        }
+        if let Some(timeout) = self.config.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.config.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(user_agent) = &self.config.user_agent {
+            builder = builder.user_agent(user_agent.as_str());
+        }
+        let client = builder.build()?;
+        clients.insert(key, client.clone());
+        Ok(client)
+    }
+
+    /// 根据响应的Date头记录本地时间和服务器时间的差值，超过[SERVER_SKEW_WARNING]时输出警告
+    pub(crate) fn record_server_time(&self, headers: &HeaderMap) {
+        let server_time = match headers.get(DATE).and_then(|v| v.to_str().ok()).and_then(date::parse_http_date) {
+            Some(server_time) => server_time,
+            None => return,
+        };
+        let skew = (Utc::now() - server_time).abs().to_std().unwrap_or_default();
+        if skew > SERVER_SKEW_WARNING {
+            warn!("oss log: local clock differs from oss server time by {:?}", skew);
+        }
+        *self.server_skew.lock().unwrap_or_else(|e| e.into_inner()) = Some(skew);
+    }
+
+    /// 最近一次响应中OSS服务器时间(Date头)和本地时间相差的绝对值，还没有收到带Date头的响应时返回None
+    ///
+    /// Date头精确到秒并且包含网络延迟，误差在1秒以上，适合用来尽早发现主机时钟漂移，
+    /// 不必等到相差15分钟请求被拒绝(RequestTimeTooSkewed)
+    ///
+    /// # 使用例子
+    ///
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::oss::{OSS, SERVER_SKEW_WARNING};
+    /// let oss = OSS::from_env();
+    /// if let Some(skew) = oss.last_server_skew() {
+    ///     if skew > SERVER_SKEW_WARNING {
+    ///         println!("clock skew: {:?}", skew);
+    ///     }
+    /// }
+    /// ```
+    pub fn last_server_skew(&self) -> Option<Duration> {
+        *self.server_skew.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 释放共享的http client和连接池，正在进行的请求不受影响，完成后连接随之关闭
+    ///
+    /// client被所有clone共享，close后其它clone再发起请求会重新创建client，
+    /// 适合轮换AccessKey重建OSS实例或者测试结束时确定性地清理连接
+    pub fn close(self) {
+        let clients = std::mem::take(&mut *self.clients.lock().unwrap_or_else(|e| e.into_inner()));
+        drop(clients);
+    }
+
     pub fn format_url<S: AsRef<str>>(&self, bucket: S, key: S, build: &RequestBuilder) -> String {
         let key = {
-            if build.parameters.len() > 0 {
-                let mut params = build.parameters.iter().collect::<Vec<_>>();
-                params.sort_by(|a, b| a.0.cmp(&b.0));
+            if !build.parameters.is_empty() || !build.query.is_empty() {
+                let mut params = build.parameters.iter().chain(build.query.iter()).collect::<Vec<_>>();
+                params.sort_by(|a, b| a.0.cmp(b.0));
                 format!(
                     "{}?{}",
                     key.as_ref(),
                     params
                         .into_iter()
-                        .map(|(k, v)| format!("{}={}", k, v))
+                        .map(|(k, v)| {
+                            if v.is_empty() {
+                                k.to_string()
+                            } else {
+                                format!("{}={}", k, urlencoding::encode(v))
+                            }
+                        })
                         .collect::<Vec<_>>()
                         .join("&")
                 )
@@ -126,52 +520,117 @@ impl<'a> OSS {
         if let Some(cdn) = &build.cdn {
             format!("{}{}", cdn, key,)
         } else {
-            if self.endpoint().starts_with("https") {
-                format!(
-                    "https://{}.{}{}",
-                    bucket.as_ref(),
-                    self.endpoint().replacen("https://", "", 1),
-                    key,
-                )
-            } else {
-                format!(
-                    "http://{}.{}{}",
-                    bucket.as_ref(),
-                    self.endpoint().replacen("http://", "", 1),
-                    key,
-                )
-            }
+            format!("{}{}", self.bucket_url_base(bucket.as_ref(), build, self.api_https(build)), key)
         }
     }
 
+    /// 请求使用的协议和去掉协议的endpoint
+    ///
+    /// 优先使用[RequestBuilder::with_scheme]强制的协议，其次是endpoint中写明的协议，都没有时使用`default_https`
+    pub(crate) fn endpoint_scheme_and_host(&self, build: &RequestBuilder, default_https: bool) -> (&'static str, &str) {
+        let endpoint = self.endpoint_str();
+        let (explicit, host) = if let Some(host) = endpoint.strip_prefix("https://") {
+            (Some(true), host)
+        } else if let Some(host) = endpoint.strip_prefix("http://") {
+            (Some(false), host)
+        } else {
+            (None, endpoint)
+        };
+        if build.force_https.or(explicit).unwrap_or(default_https) {
+            ("https://", host)
+        } else {
+            ("http://", host)
+        }
+    }
+
+    /// bucket的访问地址(不含key)，按配置使用`bucket.endpoint`、path-style的`endpoint/bucket`或者CNAME的`endpoint`
+    pub(crate) fn bucket_url_base(&self, bucket: &str, build: &RequestBuilder, default_https: bool) -> String {
+        let (schema, host) = self.endpoint_scheme_and_host(build, default_https);
+        if self.config.cname {
+            format!("{}{}", schema, host)
+        } else if self.config.path_style {
+            format!("{}{}/{}", schema, host, bucket)
+        } else {
+            format!("{}{}.{}", schema, bucket, host)
+        }
+    }
+
+    /// 拷贝源`x-oss-copy-source`，格式为`/bucket/key`，key需要URL编码
+    pub(crate) fn copy_source<S: AsRef<str>>(&self, source_key: S, build: &RequestBuilder) -> String {
+        let bucket = build.bucket.as_deref().unwrap_or(self.bucket_str());
+        format!("/{}{}", bucket, self.key_urlencode(self.format_key(source_key)))
+    }
+
     pub fn build_request<S: AsRef<str>>(
         &self,
         key: S,
         build: RequestBuilder,
-    ) -> Result<(String, HeaderMap), InvalidHeaderValue> {
-        let mut build = build.clone();
-        let url = self.format_url(self.bucket(), key.as_ref().to_string(), &build);
+    ) -> Result<(String, HeaderMap), OssError> {
+        let mut build = self.merge_default_build(build);
+        let bucket = build.bucket.as_deref().unwrap_or(self.bucket_str());
+        if bucket.is_empty() && !matches!(key.as_ref(), "" | "/") {
+            return Err(OssError::Err(format!(
+                "bucket is required for object operation: {}",
+                key.as_ref()
+            )));
+        }
+        check_user_metadata_size(&build.oss_headers)?;
+        let url = self.format_url(bucket, key.as_ref(), &build);
         let mut header = HeaderMap::new();
         let date = self.date();
         header.insert(DATE, date.parse()?);
         build.headers.insert(DATE.to_string(), date);
         let key = key.as_ref();
-        let authorization = self.oss_sign(key, &build);
+        // 同一个请求只取一次凭证，SecurityToken也参与签名
+        let credentials = self.credentials();
+        if let Some(security_token) = &credentials.security_token {
+            build.oss_headers.insert("x-oss-security-token".to_string(), security_token.to_string());
+        }
+        let authorization = format!("OSS {}:{}", credentials.key_id, self.sign_with(&credentials, key, &build));
+        for (name, value) in &build.oss_headers {
+            let name = HeaderName::try_from(name.as_str())
+                .map_err(|e| OssError::Err(format!("invalid header name {}: {}", name, e)))?;
+            header.insert(name, value.parse()?);
+        }
+        // Content-Type和Content-MD5参与签名，设置了就必须随请求发送
         if let Some(content_type) = build.content_type {
             header.insert(CONTENT_TYPE, content_type.parse()?);
         }
+        if let Some(content_md5) = build.content_md5 {
+            header.insert("content-md5", content_md5.parse()?);
+        }
+        // 其它标准header(例如Expires)不参与签名，直接发送
+        for (name, value) in build.headers.iter().filter(|(k, _)| !k.eq_ignore_ascii_case(DATE.as_str())) {
+            let name = HeaderName::try_from(name.as_str())
+                .map_err(|e| OssError::Err(format!("invalid header name {}: {}", name, e)))?;
+            header.insert(name, value.parse()?);
+        }
         header.insert(AUTHORIZATION, authorization.parse()?);
         Ok((url, header))
     }
+    /// 构建bucket级别的请求(列举对象、bucket配置等)，URL为bucket根路径，签名资源为`/bucket/`
+    pub(crate) fn build_bucket_request(&self, build: RequestBuilder) -> Result<(String, HeaderMap), OssError> {
+        let build = self.merge_default_build(build);
+        if build.bucket.as_deref().unwrap_or(self.bucket_str()).is_empty() {
+            return Err(OssError::Err("bucket is required for bucket operation".to_string()));
+        }
+        self.build_request("/", build)
+    }
     pub fn date(&self) -> String {
-        let now: DateTime<Utc> = Utc::now();
-        now.format("%a, %d %b %Y %T GMT").to_string()
+        date::http_date(Utc::now())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use reqwest::header::DATE;
+    use crate::auth::AuthAPI;
+    use std::sync::Arc;
+    use crate::config::{Credentials, OssConfig, RotatingCredentials};
     use crate::error::OssError;
+    use crate::oss::{parse_oss_uri, API, OSS};
+    use crate::request::RequestBuilder;
+    use crate::url::UrlApi;
     use std::io::Read;
 
     fn open_file(file_name: &str) -> Result<String, OssError> {
@@ -185,4 +644,317 @@ mod tests {
     fn test_read_file() {
         open_file("a").unwrap();
     }
+
+    #[test]
+    fn test_parse_oss_uri() {
+        let (bucket, key) = parse_oss_uri("oss://my-bucket/dir/hello.txt").unwrap();
+        assert_eq!(bucket, "my-bucket");
+        assert_eq!(key, "dir/hello.txt");
+    }
+
+    #[test]
+    fn test_parse_oss_uri_malformed() {
+        for uri in [
+            "s3://my-bucket/hello.txt",
+            "my-bucket/hello.txt",
+            "oss://my-bucket",
+            "oss://my-bucket/",
+            "oss://My_Bucket/hello.txt",
+            "oss://-bucket/hello.txt",
+            "oss://ab/hello.txt",
+        ] {
+            assert!(
+                matches!(parse_oss_uri(uri), Err(OssError::InvalidUri(_))),
+                "{} should be rejected",
+                uri
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_oss_uri_requires_config() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-shanghai.aliyuncs.com", "my-bucket");
+        assert_eq!(
+            oss.resolve_oss_uri("oss://other-bucket/hello.txt").unwrap(),
+            ("other-bucket".to_string(), "hello.txt".to_string())
+        );
+        for oss in [
+            OSS::new("key_id", "key_secret", "", "my-bucket"),
+            OSS::new("", "key_secret", "oss-cn-shanghai.aliyuncs.com", "my-bucket"),
+            OSS::new("key_id", "", "oss-cn-shanghai.aliyuncs.com", "my-bucket"),
+        ] {
+            assert!(matches!(oss.resolve_oss_uri("oss://other-bucket/hello.txt"), Err(OssError::Err(_))));
+        }
+    }
+
+    #[test]
+    fn test_format_url_sub_resource_without_value() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-shanghai.aliyuncs.com", "my-bucket");
+        let build = RequestBuilder::new().parameters_put("transferAcceleration", "");
+        assert_eq!(
+            oss.format_url("my-bucket", "/", &build),
+            "http://my-bucket.oss-cn-shanghai.aliyuncs.com/?transferAcceleration"
+        );
+    }
+
+    #[test]
+    fn test_format_url_query() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-shanghai.aliyuncs.com", "my-bucket");
+        let build = RequestBuilder::new()
+            .query_put("list-type", "2")
+            .with_prefix("dir/")
+            .with_start_after("/dir/b c.txt")
+            .with_continuation_token("ChR0ZXN0+/=");
+        assert_eq!(
+            oss.format_url("my-bucket", "/", &build),
+            "http://my-bucket.oss-cn-shanghai.aliyuncs.com/?continuation-token=ChR0ZXN0%2B%2F%3D&list-type=2&prefix=dir%2F&start-after=dir%2Fb%20c.txt"
+        );
+    }
+
+    #[test]
+    fn test_format_oss_resource_str() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-shanghai.aliyuncs.com", "my-bucket");
+        assert_eq!(oss.format_oss_resource_str("", "/"), "/");
+        assert_eq!(oss.format_oss_resource_str("", ""), "/");
+        assert_eq!(oss.format_oss_resource_str("my-bucket", "/"), "/my-bucket/");
+        assert_eq!(oss.format_oss_resource_str("my-bucket", ""), "/my-bucket/");
+        assert_eq!(oss.format_oss_resource_str("my-bucket", "/dir/a.txt"), "/my-bucket/dir/a.txt");
+        assert_eq!(oss.format_oss_resource_str("my-bucket", "dir/a.txt"), "/my-bucket/dir/a.txt");
+    }
+
+    #[test]
+    fn test_build_request_requires_bucket_for_object() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-shanghai.aliyuncs.com", "");
+        assert!(oss.build_request("/hello.txt", RequestBuilder::new()).is_err());
+        assert!(oss.build_request("/", RequestBuilder::new()).is_ok());
+    }
+
+    #[test]
+    fn test_build_request_sends_oss_headers() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-shanghai.aliyuncs.com", "my-bucket");
+        let build = RequestBuilder::new().oss_header_put("x-oss-storage-class", "IA");
+        let (_, headers) = oss.build_request("/hello.txt", build).unwrap();
+        assert_eq!(headers.get("x-oss-storage-class").unwrap(), "IA");
+    }
+
+    #[test]
+    fn test_build_request_sends_signed_content_headers() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new()
+            .with_content_type("image/jpeg")
+            .with_content_md5("eB5eJF1ptWaXm4bijSPyxw==");
+        let (_, headers) = oss.build_request("/a.jpg", build).unwrap();
+        assert_eq!(headers.get("content-type").unwrap(), "image/jpeg");
+        assert_eq!(headers.get("content-md5").unwrap(), "eB5eJF1ptWaXm4bijSPyxw==");
+    }
+
+    #[test]
+    fn test_build_request_sends_expires_header() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let expires = chrono::DateTime::parse_from_rfc3339("2030-01-01T00:00:00Z").unwrap().with_timezone(&chrono::Utc);
+        let build = RequestBuilder::new().with_expires_header(expires);
+        let (_, headers) = oss.build_request("/a.js", build.clone()).unwrap();
+        assert_eq!(headers.get("expires").unwrap(), "Tue, 01 Jan 2030 00:00:00 GMT");
+        // Expires不参与V1签名
+        let mut signed = build.clone();
+        let mut unsigned = RequestBuilder::new();
+        signed.headers.insert(DATE.to_string(), "Thu, 17 Nov 2005 18:49:58 GMT".to_string());
+        unsigned.headers.insert(DATE.to_string(), "Thu, 17 Nov 2005 18:49:58 GMT".to_string());
+        assert_eq!(oss.sign("/a.js", &signed), oss.sign("/a.js", &unsigned));
+    }
+
+    #[test]
+    fn test_build_request_rejects_large_user_metadata() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new()
+            .oss_header_put("x-oss-meta-a", "x".repeat(4096).as_str())
+            .oss_header_put("x-oss-storage-class", "y".repeat(8192).as_str());
+        assert!(oss.build_request("/a.txt", build.clone()).is_ok());
+        let build = build.oss_header_put("X-Oss-Meta-B", "x".repeat(4096).as_str());
+        match oss.build_request("/a.txt", build) {
+            Err(OssError::MetadataTooLarge { size, limit }) => {
+                assert_eq!(size, 12 + 4096 + 12 + 4096);
+                assert_eq!(limit, 8192);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_with_scheme() {
+        let oss = OSS::new("key_id", "key_secret", "https://oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let (url, _) = oss.build_request("/a.txt", RequestBuilder::new().with_scheme(false)).unwrap();
+        assert_eq!(url, "http://bucket.oss-cn-hangzhou.aliyuncs.com/a.txt");
+        let url = oss.sign_download_url("/a.txt", &RequestBuilder::new().with_scheme(false));
+        assert!(url.starts_with("http://bucket.oss-cn-hangzhou.aliyuncs.com/a.txt?"));
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let (url, _) = oss.build_request("/a.txt", RequestBuilder::new()).unwrap();
+        assert_eq!(url, "http://bucket.oss-cn-hangzhou.aliyuncs.com/a.txt");
+        let (url, _) = oss.build_request("/a.txt", RequestBuilder::new().with_scheme(true)).unwrap();
+        assert_eq!(url, "https://bucket.oss-cn-hangzhou.aliyuncs.com/a.txt");
+    }
+
+    #[test]
+    fn test_build_bucket_request() {
+        let oss = OSS::new("key_id", "key_secret", "https://oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let build = RequestBuilder::new().with_prefix("dir/");
+        let (url, _) = oss.build_bucket_request(build.clone()).unwrap();
+        assert_eq!(url, "https://bucket.oss-cn-hangzhou.aliyuncs.com/?prefix=dir%2F");
+        let (url, _) = oss.build_bucket_request(build.with_bucket("other")).unwrap();
+        assert_eq!(url, "https://other.oss-cn-hangzhou.aliyuncs.com/?prefix=dir%2F");
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "");
+        assert!(oss.build_bucket_request(RequestBuilder::new()).is_err());
+    }
+
+    #[test]
+    fn test_copy_source() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-shanghai.aliyuncs.com", "my-bucket");
+        let build = RequestBuilder::new();
+        assert_eq!(oss.copy_source("dir/中文.txt", &build), "/my-bucket/dir/%E4%B8%AD%E6%96%87.txt");
+        let build = build.with_bucket("other-bucket");
+        assert_eq!(oss.copy_source("/a b.txt", &build), "/other-bucket/a%20b.txt");
+    }
+
+    #[test]
+    fn test_http_client_cached_per_tcp_options() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let clone = oss.clone();
+        let build = RequestBuilder::new();
+        oss.http_client(&build).unwrap();
+        clone.http_client(&build).unwrap();
+        assert_eq!(oss.clients.lock().unwrap().len(), 1);
+        let build = build
+            .with_tcp_keepalive(std::time::Duration::from_secs(30))
+            .with_tcp_nodelay(true);
+        oss.http_client(&build).unwrap();
+        oss.http_client(&build).unwrap();
+        assert_eq!(clone.clients.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_close_releases_shared_clients() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let clone = oss.clone();
+        oss.http_client(&RequestBuilder::new()).unwrap();
+        oss.close();
+        assert!(clone.clients.lock().unwrap().is_empty());
+        clone.http_client(&RequestBuilder::new()).unwrap();
+        assert_eq!(clone.clients.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_with_config() {
+        let config = OssConfig::new("oss-cn-hangzhou.aliyuncs.com", "bucket")
+            .with_expire(600)
+            .with_path_style(true)
+            .with_tcp_nodelay(true);
+        let oss = OSS::with_config(Credentials::new("key_id", "key_secret"), config);
+        assert_eq!(
+            oss.format_url("bucket", "/a.txt", &RequestBuilder::new()),
+            "https://oss-cn-hangzhou.aliyuncs.com/bucket/a.txt"
+        );
+        let build = oss.request();
+        assert_eq!(build.expire, Some(600));
+        assert_eq!(build.with_expire(30).expire, Some(30));
+        oss.http_client(&RequestBuilder::new()).unwrap();
+        oss.http_client(&RequestBuilder::new().with_tcp_nodelay(true)).unwrap();
+        assert_eq!(oss.clients.lock().unwrap().len(), 1);
+        oss.http_client(&RequestBuilder::new().with_tcp_nodelay(false)).unwrap();
+        assert_eq!(oss.clients.lock().unwrap().len(), 2);
+
+        let legacy = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        assert_eq!(
+            legacy.format_url("bucket", "/a.txt", &RequestBuilder::new()),
+            "http://bucket.oss-cn-hangzhou.aliyuncs.com/a.txt"
+        );
+    }
+
+    #[test]
+    fn test_credential_rotation() {
+        let credentials = Arc::new(RotatingCredentials::new(Credentials::new("old_id", "old_secret")));
+        let oss = OSS::with_credential_provider(
+            credentials.clone(),
+            OssConfig::new("oss-cn-hangzhou.aliyuncs.com", "bucket"),
+        );
+        let clone = oss.clone();
+        let (_, headers) = oss.build_request("/a.txt", RequestBuilder::new()).unwrap();
+        assert!(headers.get("authorization").unwrap().to_str().unwrap().starts_with("OSS old_id:"));
+        assert!(headers.get("x-oss-security-token").is_none());
+
+        credentials.set(Credentials::new("new_id", "new_secret").with_security_token("sts-token"));
+        let (_, headers) = clone.build_request("/a.txt", RequestBuilder::new()).unwrap();
+        assert!(headers.get("authorization").unwrap().to_str().unwrap().starts_with("OSS new_id:"));
+        assert_eq!(headers.get("x-oss-security-token").unwrap(), "sts-token");
+
+        let mut build = RequestBuilder::new().oss_header_put("x-oss-security-token", "sts-token");
+        build.headers.insert(DATE.to_string(), "Thu, 17 Nov 2005 18:49:58 GMT".to_string());
+        let new_oss = OSS::new("new_id", "new_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        #[allow(deprecated)]
+        {
+            assert_eq!(new_oss.key_id_str(), "new_id");
+            assert_eq!(new_oss.key_secret_str(), "new_secret");
+            assert_eq!(oss.key_id_str(), "old_id");
+        }
+        assert_eq!(oss.sign("/a.txt", &build), new_oss.sign("/a.txt", &build));
+    }
+
+    #[test]
+    fn test_default_builder() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket")
+            .with_default_builder(
+                RequestBuilder::new()
+                    .with_expire(600)
+                    .with_bucket("default-bucket")
+                    .oss_header_put("x-oss-storage-class", "IA")
+                    .oss_header_put("x-oss-meta-app", "default"),
+            );
+        assert_eq!(oss.request().expire, Some(600));
+        let (url, headers) = oss
+            .build_request("/a.txt", RequestBuilder::new().oss_header_put("x-oss-meta-app", "call"))
+            .unwrap();
+        assert_eq!(url, "http://default-bucket.oss-cn-hangzhou.aliyuncs.com/a.txt");
+        assert_eq!(headers.get("x-oss-storage-class").unwrap(), "IA");
+        assert_eq!(headers.get("x-oss-meta-app").unwrap(), "call");
+        // 单次请求的设置优先
+        let (url, _) = oss
+            .build_request("/a.txt", RequestBuilder::new().with_bucket("other-bucket"))
+            .unwrap();
+        assert_eq!(url, "http://other-bucket.oss-cn-hangzhou.aliyuncs.com/a.txt");
+        let merged = RequestBuilder::new().with_expire(30).merge_defaults(&oss.request());
+        assert_eq!(merged.expire, Some(30));
+        assert_eq!(merged.bucket.as_deref(), Some("default-bucket"));
+        // 显式设置的值和RequestBuilder::new的初始值相同时也优先于默认值
+        let merged = oss.merge_default_build(RequestBuilder::new().with_expire(60).with_scheme(true));
+        assert_eq!(merged.expire, Some(60));
+        assert_eq!(merged.https, Some(true));
+        // 默认的RequestBuilder没有设置的值使用配置
+        let oss = OSS::with_config(
+            Credentials::new("key_id", "key_secret"),
+            OssConfig::new("oss-cn-hangzhou.aliyuncs.com", "bucket").with_expire(300),
+        )
+        .with_default_builder(RequestBuilder::new().with_http());
+        assert_eq!(oss.request().expire, Some(300));
+        assert_eq!(oss.request().https, Some(false));
+    }
+
+    #[test]
+    fn test_scoped() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket");
+        let tenant = oss.scoped("/tenants/a/");
+        assert_eq!(tenant.key_prefix(), "tenants/a");
+        assert_eq!(tenant.scoped("docs").key_prefix(), "tenants/a/docs");
+        let (url, _) = tenant.build_request(tenant.format_key("b.txt"), RequestBuilder::new()).unwrap();
+        assert_eq!(url, "http://bucket.oss-cn-hangzhou.aliyuncs.com/tenants/a/b.txt");
+        // 签名资源只包含一次作用域前缀
+        let mut build = RequestBuilder::new();
+        build.headers.insert(DATE.to_string(), "Thu, 17 Nov 2005 18:49:58 GMT".to_string());
+        assert_eq!(
+            tenant.sign(tenant.format_key("/b.txt"), &build),
+            oss.sign("/tenants/a/b.txt", &build)
+        );
+        let build = tenant.scope_list_query(RequestBuilder::new().with_prefix("dir/"));
+        assert_eq!(build.query.get("prefix").unwrap(), "tenants/a/dir/");
+        assert_eq!(tenant.unscope_key("tenants/a/dir/c.txt".to_string()), "dir/c.txt");
+        assert_eq!(oss.format_key("b.txt"), "/b.txt");
+    }
 }
@@ -0,0 +1,467 @@
+use std::fmt::{Display, Formatter};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::error::OssError;
+use crate::util::base64url_encode;
+
+/// 截帧模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotMode {
+    /// 默认模式，精确截取指定时间点的帧
+    Normal,
+    /// 快速模式，截取指定时间点之前最近的关键帧
+    Fast,
+}
+
+/// 截帧输出的图片格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    Jpg,
+    Png,
+}
+
+impl Display for SnapshotFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotFormat::Jpg => write!(f, "jpg"),
+            SnapshotFormat::Png => write!(f, "png"),
+        }
+    }
+}
+
+/// 视频截帧参数构建器，生成`x-oss-process`参数
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::process::{SnapshotFormat, VideoSnapshot};
+/// use aliyun_oss_rust_sdk::request::RequestBuilder;
+/// let snapshot = VideoSnapshot::new(1000)
+///     .with_width(640)
+///     .with_format(SnapshotFormat::Jpg);
+/// assert_eq!(snapshot.to_string(), "video/snapshot,t_1000,w_640,f_jpg");
+/// let build = RequestBuilder::new().with_process(snapshot.to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoSnapshot {
+    pub time: u64,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub mode: SnapshotMode,
+    pub format: SnapshotFormat,
+    pub auto_rotate: bool,
+}
+
+impl VideoSnapshot {
+    /// 截取视频第`time`毫秒的帧，宽高为0或不设置时按视频原始比例自动计算，
+    /// 宽高上限为视频本身的宽高，由OSS服务端校验
+    pub fn new(time: u64) -> Self {
+        Self {
+            time,
+            width: None,
+            height: None,
+            mode: SnapshotMode::Normal,
+            format: SnapshotFormat::Jpg,
+            auto_rotate: false,
+        }
+    }
+    pub fn with_width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+    pub fn with_height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+    pub fn with_mode(mut self, mode: SnapshotMode) -> Self {
+        self.mode = mode;
+        self
+    }
+    pub fn with_format(mut self, format: SnapshotFormat) -> Self {
+        self.format = format;
+        self
+    }
+    /// 根据视频的旋转信息自动旋转截图
+    pub fn with_auto_rotate(mut self) -> Self {
+        self.auto_rotate = true;
+        self
+    }
+}
+
+impl Display for VideoSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "video/snapshot,t_{}", self.time)?;
+        if let Some(width) = self.width {
+            write!(f, ",w_{}", width)?;
+        }
+        if let Some(height) = self.height {
+            write!(f, ",h_{}", height)?;
+        }
+        if self.mode == SnapshotMode::Fast {
+            write!(f, ",m_fast")?;
+        }
+        write!(f, ",f_{}", self.format)?;
+        if self.auto_rotate {
+            write!(f, ",ar_auto")?;
+        }
+        Ok(())
+    }
+}
+
+/// 水印位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatermarkPosition {
+    NorthWest,
+    North,
+    NorthEast,
+    West,
+    Center,
+    East,
+    SouthWest,
+    South,
+    SouthEast,
+}
+
+impl Display for WatermarkPosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatermarkPosition::NorthWest => write!(f, "nw"),
+            WatermarkPosition::North => write!(f, "north"),
+            WatermarkPosition::NorthEast => write!(f, "ne"),
+            WatermarkPosition::West => write!(f, "west"),
+            WatermarkPosition::Center => write!(f, "center"),
+            WatermarkPosition::East => write!(f, "east"),
+            WatermarkPosition::SouthWest => write!(f, "sw"),
+            WatermarkPosition::South => write!(f, "south"),
+            WatermarkPosition::SouthEast => write!(f, "se"),
+        }
+    }
+}
+
+/// 图片处理参数构建器，按调用顺序组合各处理操作，生成`x-oss-process`参数
+///
+/// 水印文字、字体和水印图片的key会自动做URL安全的base64编码，参数超出OSS允许的范围时返回错误
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::process::{ImageProcess, WatermarkPosition};
+/// use aliyun_oss_rust_sdk::request::RequestBuilder;
+/// let process = ImageProcess::new()
+///     .resize(Some(300), Some(300))
+///     .and_then(|p| p.watermark_text("Hello 图片服务！", "wqy-zenhei", 30, "FFFFFF", WatermarkPosition::SouthEast, 100))
+///     .unwrap();
+/// assert_eq!(
+///     process.to_string(),
+///     "image/resize,w_300,h_300/watermark,text_SGVsbG8g5Zu-54mH5pyN5Yqh77yB,type_d3F5LXplbmhlaQ,size_30,color_FFFFFF,g_se,t_100"
+/// );
+/// let build = RequestBuilder::new().with_process(process.to_string());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImageProcess {
+    pub operations: Vec<String>,
+}
+
+impl ImageProcess {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 缩放，只指定宽或高时按比例缩放，宽高取值范围[1,16384]，宽高都没有指定时返回错误
+    pub fn resize(mut self, width: Option<u32>, height: Option<u32>) -> Result<Self, OssError> {
+        if width.is_none() && height.is_none() {
+            return Err(OssError::Err("resize requires width or height".to_string()));
+        }
+        for (name, value) in [("width", width), ("height", height)] {
+            if let Some(value) = value {
+                check_size("resize", name, value, MAX_RESIZE_SIZE)?;
+            }
+        }
+        let mut operation = "resize".to_string();
+        if let Some(width) = width {
+            operation.push_str(&format!(",w_{}", width));
+        }
+        if let Some(height) = height {
+            operation.push_str(&format!(",h_{}", height));
+        }
+        self.operations.push(operation);
+        Ok(self)
+    }
+    /// 从(x, y)开始裁剪width x height大小的区域，宽高取值范围[1,4096]
+    pub fn crop(mut self, x: u32, y: u32, width: u32, height: u32) -> Result<Self, OssError> {
+        check_size("crop", "width", width, MAX_PROCESS_SIZE)?;
+        check_size("crop", "height", height, MAX_PROCESS_SIZE)?;
+        self.operations.push(format!("crop,x_{},y_{},w_{},h_{}", x, y, width, height));
+        Ok(self)
+    }
+    /// 文字水印
+    ///
+    /// `color`为RGB十六进制颜色，例如`FFFFFF`，`size`取值范围(0,1000]，`transparency`取值范围[0,100]
+    pub fn watermark_text<S: AsRef<str>>(
+        mut self,
+        text: S,
+        font: S,
+        size: u32,
+        color: S,
+        position: WatermarkPosition,
+        transparency: u8,
+    ) -> Result<Self, OssError> {
+        if size == 0 || size > 1000 {
+            return Err(OssError::Err(format!("watermark text size must be in (0, 1000], got {}", size)));
+        }
+        check_transparency(transparency)?;
+        self.operations.push(format!(
+            "watermark,text_{},type_{},size_{},color_{},g_{},t_{}",
+            base64url_encode(text.as_ref()),
+            base64url_encode(font.as_ref()),
+            size,
+            color.as_ref().trim_start_matches('#'),
+            position,
+            transparency,
+        ));
+        Ok(self)
+    }
+    /// 图片水印，`key`为同一bucket下的水印图片，可以带上水印图片自身的处理参数，
+    /// 例如`panda.png?x-oss-process=image/resize,P_30`，`transparency`取值范围[0,100]
+    pub fn watermark_image<S: AsRef<str>>(
+        mut self,
+        key: S,
+        position: WatermarkPosition,
+        transparency: u8,
+    ) -> Result<Self, OssError> {
+        check_transparency(transparency)?;
+        let key = key.as_ref().trim_start_matches('/');
+        self.operations.push(format!(
+            "watermark,image_{},g_{},t_{}",
+            base64url_encode(key),
+            position,
+            transparency,
+        ));
+        Ok(self)
+    }
+}
+
+/// 缩放的宽高上限
+const MAX_RESIZE_SIZE: u32 = 16384;
+
+/// 缩放以外的图片处理(裁剪等)的宽高上限
+const MAX_PROCESS_SIZE: u32 = 4096;
+
+fn check_size(operation: &str, name: &str, value: u32, max: u32) -> Result<(), OssError> {
+    if value == 0 || value > max {
+        return Err(OssError::Err(format!(
+            "{} {} must be in [1, {}], got {}",
+            operation, name, max, value
+        )));
+    }
+    Ok(())
+}
+
+fn check_transparency(transparency: u8) -> Result<(), OssError> {
+    if transparency > 100 {
+        return Err(OssError::Err(format!(
+            "watermark transparency must be in [0, 100], got {}",
+            transparency
+        )));
+    }
+    Ok(())
+}
+
+impl Display for ImageProcess {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "image/{}", self.operations.join("/"))
+    }
+}
+
+/// 数据处理结果另存为(sys/saveas)的返回结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessSaveResult {
+    pub bucket: String,
+    #[serde(rename = "fileSize")]
+    pub file_size: u64,
+    pub object: String,
+    pub status: String,
+}
+
+/// 生成另存为请求的body，例如`x-oss-process=image/resize,w_100|sys/saveas,o_<key>,b_<bucket>`
+pub(crate) fn save_as_body(process: &str, dest_key: &str, dest_bucket: Option<&str>) -> String {
+    let mut body = format!(
+        "x-oss-process={}|sys/saveas,o_{}",
+        process,
+        base64url_encode(dest_key.trim_start_matches('/'))
+    );
+    if let Some(bucket) = dest_bucket {
+        body.push_str(&format!(",b_{}", base64url_encode(bucket)));
+    }
+    body
+}
+
+/// 图片信息(`image/info`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    pub format: String,
+    pub file_size: u64,
+    pub orientation: Option<u32>,
+    /// 除上述字段外的其它EXIF信息
+    pub exif: HashMap<String, String>,
+}
+
+impl ImageInfo {
+    /// 解析`image/info`返回的`{"ImageWidth": {"value": "1024"}}`格式的json
+    pub fn from_json<S: AsRef<str>>(json: S) -> Result<ImageInfo, OssError> {
+        let json = json.as_ref();
+        let value: HashMap<String, serde_json::Value> = serde_json::from_str(json)?;
+        let mut fields = value
+            .into_iter()
+            .filter_map(|(k, v)| {
+                let v = v.get("value")?.as_str()?.to_string();
+                Some((k, v))
+            })
+            .collect::<HashMap<_, _>>();
+        let mut required = |name: &str| {
+            fields
+                .remove(name)
+                .ok_or_else(|| OssError::Err(format!("image info missing {}: {}", name, json)))
+        };
+        let parse_err = |name: &str, value: &str| OssError::Err(format!("image info invalid {}: {}", name, value));
+        let width = required("ImageWidth")?;
+        let height = required("ImageHeight")?;
+        let format = required("Format")?;
+        let file_size = required("FileSize")?;
+        let orientation = fields.remove("Orientation");
+        Ok(ImageInfo {
+            width: width.parse().map_err(|_| parse_err("ImageWidth", &width))?,
+            height: height.parse().map_err(|_| parse_err("ImageHeight", &height))?,
+            format,
+            file_size: file_size.parse().map_err(|_| parse_err("FileSize", &file_size))?,
+            orientation: orientation.and_then(|v| v.parse().ok()),
+            exif: fields,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::process::{save_as_body, ImageInfo, ImageProcess, ProcessSaveResult, SnapshotFormat, SnapshotMode, VideoSnapshot, WatermarkPosition};
+
+    #[test]
+    fn test_video_snapshot_default() {
+        assert_eq!(VideoSnapshot::new(0).to_string(), "video/snapshot,t_0,f_jpg");
+    }
+
+    #[test]
+    fn test_video_snapshot_full() {
+        let snapshot = VideoSnapshot::new(7000)
+            .with_width(800)
+            .with_height(600)
+            .with_mode(SnapshotMode::Fast)
+            .with_format(SnapshotFormat::Png)
+            .with_auto_rotate();
+        assert_eq!(
+            snapshot.to_string(),
+            "video/snapshot,t_7000,w_800,h_600,m_fast,f_png,ar_auto"
+        );
+    }
+
+    #[test]
+    fn test_image_watermark_text() {
+        let process = ImageProcess::new().watermark_text(
+            "Hello 图片服务！",
+            "wqy-zenhei",
+            30,
+            "#FFFFFF",
+            WatermarkPosition::SouthEast,
+            100,
+        ).unwrap();
+        assert_eq!(
+            process.to_string(),
+            "image/watermark,text_SGVsbG8g5Zu-54mH5pyN5Yqh77yB,type_d3F5LXplbmhlaQ,size_30,color_FFFFFF,g_se,t_100"
+        );
+    }
+
+    #[test]
+    fn test_image_watermark_image_with_process() {
+        let process = ImageProcess::new()
+            .resize(Some(100), None)
+            .unwrap()
+            .crop(0, 0, 80, 60)
+            .unwrap()
+            .watermark_image("/panda.png?x-oss-process=image/resize,P_30", WatermarkPosition::Center, 90)
+            .unwrap();
+        assert_eq!(
+            process.to_string(),
+            "image/resize,w_100/crop,x_0,y_0,w_80,h_60/watermark,image_cGFuZGEucG5nP3gtb3NzLXByb2Nlc3M9aW1hZ2UvcmVzaXplLFBfMzA,g_center,t_90"
+        );
+    }
+
+    #[test]
+    fn test_image_process_out_of_range() {
+        assert!(ImageProcess::new().watermark_image("panda.png", WatermarkPosition::SouthEast, 101).is_err());
+        assert!(ImageProcess::new()
+            .watermark_text("hi", "wqy-zenhei", 0, "FFFFFF", WatermarkPosition::SouthEast, 100)
+            .is_err());
+        assert!(ImageProcess::new()
+            .watermark_text("hi", "wqy-zenhei", 30, "FFFFFF", WatermarkPosition::SouthEast, 101)
+            .is_err());
+        assert!(ImageProcess::new().resize(None, None).is_err());
+        assert!(ImageProcess::new().resize(Some(16384), Some(1)).is_ok());
+        assert!(ImageProcess::new().resize(Some(16385), None).is_err());
+        assert!(ImageProcess::new().resize(None, Some(0)).is_err());
+        assert!(ImageProcess::new().crop(10, 10, 4096, 4096).is_ok());
+        assert!(ImageProcess::new().crop(0, 0, 4097, 100).is_err());
+        assert!(ImageProcess::new().crop(0, 0, 100, 0).is_err());
+    }
+
+    #[test]
+    fn test_save_as_body() {
+        assert_eq!(
+            save_as_body("image/resize,w_100", "/thumbs/panda.png", None),
+            "x-oss-process=image/resize,w_100|sys/saveas,o_dGh1bWJzL3BhbmRhLnBuZw"
+        );
+        assert_eq!(
+            save_as_body("image/resize,w_100", "panda.png", Some("test-bucket")),
+            "x-oss-process=image/resize,w_100|sys/saveas,o_cGFuZGEucG5n,b_dGVzdC1idWNrZXQ"
+        );
+    }
+
+    #[test]
+    fn test_process_save_result_json() {
+        let result: ProcessSaveResult = serde_json::from_str(
+            r#"{"bucket": "test-bucket", "fileSize": 21839, "object": "panda.png", "status": "OK"}"#,
+        )
+        .unwrap();
+        assert_eq!(result.bucket, "test-bucket");
+        assert_eq!(result.file_size, 21839);
+        assert_eq!(result.object, "panda.png");
+        assert_eq!(result.status, "OK");
+    }
+
+    #[test]
+    fn test_image_info_from_json() {
+        let info = ImageInfo::from_json(
+            r#"{
+                "FileSize": {"value": "21839"},
+                "Format": {"value": "jpg"},
+                "ImageHeight": {"value": "267"},
+                "ImageWidth": {"value": "350"},
+                "Orientation": {"value": "1"},
+                "ResolutionUnit": {"value": "1"},
+                "XResolution": {"value": "1/1"}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(info.width, 350);
+        assert_eq!(info.height, 267);
+        assert_eq!(info.format, "jpg");
+        assert_eq!(info.file_size, 21839);
+        assert_eq!(info.orientation, Some(1));
+        assert_eq!(info.exif.len(), 2);
+        assert_eq!(info.exif.get("XResolution").map(String::as_str), Some("1/1"));
+    }
+
+    #[test]
+    fn test_image_info_from_error_json() {
+        assert!(ImageInfo::from_json(r#"{"Code": "BadRequest", "Message": "unsupported image format"}"#).is_err());
+    }
+}
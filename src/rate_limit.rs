@@ -0,0 +1,90 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 令牌桶限流器，容量为每秒请求数，所有clone出来的OSS实例共享同一个限流器，`rps`为0时按1处理
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    rps: u32,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(rps: u32) -> Self {
+        let rps = rps.max(1);
+        Self {
+            rps,
+            state: Mutex::new(State {
+                tokens: rps as f64,
+                last: Instant::now(),
+            }),
+        }
+    }
+
+    /// 预占一个令牌，返回发起请求前需要等待的时间
+    pub(crate) fn reserve(&self) -> Duration {
+        self.reserve_at(Instant::now())
+    }
+
+    fn reserve_at(&self, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let rps = self.rps as f64;
+        let elapsed = now.saturating_duration_since(state.last).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * rps).min(rps);
+        state.last = now;
+        // 令牌可以透支，透支的部分就是需要排队等待的时间
+        state.tokens -= 1.0;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / rps)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use crate::config::{Credentials, OssConfig};
+    use crate::oss::OSS;
+    use crate::rate_limit::RateLimiter;
+
+    #[test]
+    fn test_reserve_burst_then_wait() {
+        let limiter = RateLimiter::new(2);
+        let now = Instant::now();
+        assert_eq!(limiter.reserve_at(now), Duration::ZERO);
+        assert_eq!(limiter.reserve_at(now), Duration::ZERO);
+        assert_eq!(limiter.reserve_at(now), Duration::from_millis(500));
+        assert_eq!(limiter.reserve_at(now), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_reserve_refill() {
+        let limiter = RateLimiter::new(10);
+        let now = Instant::now();
+        for _ in 0..10 {
+            assert_eq!(limiter.reserve_at(now), Duration::ZERO);
+        }
+        assert!(limiter.reserve_at(now) > Duration::ZERO);
+        assert_eq!(limiter.reserve_at(now + Duration::from_secs(1)), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_zero_rate_limit() {
+        let limiter = RateLimiter::new(0);
+        let now = Instant::now();
+        assert_eq!(limiter.reserve_at(now), Duration::ZERO);
+        assert_eq!(limiter.reserve_at(now), Duration::from_secs(1));
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "bucket").with_rate_limit(0);
+        assert!(oss.rate_limiter.is_some());
+        let config = OssConfig::new("oss-cn-hangzhou.aliyuncs.com", "bucket").with_rate_limit(0);
+        let oss = OSS::with_config(Credentials::new("key_id", "key_secret"), config);
+        assert!(oss.rate_limiter.is_some());
+    }
+}
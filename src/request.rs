@@ -1,5 +1,10 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use crate::date;
+use crate::entity::{Callback, CancelToken, ObjectAcl};
+use crate::error::OssError;
 
 pub type Seconds = i64;
 
@@ -30,14 +35,39 @@ unsafe impl Sync for RequestType {}
 #[derive(Clone, Debug)]
 pub struct RequestBuilder {
     pub cdn: Option<String>,
-    pub https: bool,
+    /// CDN URL鉴权(A型)的主KEY，在CDN控制台的访问控制中配置
+    pub cdn_auth_key: Option<String>,
+    pub bucket: Option<String>,
+    /// 是否使用https，没有设置时使用[crate::config::OssConfig::https]
+    pub https: Option<bool>,
+    /// 强制使用的协议，设置后忽略endpoint中写明的协议，见[RequestBuilder::with_scheme]
+    pub force_https: Option<bool>,
     pub method: RequestType,
-    pub expire: Seconds,
+    /// 签名URL的有效期(秒)，没有设置时使用[crate::config::OssConfig::expire]
+    pub expire: Option<Seconds>,
     pub headers: HashMap<String, String>,
     pub parameters: HashMap<String, String>,
+    /// 不参与签名的查询参数，例如列举对象的`prefix`、`max-keys`
+    pub query: HashMap<String, String>,
     pub content_type: Option<String>,
     pub content_md5: Option<String>,
     pub oss_headers: HashMap<String, String>,
+    pub tcp_keepalive: Option<Duration>,
+    pub tcp_nodelay: Option<bool>,
+    pub cancel_token: Option<CancelToken>,
+    pub verify_content_md5: bool,
+    /// 上传时同时发送Content-MD5并校验CRC64，见[RequestBuilder::with_full_integrity]
+    pub full_integrity: bool,
+    /// 上传和下载时校验SHA-256，见[RequestBuilder::with_verify_sha256]
+    pub verify_sha256: bool,
+    /// 批量删除时使用quiet模式，默认false(verbose)
+    pub quiet_delete: bool,
+    /// 完成分片上传时校验对象的CRC64，见[RequestBuilder::with_verify_on_complete]
+    pub verify_on_complete: bool,
+    /// 列举和bucket查询结果中同时返回原始响应body，见[RequestBuilder::with_raw_response]
+    pub raw_response: bool,
+    /// 批量操作最多同时发起的请求数，见[RequestBuilder::with_concurrency]
+    pub concurrency: Option<usize>,
 }
 
 impl Default for RequestBuilder {
@@ -54,36 +84,225 @@ impl RequestBuilder {
     pub fn new() -> Self {
         Self {
             cdn: None,
-            https: true,
+            cdn_auth_key: None,
+            bucket: None,
+            https: None,
+            force_https: None,
             method: RequestType::Get,
-            expire: 60,
+            expire: None,
             headers: HashMap::new(),
             parameters: HashMap::new(),
+            query: HashMap::new(),
             content_type: None,
             content_md5: None,
             oss_headers: HashMap::new(),
+            tcp_keepalive: None,
+            tcp_nodelay: None,
+            cancel_token: None,
+            verify_content_md5: false,
+            full_integrity: false,
+            verify_sha256: false,
+            quiet_delete: false,
+            verify_on_complete: false,
+            raw_response: false,
+            concurrency: None,
         }
     }
+    /// 以`defaults`为基础叠加当前的设置，当前设置优先
+    ///
+    /// - cdn、bucket、https、过期时间、Content-Type等可选项，当前没有设置时使用默认值
+    /// - headers、参数、查询参数和oss header合并，同名时使用当前的值
+    /// - 请求方法、Content-MD5、取消令牌、MD5校验、完整性校验和原始响应只属于单次请求，不从默认值继承
+    pub fn merge_defaults(mut self, defaults: &RequestBuilder) -> Self {
+        fn merge_map(current: &mut HashMap<String, String>, defaults: &HashMap<String, String>) {
+            for (k, v) in defaults {
+                current.entry(k.to_string()).or_insert_with(|| v.to_string());
+            }
+        }
+        self.cdn = self.cdn.or_else(|| defaults.cdn.clone());
+        self.cdn_auth_key = self.cdn_auth_key.or_else(|| defaults.cdn_auth_key.clone());
+        self.bucket = self.bucket.or_else(|| defaults.bucket.clone());
+        self.https = self.https.or(defaults.https);
+        self.force_https = self.force_https.or(defaults.force_https);
+        self.expire = self.expire.or(defaults.expire);
+        merge_map(&mut self.headers, &defaults.headers);
+        merge_map(&mut self.parameters, &defaults.parameters);
+        merge_map(&mut self.query, &defaults.query);
+        merge_map(&mut self.oss_headers, &defaults.oss_headers);
+        self.content_type = self.content_type.or_else(|| defaults.content_type.clone());
+        self.tcp_keepalive = self.tcp_keepalive.or(defaults.tcp_keepalive);
+        self.tcp_nodelay = self.tcp_nodelay.or(defaults.tcp_nodelay);
+        self.concurrency = self.concurrency.or(defaults.concurrency);
+        self
+    }
     pub fn with_http(mut self) -> Self {
-        self.https = false;
+        self.https = Some(false);
+        self
+    }
+    /// 强制本次请求使用https(`true`)或http(`false`)，不管endpoint是否写明了协议，API请求和签名URL都生效
+    ///
+    /// 例如配置的是`https://oss-cn-hangzhou.aliyuncs.com`，内网访问时可以对单次请求使用http
+    pub fn with_scheme(mut self, https: bool) -> Self {
+        self.https = Some(https);
+        self.force_https = Some(https);
         self
     }
     pub fn with_cdn<S: AsRef<str>>(mut self, cdn: S) -> Self {
         self.cdn = Some(cdn.as_ref().to_string());
         self
     }
+    /// 设置CDN URL鉴权(A型)的主KEY，[crate::url::UrlApi::shareable_url]使用它生成CDN签名链接
+    pub fn with_cdn_auth_key<S: AsRef<str>>(mut self, auth_key: S) -> Self {
+        self.cdn_auth_key = Some(auth_key.as_ref().to_string());
+        self
+    }
+    /// 设置对象的访问权限(`x-oss-object-acl`)，上传、追加和拷贝(目标对象)等写操作都会生效
+    pub fn with_object_acl(self, acl: ObjectAcl) -> Self {
+        self.oss_header_put("x-oss-object-acl".to_string(), acl.to_string())
+    }
+    /// 设置上传回调，回调参数和自定义变量通过`x-oss-callback`和`x-oss-callback-var`头发送并参与签名
+    pub fn with_callback(self, callback: &Callback) -> Self {
+        let build = self.oss_header_put("x-oss-callback".to_string(), callback.callback_header());
+        match callback.callback_var_header() {
+            Some(vars) => build.oss_header_put("x-oss-callback-var".to_string(), vars),
+            None => build,
+        }
+    }
+    /// 设置TCP keepalive，长时间运行的批量任务可以避免空闲连接被中间设备断开
+    pub fn with_tcp_keepalive(mut self, keepalive: Duration) -> Self {
+        self.tcp_keepalive = Some(keepalive);
+        self
+    }
+    /// 设置TCP_NODELAY，降低小请求的延迟
+    pub fn with_tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp_nodelay = Some(nodelay);
+        self
+    }
+    /// 设置取消令牌，令牌取消后流式下载会在下一个数据块之前停止并返回[crate::error::OssError::Cancelled]
+    pub fn with_cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+    /// 同一个操作后续请求(例如分片上传的各个分片)使用的RequestBuilder，只保留bucket、域名、协议和连接相关的设置
+    pub(crate) fn follow_up(&self) -> Self {
+        let mut build = RequestBuilder::new();
+        build.cdn = self.cdn.clone();
+        build.bucket = self.bucket.clone();
+        build.https = self.https;
+        build.force_https = self.force_https;
+        build.tcp_keepalive = self.tcp_keepalive;
+        build.tcp_nodelay = self.tcp_nodelay;
+        build.cancel_token = self.cancel_token.clone();
+        build.verify_on_complete = self.verify_on_complete;
+        build
+    }
+    /// 是否已经通过取消令牌取消
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel_token.as_ref().is_some_and(|token| token.is_cancelled())
+    }
+    /// 覆盖本次请求的bucket，默认使用OSS配置中的bucket
+    pub fn with_bucket<S: AsRef<str>>(mut self, bucket: S) -> Self {
+        self.bucket = Some(bucket.as_ref().to_string());
+        self
+    }
     pub fn with_content_type<S: AsRef<str>>(mut self, content_type: S) -> Self {
         self.content_type = Some(content_type.as_ref().to_string());
         self
     }
+    /// 设置Content-MD5(base64编码的128位MD5)，参与签名
+    pub fn with_content_md5<S: AsRef<str>>(mut self, content_md5: S) -> Self {
+        self.content_md5 = Some(content_md5.as_ref().to_string());
+        self
+    }
+    /// 下载时计算内容的MD5并和对象的Content-MD5比较，不一致时返回[crate::error::OssError::ContentMd5Mismatch]
+    ///
+    /// 只有上传时设置了Content-MD5的对象才会返回该header，没有返回时不做校验
+    pub fn with_verify_content_md5(mut self) -> Self {
+        self.verify_content_md5 = true;
+        self
+    }
+    /// 上传时计算内容的MD5和CRC64，MD5作为Content-MD5发送由OSS校验，CRC64和响应中的`x-oss-hash-crc64ecma`比较，
+    /// 不一致或者没有返回时返回[crate::error::OssError::Crc64Mismatch]
+    ///
+    /// 两个值在一次遍历中计算，适用于put_object_from_file、pub_object_from_buffer和put_object_with_callback
+    pub fn with_full_integrity(mut self) -> Self {
+        self.full_integrity = true;
+        self
+    }
+    /// 上传和下载时在本地计算内容的SHA-256，和OSS返回的`x-oss-hash-sha256`(十六进制)比较，
+    /// 不一致或者没有返回时返回[crate::error::OssError::Sha256Mismatch]
+    ///
+    /// 上传适用于put_object_from_file、pub_object_from_buffer和put_object_with_callback；
+    /// 下载适用于get_object、get_object_full、get_object_to_file和get_object_to_writer，边下载边计算，不需要缓存整个对象
+    pub fn with_verify_sha256(mut self) -> Self {
+        self.verify_sha256 = true;
+        self
+    }
+    /// 完成分片上传时，用各分片的CRC64合并出整个对象的CRC64，和响应中的`x-oss-hash-crc64ecma`比较，
+    /// 不一致或者没有返回时返回[crate::error::OssError::Crc64Mismatch]，可以发现上传损坏或顺序错误的分片
+    ///
+    /// SDK内部的分片上传(multipart_upload_reader、分片模式的BatchedWriter)会自动计算分片的CRC64；
+    /// 单独调用complete_multipart_upload时每个分片都需要通过[UploadedPart::with_crc64](crate::multipart::UploadedPart::with_crc64)设置，
+    /// 分片拷贝(例如change_storage_class拷贝超过1GB的对象)和源对象的CRC64比较
+    pub fn with_verify_on_complete(mut self) -> Self {
+        self.verify_on_complete = true;
+        self
+    }
+    /// 批量删除([delete_multiple_objects](crate::oss::OSS::delete_multiple_objects))时是否使用quiet模式
+    ///
+    /// 默认为verbose模式，响应中列出所有删除成功的key；quiet模式下OSS不返回删除结果，适合一次删除大量对象
+    pub fn with_quiet_delete(mut self, quiet: bool) -> Self {
+        self.quiet_delete = quiet;
+        self
+    }
+    /// 在解析后的结果中同时保留原始的XML响应body(`raw`字段)，SDK还没有解析的新字段可以自己从中读取
+    ///
+    /// 适用于[list_objects](crate::oss::OSS::list_objects)、[get_bucket_stat](crate::oss::OSS::get_bucket_stat)
+    /// 和[get_bucket_worm](crate::oss::OSS::get_bucket_worm)，设置后list_objects不使用列举缓存
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let build = RequestBuilder::new().with_raw_response();
+    /// assert!(build.raw_response);
+    /// ```
+    pub fn with_raw_response(mut self) -> Self {
+        self.raw_response = true;
+        self
+    }
+    /// 批量操作最多同时发起的请求数，0按1处理，没有设置时为1
+    ///
+    /// 用于[delete_by_prefix](crate::oss::OSS::delete_by_prefix)的并发批量删除
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let build = RequestBuilder::new().with_concurrency(0);
+    /// assert_eq!(build.concurrency, Some(1));
+    /// ```
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency.max(1));
+        self
+    }
+    /// 签名URL的有效期(秒)，对应签名URL中的`Expires`查询参数，和[RequestBuilder::with_expires_header]无关
     pub fn with_expire(mut self, expire: Seconds) -> Self {
-        self.expire = expire;
+        self.expire = Some(expire);
+        self
+    }
+    /// 上传时设置对象的HTTP缓存头`Expires`，OSS会保存并在下载时原样返回，适合静态资源设置较远的过期时间
+    ///
+    /// 只影响浏览器/CDN缓存，不会让对象过期，也不是签名URL的有效期(见[RequestBuilder::with_expire])
+    pub fn with_expires_header(mut self, expires: DateTime<Utc>) -> Self {
+        self.headers.insert("Expires".to_string(), date::http_date(expires));
         self
     }
     pub fn response_content_disposition<S: AsRef<str>>(mut self, file_name: S) -> Self {
         self.parameters.insert("response-content-disposition".to_string(), format!("attachment;filename={}", file_name.as_ref()));
         self
     }
+    /// 设置数据处理参数`x-oss-process`，可用于直接下载和签名URL，例如`image/resize,w_100`
+    pub fn with_process<S: AsRef<str>>(mut self, process: S) -> Self {
+        self.parameters.insert("x-oss-process".to_string(), process.as_ref().to_string());
+        self
+    }
     pub fn oss_signature_version2(mut self) -> Self {
         self.parameters.insert("x-oss-signature-version".to_string(), "OSS2".to_string());
         self
@@ -113,8 +332,59 @@ impl RequestBuilder {
         self.oss_headers.insert(key.as_ref().to_string(), value.as_ref().to_string());
         self
     }
+    /// 设置一个参与V1签名的自定义header，名称必须以`x-oss-`开头(不区分大小写)
+    ///
+    /// header名称转为小写后按字典序加入CanonicalizedOSSHeaders，并随请求发送，用于OSS新增的需要签名的header。
+    /// V1签名只有`x-oss-`开头的header参与签名，服务端不会计算其它名称的header，所以直接返回错误
+    /// # 使用例子
+    /// ```rust
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// let build = RequestBuilder::new()
+    ///     .with_signed_header("x-oss-custom-feature", "enabled")
+    ///     .unwrap();
+    /// assert!(RequestBuilder::new().with_signed_header("x-custom-feature", "enabled").is_err());
+    /// ```
+    pub fn with_signed_header<S: AsRef<str>>(self, name: S, value: S) -> Result<Self, OssError> {
+        let lower = name.as_ref().to_ascii_lowercase();
+        if !lower.starts_with("x-oss-") {
+            return Err(OssError::Err(format!(
+                "signed header {} must start with x-oss-",
+                name.as_ref()
+            )));
+        }
+        Ok(self.oss_header_put(lower.as_str(), value.as_ref()))
+    }
     pub fn parameters_put<S: AsRef<str>>(mut self, key: S, value: S) -> Self {
         self.parameters.insert(key.as_ref().to_string(), value.as_ref().to_string());
         self
     }
+    /// 添加不参与签名的查询参数
+    pub fn query_put<S: AsRef<str>>(mut self, key: S, value: S) -> Self {
+        self.query.insert(key.as_ref().to_string(), value.as_ref().to_string());
+        self
+    }
+    /// 列举对象时只返回以`prefix`开头的key
+    pub fn with_prefix<S: AsRef<str>>(self, prefix: S) -> Self {
+        self.query_put("prefix", prefix.as_ref())
+    }
+    /// 列举对象时按`delimiter`对key分组，分组结果在common_prefixes中返回
+    pub fn with_delimiter<S: AsRef<str>>(self, delimiter: S) -> Self {
+        self.query_put("delimiter", delimiter.as_ref())
+    }
+    /// 列举对象时每页最多返回的数量，最大1000
+    pub fn with_max_keys(self, max_keys: u32) -> Self {
+        self.query_put("max-keys".to_string(), max_keys.to_string())
+    }
+    /// 获取元信息时描述符号链接本身(`?symlink`)，而不是自动跟随到目标对象
+    pub fn with_no_follow_symlink(self) -> Self {
+        self.parameters_put("symlink", "")
+    }
+    /// 列举对象时从`key`之后(按字典序，不包含`key`)开始返回，可配合max-keys按key范围分片并行列举
+    pub fn with_start_after<S: AsRef<str>>(self, key: S) -> Self {
+        self.query_put("start-after", key.as_ref().trim_start_matches('/'))
+    }
+    /// 列举对象时从上一页返回的next_continuation_token继续
+    pub fn with_continuation_token<S: AsRef<str>>(self, token: S) -> Self {
+        self.parameters_put("continuation-token", token.as_ref())
+    }
 }
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::metadata::ObjectMetadata;
+
+/// 轮询退避的上限，轮询间隔最多增长到初始间隔的这个倍数
+const MAX_POLL_BACKOFF: u32 = 16;
+
+/// 等待解冻时HEAD结果的缓存，以`bucket/key`为key，所有clone出来的OSS实例共享
+///
+/// 同一个对象有多个等待任务时，在一个轮询间隔内只有一个任务发起HEAD，其它任务复用结果。
+/// 等待结束(可以读取或出错)时删除对应的结果
+#[derive(Debug, Default)]
+pub(crate) struct RestoreCache {
+    entries: Mutex<HashMap<String, (Instant, ObjectMetadata)>>,
+}
+
+impl RestoreCache {
+    /// 写入时间不超过`max_age`的结果
+    pub(crate) fn get(&self, key: &str, max_age: Duration) -> Option<ObjectMetadata> {
+        self.get_at(key, max_age, Instant::now())
+    }
+
+    pub(crate) fn insert(&self, key: String, metadata: ObjectMetadata) {
+        self.insert_at(key, metadata, Instant::now())
+    }
+
+    pub(crate) fn remove(&self, key: &str) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).remove(key);
+    }
+
+    fn get_at(&self, key: &str, max_age: Duration, now: Instant) -> Option<ObjectMetadata> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(key)
+            .filter(|(inserted, _)| now.saturating_duration_since(*inserted) < max_age)
+            .map(|(_, metadata)| metadata.clone())
+    }
+
+    fn insert_at(&self, key: String, metadata: ObjectMetadata, now: Instant) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).insert(key, (now, metadata));
+    }
+}
+
+/// 下一次轮询前的等待时间，每次翻倍，最多到`interval`的[MAX_POLL_BACKOFF]倍
+pub(crate) fn next_poll_delay(delay: Duration, interval: Duration) -> Duration {
+    delay.saturating_mul(2).min(interval.saturating_mul(MAX_POLL_BACKOFF))
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderMap;
+    use std::time::{Duration, Instant};
+    use crate::metadata::ObjectMetadata;
+    use crate::restore_cache::{next_poll_delay, RestoreCache};
+
+    #[test]
+    fn test_restore_cache_max_age() {
+        let cache = RestoreCache::default();
+        let now = Instant::now();
+        cache.insert_at("bucket/a".to_string(), ObjectMetadata::new(&HeaderMap::new()), now);
+        assert!(cache.get_at("bucket/a", Duration::from_secs(5), now + Duration::from_secs(4)).is_some());
+        assert!(cache.get_at("bucket/a", Duration::from_secs(5), now + Duration::from_secs(5)).is_none());
+        cache.remove("bucket/a");
+        assert!(cache.get_at("bucket/a", Duration::from_secs(5), now).is_none());
+    }
+
+    #[test]
+    fn test_next_poll_delay() {
+        let interval = Duration::from_secs(1);
+        assert_eq!(next_poll_delay(interval, interval), Duration::from_secs(2));
+        assert_eq!(next_poll_delay(Duration::from_secs(10), interval), Duration::from_secs(16));
+        assert_eq!(next_poll_delay(Duration::from_secs(16), interval), Duration::from_secs(16));
+    }
+}
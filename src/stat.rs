@@ -0,0 +1,213 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::entity::StorageClass;
+use crate::error::OssError;
+use crate::xml::element_text;
+
+const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// bucket的存储统计信息(GetBucketStat)，存储量单位为字节
+///
+/// 统计数据不是实时的，通常有一个小时以上的延迟
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BucketStat {
+    pub storage: u64,
+    pub object_count: u64,
+    pub multipart_upload_count: u64,
+    /// 统计数据的更新时间
+    pub last_modified_time: Option<DateTime<Utc>>,
+    pub standard_storage: u64,
+    pub standard_object_count: u64,
+    /// 低频访问的计费存储量，小于64KB的对象按64KB计算
+    pub infrequent_access_storage: u64,
+    pub infrequent_access_object_count: u64,
+    pub archive_storage: u64,
+    pub archive_object_count: u64,
+    pub cold_archive_storage: u64,
+    pub cold_archive_object_count: u64,
+    pub deep_cold_archive_storage: u64,
+    pub deep_cold_archive_object_count: u64,
+    /// 原始响应body，只有设置了[with_raw_response](crate::request::RequestBuilder::with_raw_response)时才有
+    pub raw: Option<String>,
+}
+
+impl BucketStat {
+    pub(crate) fn from_xml(xml: &str) -> Result<Self, OssError> {
+        if element_text(xml, "Storage").is_none() {
+            return Err(OssError::Err(format!("invalid bucket stat xml: {}", xml)));
+        }
+        let number = |tag: &str| element_text(xml, tag).and_then(|v| v.parse::<u64>().ok()).unwrap_or_default();
+        Ok(Self {
+            storage: number("Storage"),
+            object_count: number("ObjectCount"),
+            multipart_upload_count: number("MultipartUploadCount"),
+            last_modified_time: element_text(xml, "LastModifiedTime")
+                .and_then(|v| v.parse().ok())
+                .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+            standard_storage: number("StandardStorage"),
+            standard_object_count: number("StandardObjectCount"),
+            infrequent_access_storage: number("InfrequentAccessStorage"),
+            infrequent_access_object_count: number("InfrequentAccessObjectCount"),
+            archive_storage: number("ArchiveStorage"),
+            archive_object_count: number("ArchiveObjectCount"),
+            cold_archive_storage: number("ColdArchiveStorage"),
+            cold_archive_object_count: number("ColdArchiveObjectCount"),
+            deep_cold_archive_storage: number("DeepColdArchiveStorage"),
+            deep_cold_archive_object_count: number("DeepColdArchiveObjectCount"),
+            raw: None,
+        })
+    }
+
+    /// 指定存储类型的计费存储量(字节)
+    pub fn storage_of(&self, storage_class: StorageClass) -> u64 {
+        match storage_class {
+            StorageClass::Standard => self.standard_storage,
+            StorageClass::IA => self.infrequent_access_storage,
+            StorageClass::Archive => self.archive_storage,
+            StorageClass::ColdArchive => self.cold_archive_storage,
+            StorageClass::DeepColdArchive => self.deep_cold_archive_storage,
+        }
+    }
+}
+
+/// 各存储类型每GB每月的单价，币种由调用方决定，没有设置的存储类型按0计算
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PricingTable {
+    pub standard: f64,
+    pub infrequent_access: f64,
+    pub archive: f64,
+    pub cold_archive: f64,
+    pub deep_cold_archive: f64,
+}
+
+impl PricingTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_price(mut self, storage_class: StorageClass, price_per_gb: f64) -> Self {
+        match storage_class {
+            StorageClass::Standard => self.standard = price_per_gb,
+            StorageClass::IA => self.infrequent_access = price_per_gb,
+            StorageClass::Archive => self.archive = price_per_gb,
+            StorageClass::ColdArchive => self.cold_archive = price_per_gb,
+            StorageClass::DeepColdArchive => self.deep_cold_archive = price_per_gb,
+        }
+        self
+    }
+    pub fn price_of(&self, storage_class: StorageClass) -> f64 {
+        match storage_class {
+            StorageClass::Standard => self.standard,
+            StorageClass::IA => self.infrequent_access,
+            StorageClass::Archive => self.archive,
+            StorageClass::ColdArchive => self.cold_archive,
+            StorageClass::DeepColdArchive => self.deep_cold_archive,
+        }
+    }
+}
+
+/// 按各存储类型的计费存储量估算每月存储费用，只包含存储费用，不含请求、流量和数据取回等费用
+///
+/// # 使用例子
+///
+/// ```rust
+/// use aliyun_oss_rust_sdk::entity::StorageClass;
+/// use aliyun_oss_rust_sdk::stat::{estimate_monthly_cost, BucketStat, PricingTable};
+/// let stat = BucketStat {
+///     standard_storage: 10 * 1024 * 1024 * 1024,
+///     ..BucketStat::default()
+/// };
+/// let pricing = PricingTable::new().with_price(StorageClass::Standard, 0.12);
+/// assert!((estimate_monthly_cost(&stat, pricing) - 1.2).abs() < 1e-9);
+/// ```
+pub fn estimate_monthly_cost(stat: &BucketStat, pricing: PricingTable) -> f64 {
+    [
+        StorageClass::Standard,
+        StorageClass::IA,
+        StorageClass::Archive,
+        StorageClass::ColdArchive,
+        StorageClass::DeepColdArchive,
+    ]
+    .into_iter()
+    .map(|storage_class| stat.storage_of(storage_class) as f64 / GB * pricing.price_of(storage_class))
+    .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::StorageClass;
+    use crate::stat::{estimate_monthly_cost, BucketStat, PricingTable};
+
+    #[test]
+    fn test_bucket_stat_from_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<BucketStat>
+  <Storage>1600</Storage>
+  <ObjectCount>230</ObjectCount>
+  <MultipartUploadCount>40</MultipartUploadCount>
+  <LastModifiedTime>1643341269</LastModifiedTime>
+  <StandardStorage>430</StandardStorage>
+  <StandardObjectCount>66</StandardObjectCount>
+  <InfrequentAccessStorage>2359296</InfrequentAccessStorage>
+  <InfrequentAccessRealStorage>360</InfrequentAccessRealStorage>
+  <InfrequentAccessObjectCount>54</InfrequentAccessObjectCount>
+  <ArchiveStorage>2949120</ArchiveStorage>
+  <ArchiveRealStorage>450</ArchiveRealStorage>
+  <ArchiveObjectCount>74</ArchiveObjectCount>
+  <ColdArchiveStorage>2359296</ColdArchiveStorage>
+  <ColdArchiveRealStorage>360</ColdArchiveRealStorage>
+  <ColdArchiveObjectCount>36</ColdArchiveObjectCount>
+</BucketStat>"#;
+        let stat = BucketStat::from_xml(xml).unwrap();
+        assert_eq!(stat.object_count, 230);
+        assert_eq!(stat.last_modified_time.map(|t| t.timestamp()), Some(1643341269));
+        assert_eq!(stat.storage_of(StorageClass::IA), 2359296);
+        assert_eq!(stat.cold_archive_object_count, 36);
+        assert_eq!(stat.deep_cold_archive_storage, 0);
+        assert!(BucketStat::from_xml("<Error></Error>").is_err());
+    }
+
+    #[test]
+    fn test_bucket_stat_serde_round_trip() {
+        let stat = BucketStat::from_xml("<BucketStat><Storage>1600</Storage><ObjectCount>230</ObjectCount>\
+<LastModifiedTime>1643341269</LastModifiedTime><StandardStorage>1600</StandardStorage></BucketStat>")
+        .unwrap();
+        let value = serde_json::to_value(&stat).unwrap();
+        assert_eq!(value, serde_json::json!({
+            "storage": 1600,
+            "object_count": 230,
+            "multipart_upload_count": 0,
+            "last_modified_time": "2022-01-28T03:41:09Z",
+            "standard_storage": 1600,
+            "standard_object_count": 0,
+            "infrequent_access_storage": 0,
+            "infrequent_access_object_count": 0,
+            "archive_storage": 0,
+            "archive_object_count": 0,
+            "cold_archive_storage": 0,
+            "cold_archive_object_count": 0,
+            "deep_cold_archive_storage": 0,
+            "deep_cold_archive_object_count": 0,
+            "raw": null,
+        }));
+        let decoded: BucketStat = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, stat);
+    }
+
+    #[test]
+    fn test_estimate_monthly_cost() {
+        let gb = 1024 * 1024 * 1024;
+        let stat = BucketStat {
+            standard_storage: 100 * gb,
+            infrequent_access_storage: 50 * gb,
+            archive_storage: 200 * gb,
+            ..BucketStat::default()
+        };
+        let pricing = PricingTable::new()
+            .with_price(StorageClass::Standard, 0.12)
+            .with_price(StorageClass::IA, 0.08)
+            .with_price(StorageClass::Archive, 0.033);
+        let cost = estimate_monthly_cost(&stat, pricing);
+        assert!((cost - (12.0 + 4.0 + 6.6)).abs() < 1e-9);
+        assert_eq!(estimate_monthly_cost(&stat, PricingTable::new()), 0.0);
+    }
+}
@@ -1,9 +1,41 @@
 use std::collections::HashMap;
+use md5::{Digest, Md5};
 use reqwest::header::DATE;
-use crate::auth::AuthAPI;
-use crate::debug;
+use crate::{date, debug};
 use crate::oss::{API, OSS, OSSInfo};
-use crate::request::{RequestBuilder, RequestType};
+use crate::request::{RequestBuilder, RequestType, Seconds};
+
+/// 批量签名URL的公共选项
+#[derive(Debug, Clone, Default)]
+pub struct SignUrlOptions {
+    /// 数据处理参数`x-oss-process`
+    pub process: Option<String>,
+    /// `response-content-disposition`模板，`{filename}`会替换为key的文件名部分
+    pub content_disposition: Option<String>,
+}
+
+impl SignUrlOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_process<S: AsRef<str>>(mut self, process: S) -> Self {
+        self.process = Some(process.as_ref().to_string());
+        self
+    }
+    pub fn with_content_disposition<S: AsRef<str>>(mut self, template: S) -> Self {
+        self.content_disposition = Some(template.as_ref().to_string());
+        self
+    }
+}
+
+/// 签名上传请求，客户端使用`url`上传时必须带上`required_headers`中的所有header，否则签名不匹配
+///
+/// `required_headers`的key都是小写，例如`content-type`、`content-md5`、`x-oss-meta-author`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresignedRequest {
+    pub url: String,
+    pub required_headers: HashMap<String, String>,
+}
 
 pub trait UrlApi: OSSInfo + API {
     /// 获取签名下载URL
@@ -46,61 +78,223 @@ pub trait UrlApi: OSSInfo + API {
     /// //使用postman测试上传即可，PS:要注意content-type要和build中的一致
     /// ```
     fn sign_upload_url<S: AsRef<str>>(&self, key: S, build: &RequestBuilder) -> String;
+
+    /// 获取签名上传请求，返回URL以及上传时必须携带的header
+    ///
+    /// Content-Type、Content-MD5以及所有`x-oss-`开头的header都参与签名，上传时必须原样带上
+    ///
+    /// # 使用例子
+    ///
+    /// ```
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// use aliyun_oss_rust_sdk::url::UrlApi;
+    /// let oss = OSS::new("my_key_id", "my_key_secret", "oss-cn-shanghai.aliyuncs.com", "my-bucket");
+    /// let build = RequestBuilder::new()
+    ///     .with_content_type("text/plain")
+    ///     .oss_header_put("x-oss-meta-author", "lake")
+    ///     .with_expire(60);
+    /// let request = oss.sign_upload_request("tmp.txt", &build);
+    /// assert_eq!(request.required_headers.get("content-type").unwrap(), "text/plain");
+    /// assert_eq!(request.required_headers.get("x-oss-meta-author").unwrap(), "lake");
+    /// ```
+    fn sign_upload_request<S: AsRef<str>>(&self, key: S, build: &RequestBuilder) -> PresignedRequest;
+
+    /// 批量获取签名下载URL，过期时间、域名等公共部分只计算一次
+    ///
+    /// `options`对所有key生效，`content_disposition`中的`{filename}`会替换为key的文件名部分
+    ///
+    /// # 使用例子
+    ///
+    /// ```
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// use aliyun_oss_rust_sdk::url::{SignUrlOptions, UrlApi};
+    /// let oss = OSS::new("my_key_id", "my_key_secret", "oss-cn-shanghai.aliyuncs.com", "my-bucket");
+    /// let build = RequestBuilder::new().with_expire(600);
+    /// let options = SignUrlOptions::new()
+    ///     .with_content_disposition("attachment;filename={filename}");
+    /// let urls = oss.sign_download_urls(&["a/1.txt", "b/2.txt"], &build, &options);
+    /// assert_eq!(urls.len(), 2);
+    /// ```
+    fn sign_download_urls<S: AsRef<str>>(
+        &self,
+        keys: &[S],
+        build: &RequestBuilder,
+        options: &SignUrlOptions,
+    ) -> Vec<String>;
     fn sign_url<S: AsRef<str>>(&self, key: S, build: &RequestBuilder) -> String;
+
+    /// 获取`expires`秒内可以直接打开的匿名下载链接，自动选择CDN或OSS签名
+    ///
+    /// - `prefer_cdn`为true且`build`配置了cdn和`cdn_auth_key`时，使用CDN的A型鉴权：
+    ///   `auth_key={timestamp}-0-0-{md5}`，`md5`为`{uri}-{timestamp}-0-0-{cdn_auth_key}`的MD5，
+    ///   `timestamp`取当前时间加`expires`，CDN会在它加上控制台配置的有效时长后拒绝访问，建议把控制台有效时长设置得尽量短
+    /// - `prefer_cdn`为true、配置了cdn但没有`cdn_auth_key`时，使用cdn域名加OSS签名，需要CDN回源时透传查询参数
+    /// - 其他情况使用OSS域名的签名链接(`Expires`、`OSSAccessKeyId`、`Signature`)，忽略`build`中的cdn
+    ///
+    /// # 使用例子
+    ///
+    /// ```
+    /// use aliyun_oss_rust_sdk::oss::OSS;
+    /// use aliyun_oss_rust_sdk::request::RequestBuilder;
+    /// use aliyun_oss_rust_sdk::url::UrlApi;
+    /// let oss = OSS::new("my_key_id", "my_key_secret", "oss-cn-shanghai.aliyuncs.com", "my-bucket");
+    /// let build = RequestBuilder::new()
+    ///     .with_cdn("https://cdn.mydomain.com")
+    ///     .with_cdn_auth_key("my_cdn_auth_key");
+    /// let url = oss.shareable_url("/a/1.png", 600, true, &build);
+    /// assert!(url.starts_with("https://cdn.mydomain.com/a/1.png?auth_key="));
+    /// let url = oss.shareable_url("/a/1.png", 600, false, &build);
+    /// assert!(url.starts_with("https://my-bucket.oss-cn-shanghai.aliyuncs.com/a/1.png?Expires="));
+    /// ```
+    fn shareable_url<S: AsRef<str>>(&self, key: S, expires: Seconds, prefer_cdn: bool, build: &RequestBuilder) -> String;
 }
 
 impl UrlApi for OSS {
     fn sign_download_url<S: AsRef<str>>(&self, key: S, build: &RequestBuilder) -> String {
-        let sign = self.sign_url(key.as_ref(), build);
-        if let Some(cdn) = &build.cdn {
-            let download_url = format!("{}{}", cdn, sign);
-            debug!("download_url: {}", download_url);
-            download_url
-        } else {
-            let schema = if build.https {
-                "https://"
-            } else {
-                "http://"
-            };
-            let download_url = format!("{}{}.{}{}", schema, self.bucket(), self.endpoint(), sign);
-            debug!("download_url: {}", download_url);
-            download_url
+        let build = self.presign_build(build);
+        let sign = self.sign_url(key.as_ref(), &build);
+        let download_url = format!("{}{}", self.url_base(&build), sign);
+        debug!("download_url: {}", download_url);
+        download_url
+    }
+
+    fn sign_download_urls<S: AsRef<str>>(
+        &self,
+        keys: &[S],
+        build: &RequestBuilder,
+        options: &SignUrlOptions,
+    ) -> Vec<String> {
+        let mut build = self.presign_build(build);
+        let expires = self.expires(&build);
+        build.headers.insert(DATE.to_string(), expires.clone());
+        if let Some(process) = &options.process {
+            build.parameters.insert("x-oss-process".to_string(), process.to_string());
         }
+        let base = self.url_base(&build);
+        let mut urls = Vec::with_capacity(keys.len());
+        for key in keys {
+            let key = key.as_ref();
+            if let Some(template) = &options.content_disposition {
+                let file_name = key.rsplit('/').next().unwrap_or(key);
+                build.parameters.insert(
+                    "response-content-disposition".to_string(),
+                    template.replace("{filename}", file_name),
+                );
+            }
+            let mut url = String::with_capacity(base.len() + key.len() + 128);
+            url.push_str(&base);
+            url.push_str(&self.presign_path(key, &build, &expires));
+            urls.push(url);
+        }
+        debug!("download_urls: {:?}", urls);
+        urls
     }
 
     fn sign_upload_url<S: AsRef<str>>(&self, key: S, build: &RequestBuilder) -> String {
-        let mut build = build.clone();
+        let mut build = self.presign_build(build);
         build.method = RequestType::Put;
         let sign = self.sign_url(key.as_ref(), &build);
+        let upload_url = format!("{}{}", self.url_base(&build), sign);
+        debug!("upload_url: {}", upload_url);
+        upload_url
+    }
+
+    fn sign_upload_request<S: AsRef<str>>(&self, key: S, build: &RequestBuilder) -> PresignedRequest {
+        let build = self.presign_build(build);
+        let url = self.sign_upload_url(key, &build);
+        let mut required_headers = build
+            .oss_headers
+            .iter()
+            .map(|(k, v)| (k.to_lowercase(), v.to_string()))
+            .collect::<HashMap<_, _>>();
+        if let Some(content_type) = &build.content_type {
+            required_headers.insert("content-type".to_string(), content_type.to_string());
+        }
+        if let Some(content_md5) = &build.content_md5 {
+            required_headers.insert("content-md5".to_string(), content_md5.to_string());
+        }
+        PresignedRequest {
+            url,
+            required_headers,
+        }
+    }
+
+    fn sign_url<S: AsRef<str>>(&self, key: S, build: &RequestBuilder) -> String {
+        let mut build = self.presign_build(build);
+        let expires = self.expires(&build);
+        build.headers.insert(DATE.to_string(), expires.clone());
+        self.presign_path(key, &build, &expires)
+    }
+
+    fn shareable_url<S: AsRef<str>>(&self, key: S, expires: Seconds, prefer_cdn: bool, build: &RequestBuilder) -> String {
+        let mut build = self.presign_build(build).with_expire(expires);
+        if !prefer_cdn {
+            build.cdn = None;
+        }
+        let url = match (&build.cdn, &build.cdn_auth_key) {
+            (Some(cdn), Some(auth_key)) => {
+                let timestamp = chrono::Utc::now().timestamp() + expires;
+                format!("{}{}", cdn, self.cdn_sign_path(key, auth_key, timestamp))
+            }
+            _ => self.sign_download_url(key, &build),
+        };
+        debug!("shareable_url: {}", url);
+        url
+    }
+}
+
+impl OSS {
+    /// 签名URL使用的RequestBuilder，合并默认值，配置了[crate::config::OssConfig::signature_version2]时加上签名版本参数
+    fn presign_build(&self, build: &RequestBuilder) -> RequestBuilder {
+        let build = self.merge_default_build(build.clone());
+        if self.config().signature_version2 && !build.parameters.contains_key("x-oss-signature-version") {
+            build.oss_signature_version2()
+        } else {
+            build
+        }
+    }
+
+    /// 签名URL的过期时间戳
+    fn expires(&self, build: &RequestBuilder) -> String {
+        date::expires_epoch(chrono::Utc::now() + chrono::Duration::seconds(build.expire.unwrap_or(self.config().expire))).to_string()
+    }
+
+    /// 签名URL的域名部分，使用cdn时为cdn域名，否则见[OSS::bucket_url_base]
+    fn url_base(&self, build: &RequestBuilder) -> String {
         if let Some(cdn) = &build.cdn {
-            let download_url = format!("{}{}", cdn, sign);
-            debug!("upload_url: {}", download_url);
-            download_url
+            cdn.to_string()
         } else {
-            let schema = if build.https {
-                "https://"
-            } else {
-                "http://"
-            };
-            let download_url = format!("{}{}.{}{}", schema, self.bucket(), self.endpoint(), sign);
-            debug!("upload_url: {}", download_url);
-            download_url
+            let bucket = build.bucket.as_deref().unwrap_or(self.bucket_str());
+            self.bucket_url_base(bucket, build, build.https.unwrap_or(self.config().https))
         }
     }
 
-    fn sign_url<S: AsRef<str>>(&self, key: S, build: &RequestBuilder) -> String {
-        let mut build = build.clone();
+    /// 生成CDN A型鉴权URL的路径和`auth_key`参数，rand和uid固定为0
+    fn cdn_sign_path<S: AsRef<str>>(&self, key: S, auth_key: &str, timestamp: i64) -> String {
+        let uri = self.key_urlencode(self.format_key(key));
+        let hash = Md5::digest(format!("{}-{}-0-0-{}", uri, timestamp, auth_key));
+        format!("{}?auth_key={}-0-0-{:x}", uri, timestamp, hash)
+    }
+
+    /// 生成签名URL的路径和参数部分，`build`的Date头需要已经设置为`expires`
+    fn presign_path<S: AsRef<str>>(&self, key: S, build: &RequestBuilder, expires: &str) -> String {
         let key = self.format_key(key);
-        let expiration = chrono::Local::now() + chrono::Duration::seconds(build.expire);
-        build.headers.insert(DATE.to_string(), expiration.timestamp().to_string());
-        let signature = self.sign(
+        let credentials = self.credentials();
+        let mut build = build.clone();
+        if let Some(security_token) = &credentials.security_token {
+            build.parameters.insert("security-token".to_string(), security_token.to_string());
+        }
+        let signature = self.sign_with(
+            &credentials,
             key.as_str(),
             &build,
         );
         debug!("signature: {}", signature);
         let mut query_parameters = HashMap::new();
-        query_parameters.insert("Expires".to_string(), expiration.timestamp().to_string());
-        query_parameters.insert("OSSAccessKeyId".to_string(), self.key_id().to_string());
+        query_parameters.insert("Expires".to_string(), expires.to_string());
+        query_parameters.insert("OSSAccessKeyId".to_string(), credentials.key_id.to_string());
         query_parameters.insert("Signature".to_string(), urlencoding::encode(&signature).into_owned());
         build.parameters.iter().for_each(|(k, v)| {
             query_parameters.insert(k.to_string(), urlencoding::encode(v).into_owned());
@@ -123,9 +317,12 @@ impl UrlApi for OSS {
 
 #[cfg(test)]
 mod tests {
+    use reqwest::header::DATE;
+    use crate::config::{Credentials, OssConfig};
     use crate::oss::OSS;
     use crate::request::RequestBuilder;
-    use crate::url::UrlApi;
+    use crate::url::{SignUrlOptions, UrlApi};
+    use std::collections::HashMap;
 
     #[inline]
     fn init_log() {
@@ -164,4 +361,135 @@ mod tests {
             &build,
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn presign_path_test() {
+        let oss = OSS::new("nz2pc56s936**9l", "OtxrzxIsfpFjA7SwPzILwy8Bw21TLhquhboDYROV", "oss-cn-hangzhou.aliyuncs.com", "oss-example");
+        let mut build = RequestBuilder::new();
+        build.headers.insert(DATE.to_string(), "1141889120".to_string());
+        assert_eq!(
+            oss.presign_path("oss-api.pdf", &build, "1141889120"),
+            "/oss-api.pdf?Expires=1141889120&OSSAccessKeyId=nz2pc56s936**9l&Signature=EwaNTn1erJGkimiJ9WmXgwnANLc%3D"
+        );
+    }
+
+    #[test]
+    fn cdn_sign_path_test() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "my-bucket");
+        assert_eq!(
+            oss.cdn_sign_path("video/standard/1K.html", "aliyuncdnexp1234", 1444435200),
+            "/video/standard/1K.html?auth_key=1444435200-0-0-80cd3862d699b7118eed99103f2a3a4f"
+        );
+    }
+
+    #[test]
+    fn shareable_url_test() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "my-bucket");
+        let build = RequestBuilder::new().with_cdn("https://cdn.example.com");
+        let url = oss.shareable_url("/a.png", 600, true, &build);
+        assert!(url.starts_with("https://cdn.example.com/a.png?Expires="));
+        let url = oss.shareable_url("/a.png", 600, false, &build);
+        assert!(url.starts_with("https://my-bucket.oss-cn-hangzhou.aliyuncs.com/a.png?Expires="));
+        let url = oss.shareable_url("/a.png", 600, true, &build.with_cdn_auth_key("secret"));
+        let timestamp = url.split("auth_key=").nth(1).unwrap().split('-').next().unwrap();
+        let expected = oss.cdn_sign_path("/a.png", "secret", timestamp.parse().unwrap());
+        assert_eq!(url, format!("https://cdn.example.com{}", expected));
+        assert!(timestamp.parse::<i64>().unwrap() >= chrono::Utc::now().timestamp() + 590);
+    }
+
+    #[test]
+    fn sign_download_urls_test() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-shanghai.aliyuncs.com", "my-bucket");
+        let build = RequestBuilder::new().with_expire(600);
+        let options = SignUrlOptions::new()
+            .with_process("image/resize,w_100")
+            .with_content_disposition("attachment;filename={filename}");
+        let keys = ["a/1.png", "2.png"];
+        let urls = oss.sign_download_urls(&keys, &build, &options);
+        assert_eq!(urls.len(), 2);
+        for (key, url) in keys.iter().zip(urls.iter()) {
+            let expires = url.split("Expires=").nth(1).unwrap().split('&').next().unwrap();
+            let file_name = key.rsplit('/').next().unwrap();
+            let mut expected_build = build
+                .clone()
+                .with_process("image/resize,w_100")
+                .parameters_put("response-content-disposition", &format!("attachment;filename={}", file_name));
+            expected_build.headers.insert(DATE.to_string(), expires.to_string());
+            let expected = format!(
+                "https://my-bucket.oss-cn-shanghai.aliyuncs.com{}",
+                oss.presign_path(key, &expected_build, expires)
+            );
+            assert_eq!(url, &expected);
+        }
+    }
+
+    #[test]
+    fn sign_upload_request_test() {
+        let oss = OSS::new("key_id", "key_secret", "oss-cn-shanghai.aliyuncs.com", "my-bucket");
+        let build = RequestBuilder::new()
+            .with_content_type("image/png")
+            .with_content_md5("eB5eJF1ptWaXm4bijSPyxw==")
+            .oss_header_put("X-OSS-Meta-Author", "lake");
+        let request = oss.sign_upload_request("a.png", &build);
+        assert!(request.url.starts_with("https://my-bucket.oss-cn-shanghai.aliyuncs.com/a.png?Expires="));
+        assert_eq!(
+            request.required_headers,
+            HashMap::from([
+                ("content-type".to_string(), "image/png".to_string()),
+                ("content-md5".to_string(), "eB5eJF1ptWaXm4bijSPyxw==".to_string()),
+                ("x-oss-meta-author".to_string(), "lake".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn sign_download_url_host_test() {
+        let cases = [
+            (OssConfig::new("oss-cn-hangzhou.aliyuncs.com", "my-bucket"), "https://my-bucket.oss-cn-hangzhou.aliyuncs.com/a b.txt"),
+            (OssConfig::new("oss-accelerate.aliyuncs.com", "my-bucket"), "https://my-bucket.oss-accelerate.aliyuncs.com/a b.txt"),
+            (
+                OssConfig::new("oss-cn-hangzhou.aliyuncs.com", "my-bucket").with_path_style(true),
+                "https://oss-cn-hangzhou.aliyuncs.com/my-bucket/a b.txt",
+            ),
+            (
+                OssConfig::new("http://static.example.com", "my-bucket").with_cname(true),
+                "http://static.example.com/a b.txt",
+            ),
+        ];
+        for (config, host) in cases {
+            let oss = OSS::with_config(Credentials::new("key_id", "key_secret"), config);
+            let build = RequestBuilder::new().with_expire(600);
+            let url = oss.sign_download_url("/a b.txt", &build);
+            let base = host.trim_end_matches("/a b.txt");
+            assert!(url.starts_with(&format!("{}/a%20b.txt?Expires=", base)), "{}", url);
+            // 不同的域名形式签名都使用/my-bucket/a b.txt作为资源
+            let expires = url.split("Expires=").nth(1).unwrap().split('&').next().unwrap();
+            let mut expected_build = build.clone();
+            expected_build.headers.insert(DATE.to_string(), expires.to_string());
+            let reference = OSS::new("key_id", "key_secret", "oss-cn-hangzhou.aliyuncs.com", "my-bucket");
+            assert_eq!(url, format!("{}{}", base, reference.presign_path("/a b.txt", &expected_build, expires)));
+        }
+    }
+
+    #[test]
+    fn sign_download_url_config_test() {
+        let config = OssConfig::new("oss-cn-hangzhou.aliyuncs.com", "my-bucket")
+            .with_https(false)
+            .with_expire(300)
+            .with_signature_version2(true);
+        let oss = OSS::with_config(Credentials::new("key_id", "key_secret"), config);
+        let url = oss.sign_download_url("/a.txt", &RequestBuilder::new());
+        assert!(url.starts_with("http://my-bucket.oss-cn-hangzhou.aliyuncs.com/a.txt?Expires="), "{}", url);
+        assert!(url.contains("x-oss-signature-version=OSS2"));
+        let expires: i64 = url.split("Expires=").nth(1).unwrap().split('&').next().unwrap().parse().unwrap();
+        assert!(expires >= chrono::Utc::now().timestamp() + 290);
+        // 单次请求的设置优先于配置
+        let url = oss.sign_download_url("/a.txt", &RequestBuilder::new().with_scheme(true));
+        assert!(url.starts_with("https://"));
+        // 分片上传等后续请求同样使用配置的协议
+        assert_eq!(
+            oss.format_url("my-bucket", "/a.txt", &RequestBuilder::new().with_expire(10).follow_up()),
+            "http://my-bucket.oss-cn-hangzhou.aliyuncs.com/a.txt"
+        );
+    }
+}
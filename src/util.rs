@@ -1,6 +1,12 @@
 use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
 use base64::engine::general_purpose;
 use base64::{Engine};
+use md5::{Digest, Md5};
+use sha2::Sha256;
+use reqwest::header::HeaderMap;
+use crate::error::OssError;
+use crate::request::RequestBuilder;
 
 pub fn read_file<S: AsRef<str>>(file_name: S) -> Result<Vec<u8>, std::io::Error> {
     let file = std::fs::File::open(file_name.as_ref())?;
@@ -16,3 +22,273 @@ pub fn base64_encode<S>(content: S) -> String
 {
     general_purpose::STANDARD.encode(content)
 }
+
+/// URL安全的base64编码(不带末尾的`=`)，用于数据处理参数中的水印文字、字体、图片等
+pub fn base64url_encode<S>(content: S) -> String
+    where
+        S: AsRef<[u8]>,
+{
+    general_purpose::URL_SAFE_NO_PAD.encode(content)
+}
+
+/// HMAC-SHA1，用于V1签名和PostObject的policy签名
+///
+/// 开启`ring-hmac`特性时使用ring，否则使用RustCrypto的hmac/sha1(`rustcrypto-hmac`，默认开启)
+#[cfg(feature = "ring-hmac")]
+pub(crate) fn hmac_sha1(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, key);
+    ring::hmac::sign(&key, data).as_ref().to_vec()
+}
+
+#[cfg(all(feature = "rustcrypto-hmac", not(feature = "ring-hmac")))]
+pub(crate) fn hmac_sha1(key: &[u8], data: &[u8]) -> Vec<u8> {
+    use hmac::{Hmac, Mac};
+    // HMAC接受任意长度的key，new_from_slice不会失败
+    let mut hasher: Hmac<sha1::Sha1> = Hmac::new_from_slice(key).expect("hmac key");
+    hasher.update(data);
+    hasher.finalize().into_bytes().to_vec()
+}
+
+#[cfg(not(any(feature = "rustcrypto-hmac", feature = "ring-hmac")))]
+compile_error!("one of the features `rustcrypto-hmac` or `ring-hmac` must be enabled");
+
+/// 需要校验时取出响应中的Content-MD5
+pub(crate) fn content_md5_header(headers: &HeaderMap, verify: bool) -> Option<String> {
+    if !verify {
+        return None;
+    }
+    headers
+        .get("content-md5")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+}
+
+/// 校验下载内容的MD5，`expected`为响应中base64编码的Content-MD5，没有时不校验
+pub(crate) fn verify_content_md5(expected: Option<&str>, body: &[u8]) -> Result<(), OssError> {
+    if let Some(expected) = expected {
+        let actual = base64_encode(Md5::digest(body));
+        if actual != expected {
+            return Err(OssError::ContentMd5Mismatch {
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// 下载时使用的临时文件路径，在原文件名后加`.part`
+pub(crate) fn part_file_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// CRC-64/ECMA-182(OSS的`x-oss-hash-crc64ecma`)的反转多项式
+const CRC64_ECMA_POLY: u64 = 0xC96C_5795_D787_0F42;
+
+const CRC64_TABLE: [u64; 256] = {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ CRC64_ECMA_POLY } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// 一次遍历同时计算base64编码的MD5(Content-MD5)和CRC64
+pub(crate) fn md5_and_crc64(body: &[u8]) -> (String, u64) {
+    let mut md5 = Md5::new();
+    let mut crc = !0u64;
+    for chunk in body.chunks(64 * 1024) {
+        md5.update(chunk);
+        for &b in chunk {
+            crc = CRC64_TABLE[((crc ^ b as u64) & 0xff) as usize] ^ (crc >> 8);
+        }
+    }
+    (base64_encode(md5.finalize()), !crc)
+}
+
+/// 计算CRC-64/ECMA-182
+pub(crate) fn crc64(body: &[u8]) -> u64 {
+    !body
+        .iter()
+        .fold(!0u64, |crc, &b| CRC64_TABLE[((crc ^ b as u64) & 0xff) as usize] ^ (crc >> 8))
+}
+
+fn gf2_matrix_times(mat: &[u64; 64], mut vec: u64) -> u64 {
+    let mut sum = 0;
+    let mut i = 0;
+    while vec != 0 {
+        if vec & 1 == 1 {
+            sum ^= mat[i];
+        }
+        vec >>= 1;
+        i += 1;
+    }
+    sum
+}
+
+fn gf2_matrix_square(square: &mut [u64; 64], mat: &[u64; 64]) {
+    for n in 0..64 {
+        square[n] = gf2_matrix_times(mat, mat[n]);
+    }
+}
+
+/// 由两段数据各自的CRC64计算拼接后的CRC64，`len2`是第二段数据的长度，算法同zlib的crc32_combine
+pub(crate) fn crc64_combine(mut crc1: u64, crc2: u64, mut len2: u64) -> u64 {
+    if len2 == 0 {
+        return crc1;
+    }
+    let mut even = [0u64; 64];
+    let mut odd = [0u64; 64];
+    // odd是移位一个比特的算子
+    odd[0] = CRC64_ECMA_POLY;
+    let mut row = 1u64;
+    for item in odd.iter_mut().skip(1) {
+        *item = row;
+        row <<= 1;
+    }
+    gf2_matrix_square(&mut even, &odd);
+    gf2_matrix_square(&mut odd, &even);
+    loop {
+        gf2_matrix_square(&mut even, &odd);
+        if len2 & 1 == 1 {
+            crc1 = gf2_matrix_times(&even, crc1);
+        }
+        len2 >>= 1;
+        if len2 == 0 {
+            break;
+        }
+        gf2_matrix_square(&mut odd, &even);
+        if len2 & 1 == 1 {
+            crc1 = gf2_matrix_times(&odd, crc1);
+        }
+        len2 >>= 1;
+        if len2 == 0 {
+            break;
+        }
+    }
+    crc1 ^ crc2
+}
+
+/// 开启了[RequestBuilder::with_full_integrity]时设置Content-MD5，返回需要校验的CRC64
+pub(crate) fn apply_full_integrity(build: &mut RequestBuilder, body: &[u8]) -> Option<u64> {
+    if !build.full_integrity {
+        return None;
+    }
+    let (content_md5, crc64) = md5_and_crc64(body);
+    build.content_md5 = Some(content_md5);
+    Some(crc64)
+}
+
+/// OSS返回对象SHA-256(十六进制)的header
+const SHA256_HEADER: &str = "x-oss-hash-sha256";
+
+fn sha256_header(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(SHA256_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+}
+
+fn check_sha256(expected: String, actual: Option<String>) -> Result<(), OssError> {
+    if !actual.as_deref().is_some_and(|actual| actual.eq_ignore_ascii_case(&expected)) {
+        return Err(OssError::Sha256Mismatch { expected, actual });
+    }
+    Ok(())
+}
+
+/// 开启了[RequestBuilder::with_verify_sha256]时计算上传内容的SHA-256(十六进制)
+pub(crate) fn upload_sha256(build: &RequestBuilder, body: &[u8]) -> Option<String> {
+    build.verify_sha256.then(|| {
+        Sha256::digest(body)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    })
+}
+
+/// 校验上传响应中的`x-oss-hash-sha256`，`expected`为None时不校验
+pub(crate) fn verify_sha256(expected: Option<String>, headers: &HeaderMap) -> Result<(), OssError> {
+    match expected {
+        Some(expected) => check_sha256(expected, sha256_header(headers)),
+        None => Ok(()),
+    }
+}
+
+/// 下载时边接收边计算SHA-256，结束时和响应中的`x-oss-hash-sha256`比较
+pub(crate) struct Sha256Check {
+    hasher: Sha256,
+    actual: Option<String>,
+}
+
+impl Sha256Check {
+    /// 没有开启[RequestBuilder::with_verify_sha256]时返回None
+    pub(crate) fn new(headers: &HeaderMap, verify: bool) -> Option<Self> {
+        verify.then(|| Self {
+            hasher: Sha256::new(),
+            actual: sha256_header(headers),
+        })
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    pub(crate) fn finish(self) -> Result<(), OssError> {
+        let expected = self.hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+        check_sha256(expected, self.actual)
+    }
+}
+
+/// 校验上传响应中的`x-oss-hash-crc64ecma`，`expected`为None时不校验
+pub(crate) fn verify_crc64(expected: Option<u64>, headers: &HeaderMap) -> Result<(), OssError> {
+    if let Some(expected) = expected {
+        let actual = headers
+            .get("x-oss-hash-crc64ecma")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        if actual.as_deref().and_then(|v| v.parse::<u64>().ok()) != Some(expected) {
+            return Err(OssError::Crc64Mismatch { expected, actual });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::{crc64, crc64_combine, hmac_sha1, md5_and_crc64};
+
+    #[test]
+    fn test_hmac_sha1() {
+        // RFC 2202 test case 2，两种实现都必须得到相同的结果
+        let digest = hmac_sha1(b"Jefe", b"what do ya want for nothing?");
+        let hex = digest.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+        assert_eq!(hex, "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79");
+    }
+
+    #[test]
+    fn test_md5_and_crc64() {
+        // CRC-64/XZ(ECMA-182)的标准校验值
+        assert_eq!(md5_and_crc64(b"123456789").1, 0x995D_C9BB_DF19_39FA);
+        assert_eq!(md5_and_crc64(b"hello").0, "XUFAKrxLKna5cZ2REBfFkg==");
+        assert_eq!(md5_and_crc64(b""), ("1B2M2Y8AsgTpgAmY7PhCfg==".to_string(), 0));
+    }
+
+    #[test]
+    fn test_crc64_combine() {
+        assert_eq!(crc64(b"123456789"), 0x995D_C9BB_DF19_39FA);
+        let (a, b) = b"123456789".split_at(4);
+        assert_eq!(crc64_combine(crc64(a), crc64(b), b.len() as u64), 0x995D_C9BB_DF19_39FA);
+        assert_eq!(crc64_combine(0, crc64(b"hello"), 5), crc64(b"hello"));
+        assert_eq!(crc64_combine(crc64(b"hello"), 0, 0), crc64(b"hello"));
+    }
+}
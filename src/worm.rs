@@ -0,0 +1,141 @@
+use std::fmt::{Display, Formatter};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use crate::date;
+use crate::error::OssError;
+use crate::xml::element_text;
+
+/// 合规保留策略(WORM)的状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WormState {
+    /// 策略已创建但未锁定，24小时内可以删除，过期未锁定会自动失效
+    InProgress,
+    /// 策略已锁定，保留期内对象不能被删除和覆盖
+    Locked,
+    Other(String),
+}
+
+impl From<&str> for WormState {
+    fn from(value: &str) -> Self {
+        match value {
+            "InProgress" => WormState::InProgress,
+            "Locked" => WormState::Locked,
+            other => WormState::Other(other.to_string()),
+        }
+    }
+}
+
+impl Display for WormState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WormState::InProgress => write!(f, "InProgress"),
+            WormState::Locked => write!(f, "Locked"),
+            WormState::Other(state) => write!(f, "{}", state),
+        }
+    }
+}
+
+/// bucket的合规保留策略(GetBucketWorm)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WormConfiguration {
+    pub worm_id: String,
+    pub state: WormState,
+    /// 对象的保留天数，从对象最后修改时间开始计算
+    pub retention_period_in_days: u32,
+    /// 策略创建时间，响应中没有或格式不对时为None
+    pub creation_date: Option<DateTime<Utc>>,
+    /// 原始响应body，只有设置了[with_raw_response](crate::request::RequestBuilder::with_raw_response)时才有
+    pub raw: Option<String>,
+}
+
+impl WormConfiguration {
+    pub(crate) fn from_xml(xml: &str) -> Result<Self, OssError> {
+        let invalid = || OssError::Err(format!("invalid worm configuration xml: {}", xml));
+        Ok(Self {
+            worm_id: element_text(xml, "WormId").ok_or_else(invalid)?,
+            state: WormState::from(element_text(xml, "State").ok_or_else(invalid)?.as_str()),
+            retention_period_in_days: element_text(xml, "RetentionPeriodInDays")
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(invalid)?,
+            creation_date: element_text(xml, "CreationDate").and_then(|v| date::parse_iso8601(&v)),
+            raw: None,
+        })
+    }
+}
+
+/// 对象的保留状态，由bucket的WORM策略和对象的最后修改时间计算得到
+///
+/// OSS的WORM策略是bucket级别的，没有单独的对象级保留设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionState {
+    pub mode: WormState,
+    /// 保留到期时间，最后修改时间加上保留天数
+    pub retain_until: DateTime<Utc>,
+}
+
+impl RetentionState {
+    pub(crate) fn new(worm: &WormConfiguration, last_modified: DateTime<Utc>) -> Self {
+        Self {
+            mode: worm.state.clone(),
+            retain_until: last_modified + Duration::days(worm.retention_period_in_days as i64),
+        }
+    }
+
+    /// 策略已锁定且还在保留期内，对象不能被删除或覆盖
+    pub fn is_protected(&self) -> bool {
+        self.mode == WormState::Locked && Utc::now() < self.retain_until
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use crate::worm::{RetentionState, WormConfiguration, WormState};
+
+    #[test]
+    fn test_worm_configuration_from_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<WormConfiguration>
+  <WormId>1666E2CFB2B3418****</WormId>
+  <State>Locked</State>
+  <RetentionPeriodInDays>1</RetentionPeriodInDays>
+  <CreationDate>2020-10-15T15:50:32</CreationDate>
+</WormConfiguration>"#;
+        let worm = WormConfiguration::from_xml(xml).unwrap();
+        assert_eq!(worm.worm_id, "1666E2CFB2B3418****");
+        assert_eq!(worm.state, WormState::Locked);
+        assert_eq!(worm.retention_period_in_days, 1);
+        assert_eq!(worm.creation_date, Some(Utc.with_ymd_and_hms(2020, 10, 15, 15, 50, 32).unwrap()));
+        assert!(WormConfiguration::from_xml("<Error></Error>").is_err());
+
+        let last_modified = Utc.with_ymd_and_hms(2020, 10, 16, 0, 0, 0).unwrap();
+        let retention = RetentionState::new(&worm, last_modified);
+        assert_eq!(retention.retain_until, Utc.with_ymd_and_hms(2020, 10, 17, 0, 0, 0).unwrap());
+        assert!(!retention.is_protected());
+    }
+
+    #[test]
+    fn test_worm_serde_round_trip() {
+        let worm = WormConfiguration::from_xml("<WormConfiguration><WormId>1666E2CFB2B3418</WormId><State>InProgress</State>\
+<RetentionPeriodInDays>7</RetentionPeriodInDays><CreationDate>2020-10-15T15:50:32</CreationDate></WormConfiguration>")
+        .unwrap();
+        let value = serde_json::to_value(&worm).unwrap();
+        assert_eq!(value, serde_json::json!({
+            "worm_id": "1666E2CFB2B3418",
+            "state": "InProgress",
+            "retention_period_in_days": 7,
+            "creation_date": "2020-10-15T15:50:32Z",
+            "raw": null,
+        }));
+        let decoded: WormConfiguration = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, worm);
+
+        let retention = RetentionState::new(&worm, Utc.with_ymd_and_hms(2020, 10, 16, 0, 0, 0).unwrap());
+        let value = serde_json::to_value(&retention).unwrap();
+        assert_eq!(value, serde_json::json!({"mode": "InProgress", "retain_until": "2020-10-23T00:00:00Z"}));
+        let decoded: RetentionState = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, retention);
+        let other: WormState = serde_json::from_value(serde_json::json!({"Other": "Expired"})).unwrap();
+        assert_eq!(other, WormState::Other("Expired".to_string()));
+    }
+}
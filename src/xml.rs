@@ -0,0 +1,161 @@
+/// 转义xml文本中的特殊字符
+pub(crate) fn escape<S: AsRef<str>>(text: S) -> String {
+    let text = text.as_ref();
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 还原[escape]转义过的xml文本
+pub(crate) fn unescape<S: AsRef<str>>(text: S) -> String {
+    text.as_ref()
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 极简的xml生成器，用于拼接各种请求的xml body
+///
+/// ```ignore
+/// let mut xml = XmlWriter::new();
+/// xml.start("TransferAccelerationConfiguration")
+///     .element("Enabled", "true")
+///     .end();
+/// assert_eq!(
+///     xml.finish(),
+///     "<TransferAccelerationConfiguration><Enabled>true</Enabled></TransferAccelerationConfiguration>"
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub(crate) struct XmlWriter {
+    buf: String,
+    stack: Vec<String>,
+}
+
+impl XmlWriter {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn start(&mut self, tag: &str) -> &mut Self {
+        self.buf.push('<');
+        self.buf.push_str(tag);
+        self.buf.push('>');
+        self.stack.push(tag.to_string());
+        self
+    }
+
+    pub(crate) fn end(&mut self) -> &mut Self {
+        if let Some(tag) = self.stack.pop() {
+            self.buf.push_str("</");
+            self.buf.push_str(&tag);
+            self.buf.push('>');
+        }
+        self
+    }
+
+    /// 写入`<tag>text</tag>`，text会被转义
+    pub(crate) fn element<S: AsRef<str>>(&mut self, tag: &str, text: S) -> &mut Self {
+        self.start(tag);
+        self.buf.push_str(&escape(text));
+        self.end()
+    }
+
+    /// 关闭所有未关闭的元素并返回xml
+    pub(crate) fn finish(mut self) -> String {
+        while !self.stack.is_empty() {
+            self.end();
+        }
+        self.buf
+    }
+}
+
+/// 获取xml中第一个`<tag>`元素的文本内容(已还原转义)
+pub(crate) fn element_text<S: AsRef<str>>(xml: S, tag: &str) -> Option<String> {
+    let xml = xml.as_ref();
+    let start_tag = format!("<{}>", tag);
+    let end_tag = format!("</{}>", tag);
+    let start = xml.find(&start_tag)? + start_tag.len();
+    let end = xml[start..].find(&end_tag)? + start;
+    Some(unescape(&xml[start..end]))
+}
+
+/// 获取xml中所有`<tag>`元素的原始内容(未还原转义)，用于解析列表
+pub(crate) fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let start_tag = format!("<{}>", tag);
+    let end_tag = format!("</{}>", tag);
+    let mut result = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&start_tag) {
+        let content = &rest[start + start_tag.len()..];
+        match content.find(&end_tag) {
+            Some(end) => {
+                result.push(&content[..end]);
+                rest = &content[end + end_tag.len()..];
+            }
+            None => break,
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::xml::{element_text, elements, escape, unescape, XmlWriter};
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a<b>&\"c'"), "a&lt;b&gt;&amp;&quot;c&apos;");
+        assert_eq!(escape("中文/key.txt"), "中文/key.txt");
+        // 已经转义过的内容要再次转义，不能当成实体直接透传
+        assert_eq!(escape("&amp;"), "&amp;amp;");
+    }
+
+    #[test]
+    fn test_unescape_round_trip() {
+        for text in ["a<b>&\"c'", "&amp;", "&lt;tag&gt;", "plain"] {
+            assert_eq!(unescape(escape(text)), text);
+        }
+    }
+
+    #[test]
+    fn test_writer() {
+        let mut xml = XmlWriter::new();
+        xml.start("Delete")
+            .element("Quiet", "true")
+            .start("Object")
+            .element("Key", "dir/<a&b>.txt");
+        assert_eq!(
+            xml.finish(),
+            r#"<Delete><Quiet>true</Quiet><Object><Key>dir/&lt;a&amp;b&gt;.txt</Key></Object></Delete>"#
+        );
+    }
+
+    #[test]
+    fn test_element_text() {
+        let xml = "<Error><Code>NoSuchKey</Code><Key>a&amp;b.txt</Key></Error>";
+        assert_eq!(element_text(xml, "Code").as_deref(), Some("NoSuchKey"));
+        assert_eq!(element_text(xml, "Key").as_deref(), Some("a&b.txt"));
+        assert_eq!(element_text(xml, "Message"), None);
+    }
+
+    #[test]
+    fn test_elements() {
+        let xml = "<R><Contents><Key>a</Key></Contents><Contents><Key>b&amp;c</Key></Contents><Contents></R>";
+        let contents = elements(xml, "Contents");
+        assert_eq!(contents, vec!["<Key>a</Key>", "<Key>b&amp;c</Key>"]);
+        assert_eq!(element_text(contents[1], "Key").as_deref(), Some("b&c"));
+        assert!(elements(xml, "CommonPrefixes").is_empty());
+    }
+}